@@ -0,0 +1,229 @@
+//! OpenAI-compatible `/v1/chat/completions` endpoint.
+//!
+//! Accepts the standard OpenAI chat-completions request body and returns
+//! responses in OpenAI's exact schema (both the non-streaming JSON and the
+//! `data: {...}` / `data: [DONE]` SSE form), while still routing through the
+//! elizaOS runtime so character/system prompt and tools apply. This lets any
+//! OpenAI SDK, LangChain client, or TUI point its base URL at this worker
+//! unchanged.
+
+use axum::{
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use chrono::Utc;
+use elizaos::types::{Content, Memory, UUID};
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+
+use crate::providers::{build_client, ProviderConfig};
+use crate::{get_character, get_runtime, tools, ChatMessage};
+
+#[derive(Debug, Deserialize)]
+pub struct OpenAiChatRequest {
+    pub model: String,
+    pub messages: Vec<OpenAiRequestMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenAiRequestMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiChatCompletion {
+    pub id: String,
+    pub object: &'static str,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<OpenAiChoice>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiChoice {
+    pub index: u32,
+    pub message: OpenAiResponseMessage,
+    pub finish_reason: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiResponseMessage {
+    pub role: &'static str,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiChunk {
+    id: String,
+    object: &'static str,
+    created: i64,
+    model: String,
+    choices: Vec<OpenAiChunkChoice>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiChunkChoice {
+    index: u32,
+    delta: OpenAiDelta,
+    finish_reason: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize, Default)]
+struct OpenAiDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+/// `POST /v1/chat/completions`
+///
+/// OpenAI-compatible turns are stateless: the caller resends full message
+/// history each request, so this doesn't touch the `ConversationStore` used
+/// by the bespoke `/chat` and `/chat/stream` endpoints.
+pub async fn handle_chat_completions(Json(request): Json<OpenAiChatRequest>) -> Response {
+    if request.stream {
+        handle_stream(request).await.into_response()
+    } else {
+        match handle_once(request).await {
+            Ok(completion) => Json(completion).into_response(),
+            Err(e) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": { "message": e.to_string() } })),
+            )
+                .into_response(),
+        }
+    }
+}
+
+async fn handle_once(request: OpenAiChatRequest) -> anyhow::Result<OpenAiChatCompletion> {
+    let conversation = build_conversation(&request.messages);
+    let last_user = request
+        .messages
+        .iter()
+        .rev()
+        .find(|m| m.role == "user")
+        .map(|m| m.content.clone())
+        .unwrap_or_default();
+
+    let runtime_mutex = get_runtime().await?;
+    let runtime = runtime_mutex.lock().await;
+
+    let content = Content {
+        text: Some(last_user),
+        source: Some("openai-compat".to_string()),
+        ..Default::default()
+    };
+    let message = Memory::new(UUID::new_v4(), UUID::new_v4(), content);
+    let state = runtime.compose_state(&message).await?;
+
+    let mut conversation = conversation;
+    let response_text = tools::run_tool_loop(&runtime, &message, &state, &mut conversation).await?;
+
+    Ok(OpenAiChatCompletion {
+        id: format!("chatcmpl-{}", uuid::Uuid::new_v4()),
+        object: "chat.completion",
+        created: Utc::now().timestamp(),
+        model: request.model,
+        choices: vec![OpenAiChoice {
+            index: 0,
+            message: OpenAiResponseMessage {
+                role: "assistant",
+                content: response_text,
+            },
+            finish_reason: "stop",
+        }],
+    })
+}
+
+async fn handle_stream(request: OpenAiChatRequest) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let id = format!("chatcmpl-{}", uuid::Uuid::new_v4());
+    let created = Utc::now().timestamp();
+    let model = request.model.clone();
+    let messages = build_conversation(&request.messages);
+
+    let (tx, rx) = mpsc::channel::<String>(32);
+
+    tokio::spawn(async move {
+        let config = match ProviderConfig::from_env() {
+            Ok(config) => config,
+            Err(e) => {
+                let _ = tx.send(format!("error: {e}")).await;
+                return;
+            }
+        };
+        let client = match build_client(&config) {
+            Ok(client) => client,
+            Err(e) => {
+                let _ = tx.send(format!("error: {e}")).await;
+                return;
+            }
+        };
+
+        let (event_tx, mut event_rx) = mpsc::channel::<crate::StreamEvent>(32);
+        let forward = tokio::spawn(async move {
+            while let Some(event) = event_rx.recv().await {
+                if let Some(text) = event.text {
+                    if tx.send(text).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        // This endpoint has no abort API of its own (see `handle_chat_completions`'s
+        // doc comment on why it's stateless), so the request can only be cancelled
+        // by the upstream SSE client disconnecting; a fresh token that's never
+        // externally triggered is still what `stream_completion` needs to consume
+        // `upstream.next()` against.
+        let _ = client
+            .stream_completion(&model, &messages, event_tx, CancellationToken::new())
+            .await;
+        let _ = forward.await;
+    });
+
+    let pin: Pin<Box<dyn Stream<Item = Result<Event, std::convert::Infallible>> + Send>> = Box::pin(
+        ReceiverStream::new(rx)
+            .map(move |text| {
+                let chunk = OpenAiChunk {
+                    id: id.clone(),
+                    object: "chat.completion.chunk",
+                    created,
+                    model: model.clone(),
+                    choices: vec![OpenAiChunkChoice {
+                        index: 0,
+                        delta: OpenAiDelta { content: Some(text) },
+                        finish_reason: None,
+                    }],
+                };
+                Ok(Event::default().data(serde_json::to_string(&chunk).unwrap_or_default()))
+            })
+            .chain(stream::once(async { Ok(Event::default().data("[DONE]")) })),
+    );
+
+    Sse::new(pin).keep_alive(KeepAlive::default())
+}
+
+fn build_conversation(messages: &[OpenAiRequestMessage]) -> Vec<ChatMessage> {
+    let mut conversation: Vec<ChatMessage> = messages
+        .iter()
+        .map(|m| ChatMessage::new(m.role.clone(), m.content.clone()))
+        .collect();
+
+    if !conversation.iter().any(|m| m.role == "system") {
+        let (_, _, system) = get_character();
+        conversation.insert(0, ChatMessage::new("system", system));
+    }
+
+    conversation
+}
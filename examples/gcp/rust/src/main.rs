@@ -1,7 +1,9 @@
 //! GCP Cloud Run handler for elizaOS chat worker (Rust)
 //!
 //! This Cloud Run service processes chat messages and returns AI responses
-//! using the elizaOS runtime with OpenAI as the LLM provider.
+//! using the elizaOS runtime. The upstream LLM backend is pluggable (see
+//! `providers`): OpenAI, Azure OpenAI, Claude, Ollama, or any
+//! OpenAI-compatible endpoint, selected via `LLM_PROVIDER`.
 
 use anyhow::Result;
 use axum::{
@@ -19,34 +21,67 @@ use elizaos::{
     parse_character,
     runtime::{AgentRuntime, RuntimeOptions},
     types::{Content, Memory, UUID},
-    IMessageService,
 };
 use elizaos_plugin_openai::create_openai_elizaos_plugin;
-use futures::stream::{self, Stream};
+use futures::{
+    stream::{self, Stream},
+    StreamExt,
+};
 use once_cell::sync::OnceCell;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, env, net::SocketAddr, pin::Pin, sync::Arc, time::SystemTime};
-use tokio::sync::{Mutex, RwLock};
+use std::{collections::HashMap, env, net::SocketAddr, pin::Pin, sync::Arc};
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{error, info};
 
+mod compat;
+mod matrix;
+mod providers;
+mod store;
+mod tools;
+
+use providers::{build_client, ProviderConfig};
+use store::ConversationStore;
+
 // Global runtime instance (singleton)
 static RUNTIME: OnceCell<Mutex<AgentRuntime>> = OnceCell::new();
 
-// Shared state for conversations
-type ConversationStore = Arc<RwLock<HashMap<String, ConversationState>>>;
+// Shared, pooled conversation store (axum app state)
+type ConversationStoreHandle = Arc<dyn ConversationStore>;
+
+/// In-flight streaming generations, keyed by conversation id, so `/chat/abort`
+/// and client-disconnect can both cancel the same upstream request.
+type CancellationMap = Arc<RwLock<HashMap<String, CancellationToken>>>;
 
 #[derive(Clone)]
-struct ConversationState {
-    messages: Vec<ChatMessage>,
-    created_at: SystemTime,
+struct AppState {
+    conversations: ConversationStoreHandle,
+    cancellations: CancellationMap,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 struct ChatMessage {
     role: String,
     content: String,
+    /// Set on `role: "tool"` messages to tie the result back to the call.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "toolCallId")]
+    tool_call_id: Option<String>,
+    /// Set on `role: "assistant"` messages that invoked tools.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "toolCalls")]
+    tool_calls: Option<Vec<serde_json::Value>>,
+}
+
+impl ChatMessage {
+    fn new(role: impl Into<String>, content: impl Into<String>) -> Self {
+        ChatMessage {
+            role: role.into(),
+            content: content.into(),
+            tool_call_id: None,
+            tool_calls: None,
+        }
+    }
 }
 
 // Request/Response types
@@ -59,6 +94,17 @@ struct ChatRequest {
     conversation_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct AbortRequest {
+    #[serde(rename = "conversationId")]
+    conversation_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct AbortResponse {
+    aborted: bool,
+}
+
 #[derive(Debug, Serialize)]
 struct ChatResponse {
     response: String,
@@ -169,6 +215,14 @@ async fn handle_info() -> Json<InfoResponse> {
         "POST /chat/stream".to_string(),
         "Send a message and receive a streaming response".to_string(),
     );
+    endpoints.insert(
+        "POST /v1/chat/completions".to_string(),
+        "OpenAI-compatible chat completions (streaming and non-streaming)".to_string(),
+    );
+    endpoints.insert(
+        "POST /chat/abort".to_string(),
+        "Cancel an in-flight streaming generation for a conversation".to_string(),
+    );
     endpoints.insert("GET /health".to_string(), "Health check endpoint".to_string());
     endpoints.insert("GET /".to_string(), "This info endpoint".to_string());
 
@@ -223,22 +277,22 @@ async fn process_chat(request: ChatRequest) -> Result<ChatResponse> {
 
     // Create message
     let content = Content {
-        text: Some(request.message),
+        text: Some(request.message.clone()),
         source: Some("gcp-cloud-run".to_string()),
         ..Default::default()
     };
-    let mut message = Memory::new(user_id.clone(), room_id.clone(), content);
+    let message = Memory::new(user_id.clone(), room_id.clone(), content);
+    let state = runtime.compose_state(&message).await?;
 
-    // Process message
-    let result = runtime
-        .message_service()
-        .handle_message(&runtime, &mut message, None, None)
-        .await?;
+    let (_, _, system) = get_character();
+    let mut conversation = vec![
+        ChatMessage::new("system", system),
+        ChatMessage::new("user", request.message),
+    ];
 
-    let response_text = result
-        .response_content
-        .and_then(|c| c.text)
-        .unwrap_or_else(|| "I apologize, but I could not generate a response.".to_string());
+    // Advertise the runtime's registered actions as callable tools and loop
+    // on tool_calls until the model returns a plain text answer.
+    let response_text = tools::run_tool_loop(&runtime, &message, &state, &mut conversation).await?;
 
     Ok(ChatResponse {
         response: response_text,
@@ -249,7 +303,7 @@ async fn process_chat(request: ChatRequest) -> Result<ChatResponse> {
 
 /// Streaming chat handler
 async fn handle_stream_chat(
-    State(conversations): State<ConversationStore>,
+    State(state): State<AppState>,
     Json(request): Json<ChatRequest>,
 ) -> Response {
     if request.message.trim().is_empty() {
@@ -269,184 +323,204 @@ async fn handle_stream_chat(
         .clone()
         .unwrap_or_else(|| format!("conv-{}", uuid::Uuid::new_v4()));
 
-    // Get or create conversation state
-    let messages = {
-        let mut convos = conversations.write().await;
-        let state = convos
-            .entry(conversation_id.clone())
-            .or_insert_with(|| ConversationState {
-                messages: vec![ChatMessage {
-                    role: "system".to_string(),
-                    content: system,
-                }],
-                created_at: SystemTime::now(),
-            });
-        state.messages.push(ChatMessage {
-            role: "user".to_string(),
-            content: request.message.clone(),
-        });
-        state.messages.clone()
+    let messages = match state
+        .conversations
+        .start_turn(&conversation_id, system, request.message.clone())
+        .await
+    {
+        Ok(messages) => messages,
+        Err(e) => {
+            error!("Failed to load conversation: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Internal server error".to_string(),
+                    code: "INTERNAL_ERROR".to_string(),
+                }),
+            )
+                .into_response();
+        }
     };
 
-    let stream = create_stream(name.clone(), conversation_id.clone(), messages, conversations);
+    let cancel = CancellationToken::new();
+    state
+        .cancellations
+        .write()
+        .await
+        .insert(conversation_id.clone(), cancel.clone());
+
+    let stream = create_stream(
+        name.clone(),
+        conversation_id,
+        messages,
+        state.conversations,
+        state.cancellations,
+        cancel,
+    );
 
     Sse::new(stream)
         .keep_alive(KeepAlive::default())
         .into_response()
 }
 
+/// Cancel an in-flight streaming generation for a conversation, e.g. because
+/// the user asked the agent to stop.
+async fn handle_abort_chat(
+    State(state): State<AppState>,
+    Json(request): Json<AbortRequest>,
+) -> Json<AbortResponse> {
+    let token = state.cancellations.write().await.remove(&request.conversation_id);
+    let aborted = token.is_some();
+    if let Some(token) = token {
+        token.cancel();
+    }
+    Json(AbortResponse { aborted })
+}
+
+/// Drops its `CancellationToken` when the SSE response stream itself is
+/// dropped, which axum does as soon as the client disconnects. This is what
+/// lets a client going away cancel the same upstream request that
+/// `/chat/abort` cancels explicitly.
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Build the SSE stream for a conversation turn.
+///
+/// The metadata event is sent immediately, then `process_stream` drives the
+/// upstream request in a background task and forwards each delta to the
+/// client the moment it arrives, rather than collecting the full response
+/// first. The returned stream carries a `CancelOnDrop` guard alongside it so
+/// that the client disconnecting (which axum surfaces as the response stream
+/// being dropped) cancels the same token `/chat/abort` would.
 fn create_stream(
     character_name: String,
     conversation_id: String,
     messages: Vec<ChatMessage>,
-    conversations: ConversationStore,
+    conversations: ConversationStoreHandle,
+    cancellations: CancellationMap,
+    cancel: CancellationToken,
 ) -> Pin<Box<dyn Stream<Item = Result<Event, std::convert::Infallible>> + Send>> {
-    Box::pin(stream::once(async move {
-        // Send metadata first
-        let metadata = StreamEvent {
-            text: None,
-            conversation_id: Some(conversation_id.clone()),
-            character: Some(character_name),
-            error: None,
-        };
-
-        let events = process_stream(conversation_id, messages, conversations).await;
-        stream::iter(
-            std::iter::once(Ok(Event::default().data(
-                serde_json::to_string(&metadata).unwrap_or_default(),
-            )))
-            .chain(events.into_iter().map(|e| {
-                Ok(Event::default().data(serde_json::to_string(&e).unwrap_or_default()))
-            }))
-            .chain(std::iter::once(Ok(Event::default().data("[DONE]")))),
-        )
-    })
-    .flatten())
+    let (tx, rx) = mpsc::channel::<StreamEvent>(32);
+
+    let metadata = StreamEvent {
+        text: None,
+        conversation_id: Some(conversation_id.clone()),
+        character: Some(character_name),
+        error: None,
+    };
+
+    {
+        let conversation_id = conversation_id.clone();
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            if tx.send(metadata).await.is_err() {
+                return;
+            }
+            process_stream(conversation_id.clone(), messages, conversations, tx, cancel).await;
+            cancellations.write().await.remove(&conversation_id);
+        });
+    }
+
+    let events = ReceiverStream::new(rx)
+        .map(|event| Ok(Event::default().data(serde_json::to_string(&event).unwrap_or_default())))
+        .chain(stream::once(async { Ok(Event::default().data("[DONE]")) }));
+
+    // Carrying the guard alongside the inner stream (rather than as a
+    // separate spawned task) ties its lifetime to the SSE response stream
+    // itself, so it only fires on genuine client disconnect, not on normal
+    // completion (`CancelOnDrop::drop` after `[DONE]` is a harmless no-op
+    // cancel of an already-finished generation).
+    Box::pin(stream::unfold(
+        (events, CancelOnDrop(cancel)),
+        |(mut events, guard)| async move {
+            let next = events.next().await?;
+            Some((next, (events, guard)))
+        },
+    ))
 }
 
+/// Drive the upstream chat-completions stream and forward each delta through
+/// `tx` as soon as it arrives, appending the full text to the conversation
+/// store once the upstream stream completes or is cancelled. Whether a
+/// partial response (from cancellation) is persisted or discarded is
+/// controlled by `ABORT_DISCARD_PARTIAL` (defaults to persisting it, since a
+/// partial answer is usually more useful than silently losing the turn).
 async fn process_stream(
     conversation_id: String,
     messages: Vec<ChatMessage>,
-    conversations: ConversationStore,
-) -> Vec<StreamEvent> {
-    let base_url = env::var("OPENAI_BASE_URL").unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
-    let model = env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-5-mini".to_string());
-    let api_key = match env::var("OPENAI_API_KEY") {
-        Ok(key) => key,
-        Err(_) => {
-            return vec![StreamEvent {
-                text: None,
-                conversation_id: None,
-                character: None,
-                error: Some("OPENAI_API_KEY not set".to_string()),
-            }];
+    conversations: ConversationStoreHandle,
+    tx: mpsc::Sender<StreamEvent>,
+    cancel: CancellationToken,
+) {
+    let config = match ProviderConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            let _ = tx
+                .send(StreamEvent {
+                    text: None,
+                    conversation_id: None,
+                    character: None,
+                    error: Some(format!("Provider config error: {}", e)),
+                })
+                .await;
+            return;
         }
     };
+    let model = env::var("MODEL")
+        .or_else(|_| env::var("OPENAI_MODEL"))
+        .unwrap_or_else(|_| "gpt-5-mini".to_string());
 
-    let client = Client::new();
-
-    let openai_messages: Vec<serde_json::Value> = messages
-        .iter()
-        .map(|m| {
-            serde_json::json!({
-                "role": m.role,
-                "content": m.content
-            })
-        })
-        .collect();
-
-    let response = match client
-        .post(format!("{}/chat/completions", base_url))
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&serde_json::json!({
-            "model": model,
-            "messages": openai_messages,
-            "temperature": 0.7,
-            "max_tokens": 1024,
-            "stream": true
-        }))
-        .send()
-        .await
-    {
-        Ok(resp) => resp,
+    let client = match build_client(&config) {
+        Ok(client) => client,
         Err(e) => {
-            return vec![StreamEvent {
-                text: None,
-                conversation_id: None,
-                character: None,
-                error: Some(format!("Request error: {}", e)),
-            }];
+            let _ = tx
+                .send(StreamEvent {
+                    text: None,
+                    conversation_id: None,
+                    character: None,
+                    error: Some(format!("Provider client error: {}", e)),
+                })
+                .await;
+            return;
         }
     };
 
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return vec![StreamEvent {
-            text: None,
-            conversation_id: None,
-            character: None,
-            error: Some(format!("OpenAI error: {}", error_text)),
-        }];
-    }
-
-    let mut events = Vec::new();
-    let mut full_response = String::new();
-
-    let body = match response.text().await {
-        Ok(body) => body,
+    let full_response = match client
+        .stream_completion(&model, &messages, tx.clone(), cancel.clone())
+        .await
+    {
+        Ok(text) => text,
         Err(e) => {
-            return vec![StreamEvent {
-                text: None,
-                conversation_id: None,
-                character: None,
-                error: Some(format!("Read error: {}", e)),
-            }];
+            let _ = tx
+                .send(StreamEvent {
+                    text: None,
+                    conversation_id: None,
+                    character: None,
+                    error: Some(format!("{}", e)),
+                })
+                .await;
+            return;
         }
     };
 
-    for line in body.lines() {
-        if line.starts_with("data: ") {
-            let data = &line[6..];
-            if data == "[DONE]" {
-                continue;
-            }
-
-            if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(data) {
-                if let Some(content) = parsed["choices"][0]["delta"]["content"].as_str() {
-                    full_response.push_str(content);
-                    events.push(StreamEvent {
-                        text: Some(content.to_string()),
-                        conversation_id: None,
-                        character: None,
-                        error: None,
-                    });
-                }
-            }
-        }
+    let discard_partial_on_abort = env::var("ABORT_DISCARD_PARTIAL")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    if cancel.is_cancelled() && discard_partial_on_abort {
+        return;
     }
 
     // Store the assistant response
     if !full_response.is_empty() {
-        let mut convos = conversations.write().await;
-        if let Some(state) = convos.get_mut(&conversation_id) {
-            state.messages.push(ChatMessage {
-                role: "assistant".to_string(),
-                content: full_response,
-            });
-        }
-
-        // Prune old conversations
-        if convos.len() > 100 {
-            let mut sorted: Vec<_> = convos.iter().collect();
-            sorted.sort_by(|a, b| a.1.created_at.cmp(&b.1.created_at));
-            for (key, _) in sorted.iter().take(sorted.len().saturating_sub(100)) {
-                convos.remove(*key);
-            }
+        if let Err(e) = conversations.complete_turn(&conversation_id, full_response).await {
+            error!("Failed to persist conversation turn: {}", e);
         }
     }
-
-    events
 }
 
 #[tokio::main]
@@ -462,8 +536,19 @@ async fn main() -> Result<()> {
         )
         .init();
 
-    // Shared conversation store
-    let conversations: ConversationStore = Arc::new(RwLock::new(HashMap::new()));
+    // Shared, pooled conversation store (Postgres-backed if DATABASE_URL is
+    // set, in-memory otherwise)
+    let conversations: ConversationStoreHandle = store::build_store().await?;
+
+    // Optional: join Matrix rooms and respond there too, reusing the same
+    // runtime/tool pipeline. No-op unless MATRIX_HOMESERVER_URL /
+    // MATRIX_ACCESS_TOKEN are set.
+    matrix::spawn(conversations.clone());
+
+    let state = AppState {
+        conversations,
+        cancellations: Arc::new(RwLock::new(HashMap::new())),
+    };
 
     // CORS configuration
     let cors = CorsLayer::new()
@@ -477,8 +562,10 @@ async fn main() -> Result<()> {
         .route("/health", get(handle_health))
         .route("/chat", post(handle_chat))
         .route("/chat/stream", post(handle_stream_chat))
+        .route("/chat/abort", post(handle_abort_chat))
+        .route("/v1/chat/completions", post(compat::handle_chat_completions))
         .layer(cors)
-        .with_state(conversations);
+        .with_state(state);
 
     let port: u16 = env::var("PORT")
         .unwrap_or_else(|_| "8080".to_string())
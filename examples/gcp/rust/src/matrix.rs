@@ -0,0 +1,208 @@
+//! Optional Matrix gateway.
+//!
+//! Lets the worker join Matrix rooms and respond to messages there, in
+//! addition to the HTTP endpoints, reusing the same runtime/tool pipeline as
+//! `process_chat`. Each room maps to a conversation id in the existing
+//! `ConversationStore`. Gated behind `MATRIX_HOMESERVER_URL` /
+//! `MATRIX_ACCESS_TOKEN` so HTTP-only deployments pay nothing.
+
+use std::env;
+
+use anyhow::{Context, Result};
+use elizaos::types::{Content, Memory, UUID};
+use matrix_sdk::{
+    config::SyncSettings,
+    room::MessagesOptions,
+    ruma::events::room::message::{MessageType, RoomMessageEventContent, SyncRoomMessageEvent},
+    Client, Room, RoomState,
+};
+use tracing::{error, info, warn};
+
+use crate::{get_character, get_runtime, tools, ChatMessage, ConversationStoreHandle};
+
+/// How many prior room messages to backfill into a fresh conversation.
+const HISTORY_BACKFILL_LIMIT: u32 = 20;
+
+pub struct MatrixConfig {
+    pub homeserver_url: String,
+    pub access_token: String,
+    /// Empty means "respond in every joined room".
+    pub allowed_rooms: Vec<String>,
+}
+
+impl MatrixConfig {
+    pub fn from_env() -> Option<Self> {
+        let homeserver_url = env::var("MATRIX_HOMESERVER_URL").ok()?;
+        let access_token = env::var("MATRIX_ACCESS_TOKEN").ok()?;
+        let allowed_rooms = env::var("MATRIX_ALLOWED_ROOMS")
+            .ok()
+            .map(|rooms| rooms.split(',').map(|s| s.trim().to_string()).collect())
+            .unwrap_or_default();
+
+        Some(MatrixConfig {
+            homeserver_url,
+            access_token,
+            allowed_rooms,
+        })
+    }
+
+    fn allows(&self, room_id: &str) -> bool {
+        self.allowed_rooms.is_empty() || self.allowed_rooms.iter().any(|r| r == room_id)
+    }
+}
+
+fn conversation_id_for(room: &Room) -> String {
+    format!("matrix:{}", room.room_id())
+}
+
+/// Spawn the Matrix sync loop in the background. A no-op if Matrix isn't
+/// configured.
+pub fn spawn(conversations: ConversationStoreHandle) {
+    let Some(config) = MatrixConfig::from_env() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        if let Err(e) = run(config, conversations).await {
+            error!("Matrix gateway stopped: {}", e);
+        }
+    });
+}
+
+async fn run(config: MatrixConfig, conversations: ConversationStoreHandle) -> Result<()> {
+    info!("Connecting Matrix gateway to {}", config.homeserver_url);
+
+    let client = Client::builder()
+        .homeserver_url(&config.homeserver_url)
+        .build()
+        .await
+        .context("failed to build Matrix client")?;
+    client
+        .restore_session(matrix_sdk::matrix_auth::MatrixSession {
+            meta: matrix_sdk::SessionMeta {
+                user_id: client
+                    .whoami()
+                    .await
+                    .map(|r| r.user_id)
+                    .context("failed to resolve Matrix user id from access token")?,
+                device_id: "elizaos-gcp-worker".into(),
+            },
+            tokens: matrix_sdk::matrix_auth::MatrixSessionTokens {
+                access_token: config.access_token.clone(),
+                refresh_token: None,
+            },
+        })
+        .await
+        .context("failed to restore Matrix session")?;
+
+    // Initial sync so `client.rooms()` is populated before we backfill.
+    client.sync_once(SyncSettings::default()).await?;
+
+    for room in client.rooms() {
+        let room_id = room.room_id().to_string();
+        if !config.allows(&room_id) {
+            continue;
+        }
+        if let Err(e) = backfill_room_history(&room, &conversations).await {
+            warn!("Failed to backfill history for room {}: {}", room_id, e);
+        }
+    }
+
+    let allowed_rooms = config.allowed_rooms.clone();
+    client.add_event_handler(move |ev: SyncRoomMessageEvent, room: Room| {
+        let conversations = conversations.clone();
+        let allowed_rooms = allowed_rooms.clone();
+        async move {
+            if !(allowed_rooms.is_empty() || allowed_rooms.iter().any(|r| r == room.room_id().as_str())) {
+                return;
+            }
+            if room.state() != RoomState::Joined {
+                return;
+            }
+
+            let SyncRoomMessageEvent::Original(ev) = ev else {
+                return;
+            };
+            let MessageType::Text(text) = ev.content.msgtype else {
+                return;
+            };
+
+            if let Err(e) = handle_room_message(&room, text.body, &conversations).await {
+                error!("Failed to handle Matrix message in {}: {}", room.room_id(), e);
+            }
+        }
+    });
+
+    // Drives the event handlers above for as long as the process runs.
+    client.sync(SyncSettings::default()).await?;
+    Ok(())
+}
+
+/// Backfill recent room history into the conversation state so the agent
+/// has context on (re)connect, analogous to a CHATHISTORY replay.
+async fn backfill_room_history(room: &Room, conversations: &ConversationStoreHandle) -> Result<()> {
+    let conversation_id = conversation_id_for(room);
+    let mut options = MessagesOptions::backward();
+    options.limit = HISTORY_BACKFILL_LIMIT.into();
+
+    let response = room.messages(options).await?;
+    let (_, _, system) = get_character();
+    let mut turns = vec![ChatMessage::new("system", system)];
+
+    for event in response.chunk.into_iter().rev() {
+        let Ok(raw) = event.raw().deserialize() else {
+            continue;
+        };
+        if let matrix_sdk::ruma::events::AnySyncTimelineEvent::MessageLike(
+            matrix_sdk::ruma::events::AnySyncMessageLikeEvent::RoomMessage(
+                SyncRoomMessageEvent::Original(msg),
+            ),
+        ) = raw
+        {
+            if let MessageType::Text(text) = msg.content.msgtype {
+                let role = if msg.sender.as_str().contains("elizaos") {
+                    "assistant"
+                } else {
+                    "user"
+                };
+                turns.push(ChatMessage::new(role, text.body));
+            }
+        }
+    }
+
+    conversations.seed_history(&conversation_id, turns).await
+}
+
+async fn handle_room_message(
+    room: &Room,
+    body: String,
+    conversations: &ConversationStoreHandle,
+) -> Result<()> {
+    let runtime_mutex = get_runtime().await?;
+    let runtime = runtime_mutex.lock().await;
+
+    let content = Content {
+        text: Some(body.clone()),
+        source: Some("matrix".to_string()),
+        ..Default::default()
+    };
+    let message = Memory::new(UUID::new_v4(), UUID::new_v4(), content);
+    let state = runtime.compose_state(&message).await?;
+
+    let (_, _, system) = get_character();
+    let conversation_id = conversation_id_for(room);
+    let mut conversation = conversations.start_turn(&conversation_id, system, body).await?;
+
+    let response_text = tools::run_tool_loop(&runtime, &message, &state, &mut conversation).await?;
+
+    if !response_text.is_empty() {
+        conversations.complete_turn(&conversation_id, response_text.clone()).await?;
+    }
+
+    // Sent once complete rather than edited token-by-token: Matrix clients
+    // render message edits as a visible "(edited)" flash on every delta,
+    // which is noisier than a single final message for most rooms.
+    room.send(RoomMessageEventContent::text_plain(response_text)).await?;
+
+    Ok(())
+}
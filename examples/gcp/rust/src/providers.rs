@@ -0,0 +1,438 @@
+//! Pluggable LLM backends for the Cloud Run worker.
+//!
+//! `process_stream` used to hardcode the OpenAI chat-completions shape and
+//! read `OPENAI_*` env vars directly. `ProviderConfig` captures the handful
+//! of provider shapes we support, and `ChatClient` is the seam each one
+//! implements so the worker can point at OpenAI, Azure OpenAI, Claude,
+//! Ollama, or any OpenAI-compatible endpoint without code changes.
+
+use std::env;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::{ChatMessage, StreamEvent};
+
+/// A single `tool_calls` entry from a provider's response.
+#[derive(Debug, Clone)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    /// Raw JSON arguments string, as the provider sent it.
+    pub arguments: String,
+}
+
+/// The result of a single (possibly tool-calling) completion request.
+#[derive(Debug, Default)]
+pub struct ChatCompletion {
+    pub content: Option<String>,
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// Per-client configuration, tagged by `"type"` so it can be deserialized
+/// straight out of `RuntimeSettings`/`EnvironmentConfig`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ProviderConfig {
+    Openai(HttpProviderConfig),
+    AzureOpenai(HttpProviderConfig),
+    Claude(HttpProviderConfig),
+    Ollama(HttpProviderConfig),
+    OpenaiCompatible(HttpProviderConfig),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpProviderConfig {
+    /// Base URL for the provider's API (e.g. `https://api.openai.com/v1`).
+    #[serde(alias = "base_url")]
+    pub api_base: String,
+    pub api_key: Option<String>,
+    pub proxy: Option<String>,
+    /// Connect timeout in seconds.
+    pub connect_timeout: Option<u64>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl ProviderConfig {
+    fn http(&self) -> &HttpProviderConfig {
+        match self {
+            ProviderConfig::Openai(c)
+            | ProviderConfig::AzureOpenai(c)
+            | ProviderConfig::Claude(c)
+            | ProviderConfig::Ollama(c)
+            | ProviderConfig::OpenaiCompatible(c) => c,
+        }
+    }
+
+    /// Build the reqwest client for this provider, honouring its proxy and
+    /// connect-timeout settings.
+    fn build_client(&self) -> Result<Client> {
+        let http = self.http();
+        let mut builder = Client::builder();
+        if let Some(timeout) = http.connect_timeout {
+            builder = builder.connect_timeout(Duration::from_secs(timeout));
+        }
+        if let Some(proxy) = &http.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Load the active provider from environment variables.
+    ///
+    /// `LLM_PROVIDER` selects the variant (defaults to `openai`); each
+    /// provider then reads its own `api_base`/`api_key` from the matching
+    /// `<PROVIDER>_*` env vars, falling back to `OPENAI_*` for `openai` so
+    /// existing deployments keep working unchanged.
+    pub fn from_env() -> Result<Self> {
+        let kind = env::var("LLM_PROVIDER").unwrap_or_else(|_| "openai".to_string());
+
+        let config = |prefix: &str, default_base: &str| HttpProviderConfig {
+            api_base: env::var(format!("{prefix}_BASE_URL"))
+                .unwrap_or_else(|_| default_base.to_string()),
+            api_key: env::var(format!("{prefix}_API_KEY")).ok(),
+            proxy: env::var(format!("{prefix}_PROXY")).ok(),
+            connect_timeout: env::var(format!("{prefix}_CONNECT_TIMEOUT_SECS"))
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            model: env::var(format!("{prefix}_MODEL")).ok(),
+        };
+
+        Ok(match kind.as_str() {
+            "openai" => ProviderConfig::Openai(config("OPENAI", "https://api.openai.com/v1")),
+            "azure-openai" => ProviderConfig::AzureOpenai(config("AZURE_OPENAI", "")),
+            "claude" => ProviderConfig::Claude(config("CLAUDE", "https://api.anthropic.com/v1")),
+            "ollama" => ProviderConfig::Ollama(config("OLLAMA", "http://localhost:11434/v1")),
+            "openai-compatible" => {
+                ProviderConfig::OpenaiCompatible(config("LLM", "https://api.openai.com/v1"))
+            }
+            other => return Err(anyhow!("unknown LLM_PROVIDER type: {other}")),
+        })
+    }
+}
+
+/// A provider-agnostic streaming chat client.
+///
+/// Each impl knows how to build that provider's request and how to parse
+/// its streaming delta format, forwarding text chunks through `tx` as soon
+/// as they arrive. Returns the full accumulated response text once the
+/// upstream stream completes, or as much of it as had been produced when
+/// `cancel` fired.
+#[async_trait]
+pub trait ChatClient: Send + Sync {
+    async fn stream_completion(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        tx: mpsc::Sender<StreamEvent>,
+        cancel: CancellationToken,
+    ) -> Result<String>;
+
+    /// Non-streaming completion that may return `tool_calls` instead of
+    /// (or alongside) text, for the multi-step tool-calling loop.
+    ///
+    /// The default errors out; only clients that can emit an OpenAI-shaped
+    /// `tools`/`tool_calls` response should override it.
+    async fn complete(
+        &self,
+        _model: &str,
+        _messages: &[ChatMessage],
+        _tools: &[serde_json::Value],
+    ) -> Result<ChatCompletion> {
+        Err(anyhow!("this provider does not support tool calling"))
+    }
+}
+
+/// Construct the `ChatClient` for the configured provider.
+pub fn build_client(config: &ProviderConfig) -> Result<Box<dyn ChatClient>> {
+    match config {
+        ProviderConfig::Claude(http) => Ok(Box::new(ClaudeClient {
+            http: http.clone(),
+            client: config.build_client()?,
+        })),
+        // OpenAI, Azure OpenAI, Ollama, and generic OpenAI-compatible
+        // endpoints all speak the same `choices[].delta.content` shape.
+        ProviderConfig::Openai(http)
+        | ProviderConfig::AzureOpenai(http)
+        | ProviderConfig::Ollama(http)
+        | ProviderConfig::OpenaiCompatible(http) => Ok(Box::new(OpenAiCompatibleClient {
+            http: http.clone(),
+            client: config.build_client()?,
+        })),
+    }
+}
+
+/// Render a `ChatMessage` in OpenAI's request shape, including the
+/// `tool_call_id` / `tool_calls` fields used by the tool-calling loop.
+fn to_openai_message(m: &ChatMessage) -> serde_json::Value {
+    let mut value = serde_json::json!({ "role": m.role, "content": m.content });
+    if let Some(tool_call_id) = &m.tool_call_id {
+        value["tool_call_id"] = serde_json::Value::String(tool_call_id.clone());
+    }
+    if let Some(tool_calls) = &m.tool_calls {
+        value["tool_calls"] = serde_json::Value::Array(tool_calls.clone());
+    }
+    value
+}
+
+struct OpenAiCompatibleClient {
+    http: HttpProviderConfig,
+    client: Client,
+}
+
+#[async_trait]
+impl ChatClient for OpenAiCompatibleClient {
+    async fn stream_completion(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        tx: mpsc::Sender<StreamEvent>,
+        cancel: CancellationToken,
+    ) -> Result<String> {
+        let openai_messages: Vec<serde_json::Value> = messages.iter().map(to_openai_message).collect();
+
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.http.api_base))
+            .header("Content-Type", "application/json");
+        if let Some(api_key) = &self.http.api_key {
+            request = request.header("Authorization", format!("Bearer {api_key}"));
+        }
+
+        let response = request
+            .json(&serde_json::json!({
+                "model": model,
+                "messages": openai_messages,
+                "temperature": 0.7,
+                "max_tokens": 1024,
+                "stream": true
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("provider error: {error_text}"));
+        }
+
+        let mut full_response = String::new();
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut upstream = response.bytes_stream();
+
+        loop {
+            let chunk = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => return Ok(full_response),
+                chunk = upstream.next() => chunk,
+            };
+            let Some(chunk) = chunk else { break };
+            let chunk = chunk?;
+            buffer.extend_from_slice(&chunk);
+
+            // Split on raw newline bytes before decoding: `\n` can't appear inside a multi-byte
+            // UTF-8 sequence, so a line is only decoded once every byte of it has arrived,
+            // regardless of how the network split it across chunks.
+            while let Some(newline) = buffer.iter().position(|&b| b == b'\n') {
+                let line_bytes: Vec<u8> = buffer.drain(..=newline).collect();
+                let line = String::from_utf8_lossy(&line_bytes);
+                let line = line.trim_end_matches('\r').to_string();
+
+                let Some(data) = line.strip_prefix("data: ") else {
+                    continue;
+                };
+                if data == "[DONE]" {
+                    continue;
+                }
+
+                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(data) {
+                    if let Some(content) = parsed["choices"][0]["delta"]["content"].as_str() {
+                        full_response.push_str(content);
+                        if tx
+                            .send(StreamEvent {
+                                text: Some(content.to_string()),
+                                conversation_id: None,
+                                character: None,
+                                error: None,
+                            })
+                            .await
+                            .is_err()
+                        {
+                            return Ok(full_response);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(full_response)
+    }
+
+    async fn complete(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+    ) -> Result<ChatCompletion> {
+        let openai_messages: Vec<serde_json::Value> = messages.iter().map(to_openai_message).collect();
+
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.http.api_base))
+            .header("Content-Type", "application/json");
+        if let Some(api_key) = &self.http.api_key {
+            request = request.header("Authorization", format!("Bearer {api_key}"));
+        }
+
+        let mut body = serde_json::json!({
+            "model": model,
+            "messages": openai_messages,
+            "temperature": 0.7,
+            "max_tokens": 1024,
+        });
+        if !tools.is_empty() {
+            body["tools"] = serde_json::Value::Array(tools.to_vec());
+        }
+
+        let response = request.json(&body).send().await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("provider error: {error_text}"));
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let message = &body["choices"][0]["message"];
+
+        let tool_calls = message["tool_calls"]
+            .as_array()
+            .map(|calls| {
+                calls
+                    .iter()
+                    .filter_map(|call| {
+                        Some(ToolCall {
+                            id: call["id"].as_str()?.to_string(),
+                            name: call["function"]["name"].as_str()?.to_string(),
+                            arguments: call["function"]["arguments"].as_str().unwrap_or("{}").to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(ChatCompletion {
+            content: message["content"].as_str().map(|s| s.to_string()),
+            tool_calls,
+        })
+    }
+}
+
+struct ClaudeClient {
+    http: HttpProviderConfig,
+    client: Client,
+}
+
+#[async_trait]
+impl ChatClient for ClaudeClient {
+    async fn stream_completion(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        tx: mpsc::Sender<StreamEvent>,
+        cancel: CancellationToken,
+    ) -> Result<String> {
+        let api_key = self
+            .http
+            .api_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("CLAUDE_API_KEY not set"))?;
+
+        // Claude's Messages API takes `system` separately from the turns.
+        let system = messages
+            .iter()
+            .find(|m| m.role == "system")
+            .map(|m| m.content.clone());
+        let turns: Vec<serde_json::Value> = messages
+            .iter()
+            .filter(|m| m.role != "system")
+            .map(|m| serde_json::json!({ "role": m.role, "content": m.content }))
+            .collect();
+
+        let response = self
+            .client
+            .post(format!("{}/messages", self.http.api_base))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({
+                "model": model,
+                "system": system,
+                "messages": turns,
+                "max_tokens": 1024,
+                "stream": true
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("provider error: {error_text}"));
+        }
+
+        let mut full_response = String::new();
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut upstream = response.bytes_stream();
+
+        loop {
+            let chunk = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => return Ok(full_response),
+                chunk = upstream.next() => chunk,
+            };
+            let Some(chunk) = chunk else { break };
+            let chunk = chunk?;
+            buffer.extend_from_slice(&chunk);
+
+            // Split on raw newline bytes before decoding: `\n` can't appear inside a multi-byte
+            // UTF-8 sequence, so a line is only decoded once every byte of it has arrived,
+            // regardless of how the network split it across chunks.
+            while let Some(newline) = buffer.iter().position(|&b| b == b'\n') {
+                let line_bytes: Vec<u8> = buffer.drain(..=newline).collect();
+                let line = String::from_utf8_lossy(&line_bytes);
+                let line = line.trim_end_matches('\r').to_string();
+
+                let Some(data) = line.strip_prefix("data: ") else {
+                    continue;
+                };
+
+                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(data) {
+                    if parsed["type"] == "content_block_delta" {
+                        if let Some(content) = parsed["delta"]["text"].as_str() {
+                            full_response.push_str(content);
+                            if tx
+                                .send(StreamEvent {
+                                    text: Some(content.to_string()),
+                                    conversation_id: None,
+                                    character: None,
+                                    error: None,
+                                })
+                                .await
+                                .is_err()
+                            {
+                                return Ok(full_response);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(full_response)
+    }
+}
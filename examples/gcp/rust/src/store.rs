@@ -0,0 +1,235 @@
+//! Conversation storage backends.
+//!
+//! The original `ConversationStore` was an in-process `HashMap` that is lost
+//! whenever the Cloud Run instance scales to zero or restarts, and isn't
+//! shared across instances. `ConversationStore` is now a trait with that
+//! in-memory impl plus a Postgres-backed one (selected via `DATABASE_URL`),
+//! so conversations survive restarts and multiple horizontally-scaled
+//! workers can share state.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::{postgres::PgPoolOptions, PgPool, Row};
+use tokio::sync::RwLock;
+
+use crate::ChatMessage;
+
+/// How long a conversation can sit untouched before it's eligible for
+/// cleanup. Replaces the old 100-conversation LRU pruning, which didn't
+/// make sense once conversations are shared across instances.
+const CONVERSATION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[async_trait]
+pub trait ConversationStore: Send + Sync {
+    /// Append the user's turn (creating the conversation with
+    /// `system_prompt` if it doesn't exist yet) and return the full message
+    /// history to send to the model.
+    async fn start_turn(
+        &self,
+        conversation_id: &str,
+        system_prompt: String,
+        user_message: String,
+    ) -> Result<Vec<ChatMessage>>;
+
+    /// Persist the assistant's full response once the turn completes, and
+    /// sweep conversations past their TTL.
+    async fn complete_turn(&self, conversation_id: &str, assistant_message: String) -> Result<()>;
+
+    /// Seed a conversation with prior history (e.g. a Matrix room replay) if
+    /// it doesn't already have any turns. A no-op once the conversation has
+    /// started.
+    async fn seed_history(&self, conversation_id: &str, turns: Vec<ChatMessage>) -> Result<()>;
+}
+
+/// Build the configured store: Postgres-backed if `DATABASE_URL` is set,
+/// in-memory otherwise.
+pub async fn build_store() -> Result<Arc<dyn ConversationStore>> {
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        return Ok(Arc::new(
+            PostgresConversationStore::connect(&database_url).await?,
+        ));
+    }
+    Ok(Arc::new(InMemoryConversationStore::default()))
+}
+
+#[derive(Clone)]
+struct ConversationState {
+    messages: Vec<ChatMessage>,
+    updated_at: SystemTime,
+}
+
+#[derive(Default)]
+pub struct InMemoryConversationStore {
+    conversations: RwLock<HashMap<String, ConversationState>>,
+}
+
+#[async_trait]
+impl ConversationStore for InMemoryConversationStore {
+    async fn start_turn(
+        &self,
+        conversation_id: &str,
+        system_prompt: String,
+        user_message: String,
+    ) -> Result<Vec<ChatMessage>> {
+        let mut convos = self.conversations.write().await;
+        let state = convos
+            .entry(conversation_id.to_string())
+            .or_insert_with(|| ConversationState {
+                messages: vec![ChatMessage::new("system", system_prompt)],
+                updated_at: SystemTime::now(),
+            });
+        state.messages.push(ChatMessage::new("user", user_message));
+        state.updated_at = SystemTime::now();
+        Ok(state.messages.clone())
+    }
+
+    async fn complete_turn(&self, conversation_id: &str, assistant_message: String) -> Result<()> {
+        let mut convos = self.conversations.write().await;
+        if let Some(state) = convos.get_mut(conversation_id) {
+            state.messages.push(ChatMessage::new("assistant", assistant_message));
+            state.updated_at = SystemTime::now();
+        }
+        convos.retain(|_, state| {
+            state
+                .updated_at
+                .elapsed()
+                .map(|age| age < CONVERSATION_TTL)
+                .unwrap_or(true)
+        });
+        Ok(())
+    }
+
+    async fn seed_history(&self, conversation_id: &str, turns: Vec<ChatMessage>) -> Result<()> {
+        let mut convos = self.conversations.write().await;
+        convos.entry(conversation_id.to_string()).or_insert_with(|| ConversationState {
+            messages: turns,
+            updated_at: SystemTime::now(),
+        });
+        Ok(())
+    }
+}
+
+pub struct PostgresConversationStore {
+    pool: PgPool,
+}
+
+impl PostgresConversationStore {
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(10)
+            .connect(database_url)
+            .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS conversation_turns (
+                id BIGSERIAL PRIMARY KEY,
+                conversation_id TEXT NOT NULL,
+                role TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            )",
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS conversation_turns_conversation_id_idx \
+             ON conversation_turns (conversation_id, created_at)",
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl ConversationStore for PostgresConversationStore {
+    async fn start_turn(
+        &self,
+        conversation_id: &str,
+        system_prompt: String,
+        user_message: String,
+    ) -> Result<Vec<ChatMessage>> {
+        let existing = sqlx::query(
+            "SELECT role, content FROM conversation_turns \
+             WHERE conversation_id = $1 ORDER BY created_at ASC",
+        )
+        .bind(conversation_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut messages: Vec<ChatMessage> = existing
+            .iter()
+            .map(|row| ChatMessage::new(row.get::<String, _>("role"), row.get::<String, _>("content")))
+            .collect();
+
+        if messages.is_empty() {
+            sqlx::query(
+                "INSERT INTO conversation_turns (conversation_id, role, content) \
+                 VALUES ($1, 'system', $2)",
+            )
+            .bind(conversation_id)
+            .bind(&system_prompt)
+            .execute(&self.pool)
+            .await?;
+            messages.push(ChatMessage::new("system", system_prompt));
+        }
+
+        sqlx::query(
+            "INSERT INTO conversation_turns (conversation_id, role, content) \
+             VALUES ($1, 'user', $2)",
+        )
+        .bind(conversation_id)
+        .bind(&user_message)
+        .execute(&self.pool)
+        .await?;
+        messages.push(ChatMessage::new("user", user_message));
+
+        Ok(messages)
+    }
+
+    async fn complete_turn(&self, conversation_id: &str, assistant_message: String) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO conversation_turns (conversation_id, role, content) \
+             VALUES ($1, 'assistant', $2)",
+        )
+        .bind(conversation_id)
+        .bind(&assistant_message)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("DELETE FROM conversation_turns WHERE created_at < now() - interval '24 hours'")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn seed_history(&self, conversation_id: &str, turns: Vec<ChatMessage>) -> Result<()> {
+        let already_seeded: (i64,) =
+            sqlx::query_as("SELECT count(*) FROM conversation_turns WHERE conversation_id = $1")
+                .bind(conversation_id)
+                .fetch_one(&self.pool)
+                .await?;
+        if already_seeded.0 > 0 {
+            return Ok(());
+        }
+
+        for turn in turns {
+            sqlx::query(
+                "INSERT INTO conversation_turns (conversation_id, role, content) VALUES ($1, $2, $3)",
+            )
+            .bind(conversation_id)
+            .bind(&turn.role)
+            .bind(&turn.content)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
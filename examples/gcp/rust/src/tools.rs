@@ -0,0 +1,156 @@
+//! Multi-step tool/function calling loop for the non-streaming chat handler.
+//!
+//! The agent's registered actions are advertised to the LLM as callable
+//! functions. Once the model answers with one or more `tool_calls`, we run
+//! the corresponding elizaOS actions through the runtime, feed the results
+//! back as `role: "tool"` messages, and re-invoke the model -- looping until
+//! it returns a plain text answer or `MAX_TOOL_STEPS` is hit.
+
+use std::collections::HashMap;
+use std::env;
+
+use anyhow::{anyhow, Result};
+use elizaos::{
+    runtime::AgentRuntime,
+    types::{ActionDefinition, ActionResult, Memory, State},
+};
+use serde_json::Value;
+
+use crate::providers::{build_client, ProviderConfig};
+use crate::ChatMessage;
+
+/// Guard against a model that keeps calling tools without ever answering.
+const MAX_TOOL_STEPS: usize = 8;
+
+/// Build the OpenAI `tools` schema from the runtime's registered actions.
+pub fn build_tool_schemas(definitions: &[ActionDefinition]) -> Vec<Value> {
+    definitions
+        .iter()
+        .map(|def| {
+            serde_json::json!({
+                "type": "function",
+                "function": {
+                    "name": tool_name(&def.name),
+                    "description": def.description,
+                    // elizaOS action parameters are proto-typed and not yet
+                    // projected into JSON schema; accept anything and let
+                    // the action's own validation reject bad input.
+                    "parameters": {
+                        "type": "object",
+                        "additionalProperties": true
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+/// OpenAI function names must match `^[a-zA-Z0-9_-]+$`.
+fn tool_name(action_name: &str) -> String {
+    action_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn resolve_model() -> String {
+    env::var("MODEL")
+        .or_else(|_| env::var("OPENAI_MODEL"))
+        .unwrap_or_else(|_| "gpt-5-mini".to_string())
+}
+
+fn tool_result_text(result: &ActionResult) -> String {
+    if let Some(error) = &result.error {
+        return serde_json::json!({ "success": false, "error": error }).to_string();
+    }
+    serde_json::json!({
+        "success": result.success,
+        "text": result.text,
+        "data": result.data,
+    })
+    .to_string()
+}
+
+/// Drive the tool-calling loop for a single user turn, mutating
+/// `conversation` in place so the caller can persist the full transcript.
+pub async fn run_tool_loop(
+    runtime: &AgentRuntime,
+    message: &Memory,
+    state: &State,
+    conversation: &mut Vec<ChatMessage>,
+) -> Result<String> {
+    let config = ProviderConfig::from_env()?;
+    let client = build_client(&config)?;
+    let model = resolve_model();
+
+    let definitions = runtime.list_action_definitions().await;
+    let tools = build_tool_schemas(&definitions);
+
+    // Identical tool calls (same name + arguments) within this conversation
+    // reuse their prior result instead of re-running the action.
+    let mut call_cache: HashMap<String, ActionResult> = HashMap::new();
+
+    for _ in 0..MAX_TOOL_STEPS {
+        let completion = client
+            .complete(&model, conversation, &tools)
+            .await
+            .map_err(|e| anyhow!("{e} (does the configured provider/model support tool calling?)"))?;
+
+        if completion.tool_calls.is_empty() {
+            return Ok(completion.content.unwrap_or_default());
+        }
+
+        conversation.push(ChatMessage {
+            role: "assistant".to_string(),
+            content: completion.content.clone().unwrap_or_default(),
+            tool_call_id: None,
+            tool_calls: Some(
+                completion
+                    .tool_calls
+                    .iter()
+                    .map(|call| {
+                        serde_json::json!({
+                            "id": call.id,
+                            "type": "function",
+                            "function": { "name": call.name, "arguments": call.arguments },
+                        })
+                    })
+                    .collect(),
+            ),
+        });
+
+        for call in &completion.tool_calls {
+            let cache_key = format!("{}:{}", call.name, call.arguments);
+            let result = match call_cache.get(&cache_key) {
+                Some(cached) => cached.clone(),
+                None => {
+                    let params: HashMap<String, Value> =
+                        serde_json::from_str(&call.arguments).unwrap_or_default();
+                    let mut action_params = HashMap::new();
+                    action_params.insert(call.name.to_uppercase(), params);
+
+                    let results = runtime
+                        .process_selected_actions(message, state, &[call.name.clone()], &action_params)
+                        .await?;
+                    let result = results
+                        .into_iter()
+                        .next()
+                        .unwrap_or_else(|| ActionResult::failure("action produced no result"));
+                    call_cache.insert(cache_key, result.clone());
+                    result
+                }
+            };
+
+            conversation.push(ChatMessage {
+                role: "tool".to_string(),
+                content: tool_result_text(&result),
+                tool_call_id: Some(call.id.clone()),
+                tool_calls: None,
+            });
+        }
+    }
+
+    Err(anyhow!(
+        "exceeded max tool-call steps ({MAX_TOOL_STEPS}) without a final answer"
+    ))
+}
@@ -125,6 +125,17 @@ impl ElizaCloudClient {
         Ok(Self { config, client })
     }
 
+    /// Create a client reusing an already-built [`Client`] (e.g. one handed out by a shared
+    /// connection pool such as `elizaos_plugin_mcp::TransportClientPool`), instead of building a
+    /// dedicated connection pool and TLS session per `ElizaCloudClient`.
+    pub fn with_client(config: ElizaCloudConfig, client: Client) -> Result<Self> {
+        if config.api_key.is_empty() {
+            return Err(ElizaCloudError::configuration("API key is required"));
+        }
+
+        Ok(Self { config, client })
+    }
+
     fn auth_headers(&self, use_embedding_key: bool) -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
         let api_key = if use_embedding_key {
@@ -506,6 +517,22 @@ mod tests {
         assert!(client.is_err());
     }
 
+    #[test]
+    fn test_with_client_reuses_injected_client() {
+        let config = ElizaCloudConfig::new("test_key");
+        let shared = Client::builder().build().unwrap();
+        let client = ElizaCloudClient::with_client(config, shared);
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_with_client_empty_key() {
+        let config = ElizaCloudConfig::new("");
+        let shared = Client::builder().build().unwrap();
+        let client = ElizaCloudClient::with_client(config, shared);
+        assert!(client.is_err());
+    }
+
     #[test]
     fn test_size_to_aspect_ratio() {
         assert_eq!(size_to_aspect_ratio("1024x1024"), "1:1");
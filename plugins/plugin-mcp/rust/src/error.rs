@@ -58,6 +58,15 @@ pub enum McpError {
     /// Invalid argument provided.
     #[error("Invalid argument: {details}")]
     InvalidArgument { details: String },
+
+    /// The selected model/provider was asked to drive a tool-calling loop but doesn't advertise
+    /// function-calling support.
+    #[error("Model/provider does not support function calling: {message}")]
+    FunctionCallingUnsupported { message: String },
+
+    /// A tool call that required approval was denied.
+    #[error("Tool call '{tool_name}' requires approval and was denied")]
+    ToolCallDenied { tool_name: String },
 }
 
 impl McpError {
@@ -119,6 +128,20 @@ impl McpError {
             details: details.into(),
         }
     }
+
+    /// Create a function-calling-unsupported error.
+    pub fn function_calling_unsupported(message: impl Into<String>) -> Self {
+        Self::FunctionCallingUnsupported {
+            message: message.into(),
+        }
+    }
+
+    /// Create a tool-call-denied error.
+    pub fn tool_call_denied(tool_name: impl Into<String>) -> Self {
+        Self::ToolCallDenied {
+            tool_name: tool_name.into(),
+        }
+    }
 }
 
 
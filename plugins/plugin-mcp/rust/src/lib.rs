@@ -5,6 +5,7 @@ pub mod client;
 pub mod error;
 pub mod providers;
 pub mod service;
+pub mod tool_loop;
 pub mod transport;
 pub mod transports;
 pub mod types;
@@ -14,8 +15,12 @@ pub use client::McpClient;
 pub use error::{McpError, McpResult};
 pub use providers::{McpProvider, McpProviderTrait, ProviderContext, ProviderResult};
 pub use service::McpService;
+pub use tool_loop::{
+    run_tool_loop, DenyAll, GenerateTextParams, ModelTurn, ToolCallApprover, ToolCallRequest,
+    ToolCallingModel, ToolSpec, DEFAULT_MAX_STEPS,
+};
 pub use transport::{StdioTransport, Transport};
-pub use transports::HttpTransport;
+pub use transports::{HttpTransport, TransportClientPool};
 pub use types::{
     ConnectionStatus, HttpServerConfig, McpResource, McpResourceContent, McpResourceTemplate,
     McpServerConfig, McpTool, McpToolInputSchema, McpToolResult, StdioServerConfig, TextContent,
@@ -0,0 +1,242 @@
+//! Multi-step tool-calling loop over MCP transports.
+//!
+//! Closes the loop between a model's text generation and MCP tool execution: call the model,
+//! dispatch any tool calls it asks for via [`HttpTransport::send_request`], feed the results back
+//! into the prompt, and repeat until the model returns a final answer or `max_steps` is hit.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::error::{McpError, McpResult};
+use crate::transports::HttpTransport;
+use crate::types::{JsonRpcRequest, JsonRpcResponse, McpTool, McpToolResult};
+
+/// Default cap on model <-> tool round trips for a single [`run_tool_loop`] call.
+pub const DEFAULT_MAX_STEPS: u32 = 8;
+
+/// Parameters for a single text generation call, threaded through the loop. `prompt` grows as
+/// tool calls and their results are appended to it between steps.
+#[derive(Clone, Debug)]
+pub struct GenerateTextParams {
+    /// The prompt, including any tool call/result transcript appended by earlier steps.
+    pub prompt: String,
+    /// Temperature for randomness.
+    pub temperature: Option<f64>,
+    /// Maximum tokens to generate.
+    pub max_tokens: Option<i32>,
+}
+
+/// A tool offered to the model, annotated with whether it may run without approval.
+#[derive(Clone, Debug)]
+pub struct ToolSpec {
+    /// The MCP tool definition advertised to the model.
+    pub tool: McpTool,
+    /// `false` for side-effecting tools (writes, deletes, external actions) that must be approved
+    /// via [`ToolCallApprover`] before the loop will execute them.
+    pub may_execute: bool,
+}
+
+/// A single tool call the model asked for.
+#[derive(Clone, Debug)]
+pub struct ToolCallRequest {
+    /// Name of the tool to call.
+    pub name: String,
+    /// Arguments to pass.
+    pub arguments: Value,
+}
+
+/// What the model returned for one turn of the loop.
+#[derive(Clone, Debug)]
+pub enum ModelTurn {
+    /// The model is done; this is its final answer.
+    FinalAnswer(String),
+    /// The model wants these tool calls executed before it continues.
+    ToolCalls(Vec<ToolCallRequest>),
+}
+
+/// A model backend the loop can drive. Implemented per provider (e.g. ElizaCloud).
+#[async_trait]
+pub trait ToolCallingModel: Send + Sync {
+    /// Whether this model/provider advertises function-calling support. The loop checks this
+    /// up front rather than silently treating tool requests it can't express as a final answer.
+    fn supports_function_calling(&self) -> bool;
+
+    /// Generate the next turn given the prompt-so-far and the tools on offer.
+    async fn generate(
+        &self,
+        params: &GenerateTextParams,
+        tools: &[ToolSpec],
+    ) -> McpResult<ModelTurn>;
+}
+
+/// Approves (or denies) a tool call that isn't cleared for automatic execution.
+#[async_trait]
+pub trait ToolCallApprover: Send + Sync {
+    /// Return `true` to let the call proceed, `false` to deny it.
+    async fn approve(&self, call: &ToolCallRequest) -> bool;
+}
+
+/// An approver that denies every gated call; the safe default when nothing more specific (a UI
+/// prompt, a policy lookup) is wired up.
+pub struct DenyAll;
+
+#[async_trait]
+impl ToolCallApprover for DenyAll {
+    async fn approve(&self, _call: &ToolCallRequest) -> bool {
+        false
+    }
+}
+
+/// Hash `(tool_name, arguments)` into a cache key, so a model repeating an identical call within
+/// the session reuses the prior result instead of re-executing it.
+fn cache_key(name: &str, arguments: &Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(name.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(arguments.to_string().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Look up the tool's spec, run the approval gate for side-effecting tools, and execute the call
+/// over `transport` (or return the cached result for a repeat call).
+async fn execute_tool_call(
+    transport: &mut HttpTransport,
+    tools: &[ToolSpec],
+    approver: &dyn ToolCallApprover,
+    cache: &mut HashMap<String, McpToolResult>,
+    request_id: u64,
+    call: &ToolCallRequest,
+) -> McpResult<McpToolResult> {
+    let spec = tools
+        .iter()
+        .find(|t| t.tool.name == call.name)
+        .ok_or_else(|| McpError::tool_not_found(call.name.clone(), "tool_loop"))?;
+
+    if !spec.may_execute && !approver.approve(call).await {
+        return Err(McpError::tool_call_denied(call.name.clone()));
+    }
+
+    let key = cache_key(&call.name, &call.arguments);
+    if let Some(cached) = cache.get(&key) {
+        return Ok(cached.clone());
+    }
+
+    let request = JsonRpcRequest::new(
+        request_id,
+        "tools/call",
+        Some(json!({
+            "name": call.name,
+            "arguments": call.arguments,
+        })),
+    );
+
+    let raw_response = transport.send_request(serde_json::to_value(&request)?).await?;
+    let response: JsonRpcResponse = serde_json::from_value(raw_response)?;
+
+    if let Some(error) = response.error {
+        return Err(McpError::server(error.code, error.message));
+    }
+
+    let result: McpToolResult = serde_json::from_value(
+        response
+            .result
+            .ok_or_else(|| McpError::protocol("Missing result"))?,
+    )?;
+
+    cache.insert(key, result.clone());
+    Ok(result)
+}
+
+/// Render a tool call and its result into the transcript appended to the prompt for the model's
+/// next turn.
+fn render_tool_exchange(call: &ToolCallRequest, outcome: &McpResult<McpToolResult>) -> String {
+    let result_text = match outcome {
+        Ok(result) => serde_json::to_string(result).unwrap_or_else(|_| "null".to_string()),
+        Err(e) => format!("error: {e}"),
+    };
+
+    format!(
+        "\n\n[tool_call: {} {}]\n[tool_result: {}]",
+        call.name, call.arguments, result_text
+    )
+}
+
+/// Drive the model <-> tool loop until the model returns a final answer or `max_steps` round
+/// trips have been used without one.
+pub async fn run_tool_loop(
+    model: &dyn ToolCallingModel,
+    transport: &mut HttpTransport,
+    tools: &[ToolSpec],
+    approver: &dyn ToolCallApprover,
+    mut params: GenerateTextParams,
+    max_steps: u32,
+) -> McpResult<String> {
+    if !model.supports_function_calling() {
+        return Err(McpError::function_calling_unsupported(
+            "the selected model/provider does not advertise function-calling support",
+        ));
+    }
+
+    let mut cache: HashMap<String, McpToolResult> = HashMap::new();
+    let mut next_id: u64 = 1;
+
+    for _ in 0..max_steps {
+        match model.generate(&params, tools).await? {
+            ModelTurn::FinalAnswer(text) => return Ok(text),
+            ModelTurn::ToolCalls(calls) => {
+                for call in &calls {
+                    let outcome =
+                        execute_tool_call(transport, tools, approver, &mut cache, next_id, call)
+                            .await;
+                    next_id += 1;
+                    params.prompt.push_str(&render_tool_exchange(call, &outcome));
+                }
+            }
+        }
+    }
+
+    Err(McpError::protocol(format!(
+        "Tool loop exceeded max_steps ({max_steps}) without a final answer"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_key_is_stable_and_argument_specific() {
+        let a = cache_key("search", &json!({"q": "eliza"}));
+        let b = cache_key("search", &json!({"q": "eliza"}));
+        let c = cache_key("search", &json!({"q": "other"}));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[tokio::test]
+    async fn deny_all_denies() {
+        let call = ToolCallRequest {
+            name: "delete_file".to_string(),
+            arguments: json!({"path": "/tmp/x"}),
+        };
+        assert!(!DenyAll.approve(&call).await);
+    }
+
+    #[test]
+    fn render_tool_exchange_includes_call_and_result() {
+        let call = ToolCallRequest {
+            name: "search".to_string(),
+            arguments: json!({"q": "eliza"}),
+        };
+        let outcome: McpResult<McpToolResult> = Ok(McpToolResult {
+            content: vec![],
+            is_error: false,
+        });
+        let rendered = render_tool_exchange(&call, &outcome);
+        assert!(rendered.contains("search"));
+        assert!(rendered.contains("tool_result"));
+    }
+}
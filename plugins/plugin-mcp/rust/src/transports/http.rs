@@ -1,30 +1,60 @@
 //! HTTP/SSE transport for MCP connections.
 
 use async_trait::async_trait;
+use futures::StreamExt;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, Mutex};
 
 use crate::error::{McpError, McpResult};
 use crate::transport::Transport;
+use crate::transports::TransportClientPool;
 use crate::types::HttpServerConfig;
 
+/// Initial delay before the first SSE reconnect attempt.
+const SSE_RECONNECT_BASE: Duration = Duration::from_millis(500);
+/// Upper bound on the SSE reconnect backoff.
+const SSE_RECONNECT_MAX: Duration = Duration::from_secs(30);
+/// Channel capacity for server-initiated messages pushed out of the SSE stream.
+const RESPONSE_CHANNEL_CAPACITY: usize = 256;
+
 /// Transport that communicates with an MCP server via HTTP/SSE.
 pub struct HttpTransport {
     config: HttpServerConfig,
     client: Option<reqwest::Client>,
     request_id: AtomicU64,
-    pending_responses: Arc<Mutex<HashMap<u64, tokio::sync::oneshot::Sender<Value>>>>,
+    pending_responses: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
     sse_task: Option<tokio::task::JoinHandle<()>>,
     connected: bool,
     response_rx: Option<mpsc::Receiver<Value>>,
+    /// The `id` and receiving half registered by the most recent `send()` carrying an `id`,
+    /// awaited by the next `receive()` call.
+    pending_receive: Option<(u64, oneshot::Receiver<Value>)>,
+    /// Last SSE `id:` field seen, resent as `Last-Event-ID` so the server can replay anything
+    /// missed across a reconnect.
+    last_event_id: Arc<Mutex<Option<String>>>,
+    /// Shared client registry to pull this transport's `reqwest::Client` from, if one was
+    /// injected. Without one, `connect()` builds a dedicated client as before.
+    pool: Option<Arc<TransportClientPool>>,
 }
 
 impl HttpTransport {
-    /// Create a new HTTP transport.
+    /// Create a new HTTP transport that builds its own `reqwest::Client` on `connect()`.
     pub fn new(config: HttpServerConfig) -> Self {
+        Self::with_pool_inner(config, None)
+    }
+
+    /// Create a new HTTP transport that pulls its `reqwest::Client` from `pool` on `connect()`,
+    /// sharing keep-alive connections and HTTP/2 multiplexing with every other transport backed
+    /// by the same pool.
+    pub fn with_pool(config: HttpServerConfig, pool: Arc<TransportClientPool>) -> Self {
+        Self::with_pool_inner(config, Some(pool))
+    }
+
+    fn with_pool_inner(config: HttpServerConfig, pool: Option<Arc<TransportClientPool>>) -> Self {
         Self {
             config,
             client: None,
@@ -33,6 +63,9 @@ impl HttpTransport {
             sse_task: None,
             connected: false,
             response_rx: None,
+            pending_receive: None,
+            last_event_id: Arc::new(Mutex::new(None)),
+            pool,
         }
     }
 
@@ -40,6 +73,124 @@ impl HttpTransport {
     fn next_id(&self) -> u64 {
         self.request_id.fetch_add(1, Ordering::SeqCst)
     }
+
+    /// Parse one complete SSE event's accumulated `data:` payload and route it: a JSON-RPC message
+    /// carrying an `id` that's being waited on fires the matching `oneshot`; everything else
+    /// (server-initiated notifications, or responses nobody is waiting for anymore) is pushed
+    /// onto `response_rx` so `receive()` returns it in arrival order.
+    async fn dispatch_event(
+        payload: &str,
+        pending_responses: &Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
+        tx: &mpsc::Sender<Value>,
+    ) {
+        let value: Value = match serde_json::from_str(payload) {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+
+        if let Some(id) = value.get("id").and_then(Value::as_u64) {
+            let sender = pending_responses.lock().await.remove(&id);
+            if let Some(sender) = sender {
+                let _ = sender.send(value);
+                return;
+            }
+        }
+
+        let _ = tx.send(value).await;
+    }
+
+    /// Issue a single long-lived GET against the event endpoint and dispatch events from it until
+    /// the stream ends or the receiving end goes away. Returns `Ok(())` once the stream ends so
+    /// the reconnect loop can retry immediately; connection failures are surfaced as `Err` so the
+    /// caller backs off before retrying.
+    async fn run_event_stream(
+        client: &reqwest::Client,
+        url: &str,
+        pending_responses: &Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
+        tx: &mpsc::Sender<Value>,
+        last_event_id: &Arc<Mutex<Option<String>>>,
+    ) -> McpResult<()> {
+        let mut request = client.get(url).header("Accept", "text/event-stream");
+        if let Some(id) = last_event_id.lock().await.clone() {
+            request = request.header("Last-Event-ID", id);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| McpError::connection(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| McpError::connection(e.to_string()))?;
+
+        let mut stream = response.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut data_lines: Vec<String> = Vec::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| McpError::connection(e.to_string()))?;
+            buffer.extend_from_slice(&chunk);
+
+            // Split on raw newline bytes before decoding: `\n` can't appear inside a multi-byte
+            // UTF-8 sequence, so a line is only decoded once every byte of it has arrived,
+            // regardless of how the network split it across chunks.
+            while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                let line_bytes: Vec<u8> = buffer.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&line_bytes);
+                let line = line.trim_end_matches('\r').to_string();
+
+                if line.is_empty() {
+                    if !data_lines.is_empty() {
+                        let payload = data_lines.join("\n");
+                        data_lines.clear();
+                        Self::dispatch_event(&payload, pending_responses, tx).await;
+                    }
+                    continue;
+                }
+
+                if let Some(id) = line.strip_prefix("id:") {
+                    *last_event_id.lock().await = Some(id.trim().to_string());
+                } else if let Some(data) = line.strip_prefix("data:") {
+                    data_lines.push(data.trim_start().to_string());
+                }
+                // Other SSE fields (`event:`, `retry:`, comments) carry no JSON-RPC payload here.
+            }
+
+            if tx.is_closed() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keep (re)running the event stream with capped exponential backoff between failed attempts,
+    /// until the response channel's receiving end is dropped (the transport was closed or the
+    /// struct itself was dropped).
+    async fn run_sse_loop(
+        client: reqwest::Client,
+        url: String,
+        pending_responses: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
+        tx: mpsc::Sender<Value>,
+        last_event_id: Arc<Mutex<Option<String>>>,
+    ) {
+        let mut backoff = SSE_RECONNECT_BASE;
+
+        loop {
+            if tx.is_closed() {
+                return;
+            }
+
+            match Self::run_event_stream(&client, &url, &pending_responses, &tx, &last_event_id)
+                .await
+            {
+                Ok(()) => backoff = SSE_RECONNECT_BASE,
+                Err(_) => {
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(SSE_RECONNECT_MAX);
+                }
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -49,12 +200,27 @@ impl Transport for HttpTransport {
             return Err(McpError::AlreadyConnected);
         }
 
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_millis(self.config.timeout_ms))
-            .build()
-            .map_err(|e| McpError::connection(e.to_string()))?;
+        let timeout = std::time::Duration::from_millis(self.config.timeout_ms);
+        let client = match &self.pool {
+            Some(pool) => pool.client_for(&self.config.url, timeout).await?,
+            None => reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .map_err(|e| McpError::connection(e.to_string()))?,
+        };
+
+        let (tx, rx) = mpsc::channel(RESPONSE_CHANNEL_CAPACITY);
+        let sse_task = tokio::spawn(Self::run_sse_loop(
+            client.clone(),
+            self.config.url.clone(),
+            self.pending_responses.clone(),
+            tx,
+            self.last_event_id.clone(),
+        ));
 
         self.client = Some(client);
+        self.sse_task = Some(sse_task);
+        self.response_rx = Some(rx);
         self.connected = true;
 
         Ok(())
@@ -63,6 +229,12 @@ impl Transport for HttpTransport {
     async fn send(&mut self, message: &Value) -> McpResult<()> {
         let client = self.client.as_ref().ok_or(McpError::NotConnected)?;
 
+        if let Some(id) = message.get("id").and_then(Value::as_u64) {
+            let (tx, rx) = oneshot::channel();
+            self.pending_responses.lock().await.insert(id, tx);
+            self.pending_receive = Some((id, rx));
+        }
+
         client
             .post(&self.config.url)
             .header("Content-Type", "application/json")
@@ -77,11 +249,25 @@ impl Transport for HttpTransport {
     }
 
     async fn receive(&mut self) -> McpResult<Value> {
-        // For simple HTTP transport, we use a request/response pattern
-        // This is different from SSE where responses come asynchronously
-        Err(McpError::protocol(
-            "Direct receive not supported for HTTP transport, use send_request",
-        ))
+        if let Some((id, rx)) = self.pending_receive.take() {
+            let timeout = Duration::from_millis(self.config.timeout_ms);
+            return match tokio::time::timeout(timeout, rx).await {
+                Ok(Ok(value)) => Ok(value),
+                Ok(Err(_)) => Err(McpError::connection(
+                    "SSE stream closed before a response arrived",
+                )),
+                Err(_) => {
+                    self.pending_responses.lock().await.remove(&id);
+                    Err(McpError::timeout("receive"))
+                }
+            };
+        }
+
+        let rx = self.response_rx.as_mut().ok_or(McpError::NotConnected)?;
+
+        rx.recv()
+            .await
+            .ok_or_else(|| McpError::connection("SSE stream closed"))
     }
 
     async fn close(&mut self) -> McpResult<()> {
@@ -92,7 +278,10 @@ impl Transport for HttpTransport {
         }
 
         self.client = None;
+        self.response_rx = None;
+        self.pending_receive = None;
         self.pending_responses.lock().await.clear();
+        *self.last_event_id.lock().await = None;
 
         Ok(())
     }
@@ -139,7 +328,16 @@ mod tests {
         let transport = HttpTransport::new(config);
         assert!(!transport.is_connected());
     }
-}
-
 
+    #[test]
+    fn test_http_transport_with_pool_creation() {
+        let config = HttpServerConfig {
+            url: "http://localhost:8080/mcp".to_string(),
+            timeout_ms: 30000,
+        };
 
+        let pool = Arc::new(crate::transports::TransportClientPool::new());
+        let transport = HttpTransport::with_pool(config, pool);
+        assert!(!transport.is_connected());
+    }
+}
@@ -0,0 +1,7 @@
+//! Transports beyond plain stdio, grouped in their own module.
+
+mod http;
+mod pool;
+
+pub use http::HttpTransport;
+pub use pool::TransportClientPool;
@@ -0,0 +1,105 @@
+//! Shared `reqwest::Client` registry for transports that talk to many hosts.
+//!
+//! Each [`super::HttpTransport::connect`] building its own client spins up an independent
+//! connection pool and TLS session per MCP server; an agent talking to several servers ends up
+//! with many redundant pools. [`TransportClientPool`] hands out one cloned `reqwest::Client` per
+//! host/timeout pair instead, so keep-alive connections and HTTP/2 multiplexing are shared across
+//! every transport that talks to the same host.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+
+use crate::error::{McpError, McpResult};
+
+/// Key a pooled client by the parameters that actually shape the underlying TLS/keep-alive
+/// session, so two transports pointed at the same host and timeout share a client even if their
+/// higher-level server configs differ.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ClientKey {
+    host: String,
+    timeout_ms: u64,
+}
+
+/// Registry of shared [`reqwest::Client`]s, keyed by host and timeout.
+#[derive(Default)]
+pub struct TransportClientPool {
+    clients: Mutex<HashMap<ClientKey, reqwest::Client>>,
+}
+
+impl TransportClientPool {
+    /// Create an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the shared client for `url`'s host and `timeout`, building and caching one if this is
+    /// the first request for that key.
+    pub async fn client_for(&self, url: &str, timeout: Duration) -> McpResult<reqwest::Client> {
+        let host = reqwest::Url::parse(url)
+            .map_err(|e| McpError::connection(e.to_string()))?
+            .host_str()
+            .ok_or_else(|| McpError::connection("URL has no host"))?
+            .to_string();
+
+        let key = ClientKey {
+            host,
+            timeout_ms: timeout.as_millis() as u64,
+        };
+
+        let mut clients = self.clients.lock().await;
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| McpError::connection(e.to_string()))?;
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_client_for_reuses_entry_for_same_host_and_timeout() {
+        let pool = TransportClientPool::new();
+
+        pool.client_for("http://localhost:8080/mcp", Duration::from_millis(1000))
+            .await
+            .unwrap();
+        pool.client_for("http://localhost:8080/other", Duration::from_millis(1000))
+            .await
+            .unwrap();
+
+        assert_eq!(pool.clients.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_client_for_builds_distinct_entries_for_different_hosts() {
+        let pool = TransportClientPool::new();
+
+        pool.client_for("http://localhost:8080/mcp", Duration::from_millis(1000))
+            .await
+            .unwrap();
+        pool.client_for("http://other-host:8080/mcp", Duration::from_millis(1000))
+            .await
+            .unwrap();
+
+        assert_eq!(pool.clients.lock().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_client_for_rejects_url_without_host() {
+        let pool = TransportClientPool::new();
+
+        let result = pool.client_for("not-a-url", Duration::from_millis(1000)).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -1,9 +1,51 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use chrono::Utc;
+use elizaos::types::streaming::IStreamExtractor;
 use tracing::{debug, info, warn};
+use uuid::Uuid;
 
 use super::{EvaluatorContext, EvaluatorResult, MemoryEvaluator};
 use crate::config::MemoryConfig;
-use crate::types::{LongTermMemoryCategory, MemoryExtraction};
+use crate::store::LongTermMemoryStore;
+use crate::types::{LongTermMemory, LongTermMemoryCategory, MemoryExtraction};
+
+/// Parse a single complete `<memory>...</memory>` block's `<category>`, `<content>`, and
+/// `<confidence>` fields. Returns `None` if a field is missing/malformed or the category isn't a
+/// known [`LongTermMemoryCategory`].
+fn parse_memory_block(memory_block: &str) -> Option<(LongTermMemoryCategory, String, f64)> {
+    let category = memory_block.find("<category>").and_then(|start| {
+        memory_block[start + 10..]
+            .find("</category>")
+            .map(|end| memory_block[start + 10..start + 10 + end].trim())
+    })?;
+
+    let content = memory_block.find("<content>").and_then(|start| {
+        memory_block[start + 9..]
+            .find("</content>")
+            .map(|end| memory_block[start + 9..start + 9 + end].trim().to_string())
+    })?;
+
+    let confidence = memory_block.find("<confidence>").and_then(|start| {
+        memory_block[start + 12..]
+            .find("</confidence>")
+            .and_then(|end| {
+                memory_block[start + 12..start + 12 + end]
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+            })
+    })?;
+
+    match category.parse::<LongTermMemoryCategory>() {
+        Ok(category) => Some((category, content, confidence)),
+        Err(e) => {
+            warn!("Invalid memory category: {}", e);
+            None
+        }
+    }
+}
 
 fn parse_memory_extraction_xml(xml: &str) -> Vec<MemoryExtraction> {
     let mut extractions = Vec::new();
@@ -13,43 +55,13 @@ fn parse_memory_extraction_xml(xml: &str) -> Vec<MemoryExtraction> {
         if let Some(mem_end) = remaining[mem_start..].find("</memory>") {
             let memory_block = &remaining[mem_start..mem_start + mem_end + 9];
 
-            let category = memory_block.find("<category>").and_then(|start| {
-                memory_block[start + 10..]
-                    .find("</category>")
-                    .map(|end| memory_block[start + 10..start + 10 + end].trim())
-            });
-
-            let content = memory_block.find("<content>").and_then(|start| {
-                memory_block[start + 9..]
-                    .find("</content>")
-                    .map(|end| memory_block[start + 9..start + 9 + end].trim().to_string())
-            });
-
-            let confidence = memory_block.find("<confidence>").and_then(|start| {
-                memory_block[start + 12..]
-                    .find("</confidence>")
-                    .and_then(|end| {
-                        memory_block[start + 12..start + 12 + end]
-                            .trim()
-                            .parse::<f64>()
-                            .ok()
-                    })
-            });
-
-            if let (Some(cat_str), Some(content), Some(conf)) = (category, content, confidence) {
-                match cat_str.parse::<LongTermMemoryCategory>() {
-                    Ok(category) => {
-                        extractions.push(MemoryExtraction {
-                            category,
-                            content,
-                            confidence: conf,
-                            metadata: serde_json::json!({}),
-                        });
-                    }
-                    Err(e) => {
-                        warn!("Invalid memory category: {}", e);
-                    }
-                }
+            if let Some((category, content, confidence)) = parse_memory_block(memory_block) {
+                extractions.push(MemoryExtraction {
+                    category,
+                    content,
+                    confidence,
+                    metadata: serde_json::json!({}),
+                });
             }
 
             remaining = &remaining[mem_start + mem_end + 9..];
@@ -61,6 +73,86 @@ fn parse_memory_extraction_xml(xml: &str) -> Vec<MemoryExtraction> {
     extractions
 }
 
+/// Streams XML memory-extraction output from an LLM and emits each [`MemoryExtraction`] as soon
+/// as its `<memory>...</memory>` block closes, instead of waiting for the full response to land.
+///
+/// Implements [`IStreamExtractor`] so it can be driven chunk-by-chunk as `TextStreamChunk`s arrive;
+/// completed extractions are buffered internally and retrieved with [`Self::take_extractions`]
+/// after each `push`, since the trait's `push`/`flush` return the (unmodified) streamed text
+/// rather than structured data.
+pub struct StreamingMemoryExtractor {
+    confidence_threshold: f64,
+    buffer: String,
+    extractions: Vec<MemoryExtraction>,
+}
+
+impl StreamingMemoryExtractor {
+    /// Create a new extractor using the same confidence floor as
+    /// [`LongTermExtractionEvaluator::filter_by_confidence`].
+    pub fn new(config: &MemoryConfig) -> Self {
+        Self {
+            confidence_threshold: config.long_term_confidence_threshold.max(0.85),
+            buffer: String::new(),
+            extractions: Vec::new(),
+        }
+    }
+
+    /// Drain and return the [`MemoryExtraction`]s completed since the last call.
+    pub fn take_extractions(&mut self) -> Vec<MemoryExtraction> {
+        std::mem::take(&mut self.extractions)
+    }
+
+    /// Parse and validate every complete `<memory>...</memory>` block currently in the buffer,
+    /// queuing the ones that pass the confidence threshold and dropping the consumed text.
+    fn drain_complete_blocks(&mut self) {
+        while let Some(mem_start) = self.buffer.find("<memory>") {
+            let Some(mem_end) = self.buffer[mem_start..].find("</memory>") else {
+                break;
+            };
+            let block_end = mem_start + mem_end + "</memory>".len();
+
+            if let Some((category, content, confidence)) =
+                parse_memory_block(&self.buffer[mem_start..block_end])
+            {
+                if confidence >= self.confidence_threshold {
+                    self.extractions.push(MemoryExtraction {
+                        category,
+                        content,
+                        confidence,
+                        metadata: serde_json::json!({}),
+                    });
+                }
+            }
+
+            self.buffer.drain(..block_end);
+        }
+    }
+}
+
+impl IStreamExtractor for StreamingMemoryExtractor {
+    fn done(&self) -> bool {
+        false
+    }
+
+    fn push(&mut self, chunk: &str) -> String {
+        self.buffer.push_str(chunk);
+        self.drain_complete_blocks();
+        chunk.to_string()
+    }
+
+    fn flush(&mut self) -> String {
+        // Anything left in the buffer never closed a `<memory>` block; discard it rather than
+        // emitting a malformed/partial extraction.
+        self.buffer.clear();
+        String::new()
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.extractions.clear();
+    }
+}
+
 /// Evaluator for extracting long-term memories from conversations.
 ///
 /// This evaluator analyzes conversation content to identify facts, preferences,
@@ -68,12 +160,23 @@ fn parse_memory_extraction_xml(xml: &str) -> Vec<MemoryExtraction> {
 /// long-term memory for future reference.
 pub struct LongTermExtractionEvaluator {
     config: MemoryConfig,
+    store: Option<Arc<dyn LongTermMemoryStore>>,
 }
 
 impl LongTermExtractionEvaluator {
     /// Creates a new `LongTermExtractionEvaluator` with the given configuration.
     pub fn new(config: MemoryConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            store: None,
+        }
+    }
+
+    /// Attach a [`LongTermMemoryStore`] so `handler` persists extractions instead of only
+    /// reporting them.
+    pub fn with_store(mut self, store: Arc<dyn LongTermMemoryStore>) -> Self {
+        self.store = Some(store);
+        self
     }
 
     /// Determines whether memory extraction should occur based on message counts.
@@ -111,6 +214,13 @@ impl LongTermExtractionEvaluator {
             .filter(|e| e.confidence >= threshold)
             .collect()
     }
+
+    /// Create a [`StreamingMemoryExtractor`] sharing this evaluator's confidence threshold, so
+    /// extracted facts can be persisted as they arrive instead of after the full response
+    /// completes.
+    pub fn streaming_extractor(&self) -> StreamingMemoryExtractor {
+        StreamingMemoryExtractor::new(&self.config)
+    }
 }
 
 #[async_trait]
@@ -157,6 +267,43 @@ impl MemoryEvaluator for LongTermExtractionEvaluator {
             context.entity_id, context.message_count
         );
 
+        let mut stored_count = 0;
+
+        if let Some(store) = &self.store {
+            if let Some(xml) = context.state.get("extractionResponse").and_then(|v| v.as_str()) {
+                let extractions = self.filter_by_confidence(self.parse_response(xml));
+
+                if !extractions.is_empty() {
+                    let now = Utc::now();
+                    let memories: Vec<LongTermMemory> = extractions
+                        .into_iter()
+                        .map(|extraction| LongTermMemory {
+                            id: Uuid::new_v4(),
+                            agent_id: context.agent_id,
+                            entity_id: context.entity_id,
+                            category: extraction.category,
+                            content: extraction.content,
+                            metadata: extraction.metadata,
+                            embedding: None,
+                            confidence: extraction.confidence,
+                            source: None,
+                            created_at: now,
+                            updated_at: now,
+                            last_accessed_at: None,
+                            access_count: 0,
+                            similarity: None,
+                        })
+                        .collect();
+
+                    stored_count = memories.len();
+                    if let Err(e) = store.put_batch(&memories).await {
+                        warn!("Failed to persist long-term memories: {}", e);
+                        stored_count = 0;
+                    }
+                }
+            }
+        }
+
         Some(EvaluatorResult {
             success: true,
             data: Some(serde_json::json!({
@@ -164,6 +311,7 @@ impl MemoryEvaluator for LongTermExtractionEvaluator {
                 "entity_id": context.entity_id.to_string(),
                 "room_id": context.room_id.to_string(),
                 "message_count": context.message_count,
+                "stored_count": stored_count,
             })),
         })
     }
@@ -264,4 +412,42 @@ mod tests {
         assert_eq!(filtered[0].content, "High confidence");
         assert_eq!(filtered[1].content, "Threshold confidence");
     }
+
+    #[test]
+    fn test_streaming_extractor_emits_on_block_close() {
+        let config = MemoryConfig {
+            long_term_confidence_threshold: 0.85,
+            ..Default::default()
+        };
+        let mut extractor = StreamingMemoryExtractor::new(&config);
+
+        extractor.push("<memories><memory><category>sem");
+        assert!(extractor.take_extractions().is_empty());
+
+        extractor.push("antic</category><content>User likes Rust</content>");
+        extractor.push("<confidence>0.95</confidence></memory>");
+
+        let extractions = extractor.take_extractions();
+        assert_eq!(extractions.len(), 1);
+        assert_eq!(extractions[0].category, LongTermMemoryCategory::Semantic);
+        assert_eq!(extractions[0].content, "User likes Rust");
+    }
+
+    #[test]
+    fn test_streaming_extractor_drops_low_confidence_and_partial_blocks() {
+        let config = MemoryConfig {
+            long_term_confidence_threshold: 0.85,
+            ..Default::default()
+        };
+        let mut extractor = StreamingMemoryExtractor::new(&config);
+
+        extractor.push(
+            "<memory><category>episodic</category><content>low</content><confidence>0.5</confidence></memory>",
+        );
+        assert!(extractor.take_extractions().is_empty());
+
+        extractor.push("<memory><category>semantic</category><content>unterminated");
+        extractor.flush();
+        assert!(extractor.take_extractions().is_empty());
+    }
 }
@@ -1,7 +1,7 @@
 mod long_term_extraction;
 mod summarization;
 
-pub use long_term_extraction::LongTermExtractionEvaluator;
+pub use long_term_extraction::{LongTermExtractionEvaluator, StreamingMemoryExtractor};
 pub use summarization::SummarizationEvaluator;
 
 use async_trait::async_trait;
@@ -31,6 +31,8 @@ pub mod evaluators;
 /// Providers for memory context in agent interactions.
 pub mod providers;
 pub mod service;
+/// Pluggable long-term memory storage backends.
+pub mod store;
 pub mod types;
 
 #[cfg(feature = "wasm")]
@@ -40,12 +42,17 @@ pub use config::MemoryConfig;
 pub use error::{MemoryError, Result};
 pub use evaluators::{
     EvaluatorContext, EvaluatorResult, LongTermExtractionEvaluator, MemoryEvaluator,
-    SummarizationEvaluator,
+    StreamingMemoryExtractor, SummarizationEvaluator,
 };
 pub use providers::{
     ContextSummaryProvider, LongTermMemoryProvider, MemoryProvider, ProviderContext, ProviderResult,
 };
 pub use service::MemoryService;
+#[cfg(feature = "local")]
+pub use store::LocalMemoryStore;
+#[cfg(feature = "object-store")]
+pub use store::{ObjectStoreMemoryStore, ObjectStoreMemoryStoreConfig};
+pub use store::LongTermMemoryStore;
 pub use types::{
     LongTermMemory, LongTermMemoryCategory, MemoryExtraction, SessionSummary, SummaryResult,
 };
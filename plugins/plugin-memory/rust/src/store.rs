@@ -0,0 +1,402 @@
+#![allow(missing_docs)]
+//! Pluggable persistence backends for [`LongTermMemory`].
+//!
+//! [`crate::evaluators::LongTermExtractionEvaluator::handler`] persists extracted memories
+//! through [`LongTermMemoryStore`] instead of just returning JSON, so a deployment can swap where
+//! long-term memory lives (the local database, S3-compatible object storage, ...) without
+//! touching extraction logic.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::types::{LongTermMemory, LongTermMemoryCategory};
+
+/// Abstracts persistence of [`LongTermMemory`] records so
+/// [`crate::evaluators::LongTermExtractionEvaluator`] doesn't need to know which backend a
+/// deployment has configured.
+#[async_trait]
+pub trait LongTermMemoryStore: Send + Sync {
+    /// Persist (insert or overwrite) a single memory.
+    async fn put(&self, memory: &LongTermMemory) -> Result<()>;
+
+    /// Persist many memories at once. The default sequentially calls [`Self::put`]; backends for
+    /// which batching is cheaper (object storage, to amortize per-call overhead on high-volume
+    /// extraction) should override this.
+    async fn put_batch(&self, memories: &[LongTermMemory]) -> Result<()> {
+        for memory in memories {
+            self.put(memory).await?;
+        }
+        Ok(())
+    }
+
+    /// Fetch a single memory by ID, scoped to the entity it's about.
+    async fn get(&self, entity_id: Uuid, id: Uuid) -> Result<Option<LongTermMemory>>;
+
+    /// List memories for an entity, optionally narrowed to one category, most-confident/most
+    /// recently-updated first, capped at `limit`.
+    async fn search(
+        &self,
+        entity_id: Uuid,
+        category: Option<LongTermMemoryCategory>,
+        limit: i32,
+    ) -> Result<Vec<LongTermMemory>>;
+
+    /// Remove a memory, scoped to the entity it's about.
+    async fn delete(&self, entity_id: Uuid, id: Uuid) -> Result<()>;
+}
+
+#[cfg(feature = "local")]
+pub use local::LocalMemoryStore;
+
+#[cfg(feature = "local")]
+mod local {
+    use super::{async_trait, Arc, LongTermMemory, LongTermMemoryCategory, LongTermMemoryStore, Uuid};
+    use crate::error::{MemoryError, Result};
+    use crate::service::DatabaseAdapter;
+
+    /// Default store, backed by the crate's existing [`DatabaseAdapter`] (whatever the host
+    /// application wires in), persisting to the `long_term_memories` table the same way
+    /// [`crate::service::MemoryService`] does.
+    pub struct LocalMemoryStore {
+        db: Arc<dyn DatabaseAdapter>,
+    }
+
+    impl LocalMemoryStore {
+        /// Create a store over an existing database adapter.
+        pub fn new(db: Arc<dyn DatabaseAdapter>) -> Self {
+            Self { db }
+        }
+    }
+
+    #[async_trait]
+    impl LongTermMemoryStore for LocalMemoryStore {
+        async fn put(&self, memory: &LongTermMemory) -> Result<()> {
+            self.db
+                .insert(
+                    "long_term_memories",
+                    serde_json::json!({
+                        "id": memory.id.to_string(),
+                        "agent_id": memory.agent_id.to_string(),
+                        "entity_id": memory.entity_id.to_string(),
+                        "category": memory.category.to_string(),
+                        "content": memory.content,
+                        "metadata": memory.metadata,
+                        "embedding": memory.embedding,
+                        "confidence": memory.confidence,
+                        "source": memory.source,
+                        "access_count": memory.access_count,
+                        "created_at": memory.created_at.to_rfc3339(),
+                        "updated_at": memory.updated_at.to_rfc3339(),
+                    }),
+                )
+                .await
+        }
+
+        async fn get(&self, entity_id: Uuid, id: Uuid) -> Result<Option<LongTermMemory>> {
+            let results = self
+                .db
+                .select(
+                    "long_term_memories",
+                    serde_json::json!({
+                        "id": id.to_string(),
+                        "entity_id": entity_id.to_string(),
+                    }),
+                    None,
+                    Some(1),
+                )
+                .await?;
+
+            results
+                .into_iter()
+                .next()
+                .map(|row| serde_json::from_value(row).map_err(MemoryError::from))
+                .transpose()
+        }
+
+        async fn search(
+            &self,
+            entity_id: Uuid,
+            category: Option<LongTermMemoryCategory>,
+            limit: i32,
+        ) -> Result<Vec<LongTermMemory>> {
+            let mut conditions = serde_json::json!({ "entity_id": entity_id.to_string() });
+            if let Some(category) = category {
+                conditions["category"] = serde_json::json!(category.to_string());
+            }
+
+            let results = self
+                .db
+                .select(
+                    "long_term_memories",
+                    conditions,
+                    Some(vec![("confidence", "desc"), ("updated_at", "desc")]),
+                    Some(limit),
+                )
+                .await?;
+
+            results
+                .into_iter()
+                .map(|row| serde_json::from_value(row).map_err(MemoryError::from))
+                .collect()
+        }
+
+        async fn delete(&self, entity_id: Uuid, id: Uuid) -> Result<()> {
+            self.db
+                .delete(
+                    "long_term_memories",
+                    serde_json::json!({
+                        "id": id.to_string(),
+                        "entity_id": entity_id.to_string(),
+                    }),
+                )
+                .await
+        }
+    }
+}
+
+#[cfg(feature = "object-store")]
+pub use object_store::{ObjectStoreMemoryStore, ObjectStoreMemoryStoreConfig};
+
+#[cfg(feature = "object-store")]
+mod object_store {
+    use super::{async_trait, LongTermMemory, LongTermMemoryCategory, LongTermMemoryStore, Uuid};
+    use crate::error::{MemoryError, Result};
+
+    use aes_gcm::aead::{Aead, Payload};
+    use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+    use aws_config::BehaviorVersion;
+    use aws_credential_types::Credentials;
+    use aws_sdk_s3::{
+        config::{Builder as S3ConfigBuilder, Region},
+        primitives::ByteStream,
+        Client,
+    };
+    use sha2::{Digest, Sha256};
+
+    /// Configuration for the S3-compatible backend an [`ObjectStoreMemoryStore`] writes to.
+    /// Mirrors `plugin-sql`'s blob store configuration since both talk to the same class of
+    /// provider (AWS, MinIO, Garage, R2, ...).
+    #[derive(Clone)]
+    pub struct ObjectStoreMemoryStoreConfig {
+        pub access_key_id: String,
+        pub secret_access_key: String,
+        pub region: String,
+        pub bucket: String,
+        pub endpoint: Option<String>,
+        pub force_path_style: bool,
+        /// Key memories are encrypted with (AES-256-GCM) before upload.
+        pub encryption_key: [u8; 32],
+    }
+
+    /// Object-storage (S3/Garage-compatible) backend for [`LongTermMemoryStore`]. Each memory is
+    /// written as its own encrypted, content-addressed object keyed by entity and category, so
+    /// re-extracting the same fact overwrites rather than duplicating. The `Client` is built once
+    /// in [`Self::new`] and shared across every `put`/`get`/`search`/`delete` call instead of
+    /// being constructed per call.
+    pub struct ObjectStoreMemoryStore {
+        client: Client,
+        config: ObjectStoreMemoryStoreConfig,
+    }
+
+    impl ObjectStoreMemoryStore {
+        /// Build an object-store-backed memory store from `config`.
+        pub async fn new(config: ObjectStoreMemoryStoreConfig) -> Result<Self> {
+            let credentials = Credentials::new(
+                &config.access_key_id,
+                &config.secret_access_key,
+                None,
+                None,
+                "elizaos-plugin-memory",
+            );
+
+            let mut s3_config = S3ConfigBuilder::new()
+                .behavior_version(BehaviorVersion::latest())
+                .region(Region::new(config.region.clone()))
+                .credentials_provider(credentials)
+                .force_path_style(config.force_path_style);
+
+            if let Some(ref endpoint) = config.endpoint {
+                s3_config = s3_config.endpoint_url(endpoint);
+            }
+
+            let client = Client::from_conf(s3_config.build());
+
+            Ok(Self { client, config })
+        }
+
+        /// `{entity_id}/{category}/{content_hash}` — content-addressed so re-extracting the same
+        /// fact for the same entity/category overwrites the existing object instead of
+        /// duplicating it.
+        fn object_key(entity_id: Uuid, category: LongTermMemoryCategory, content: &str) -> String {
+            let mut hasher = Sha256::new();
+            hasher.update(content.as_bytes());
+            format!("{}/{}/{}", entity_id, category, hex::encode(hasher.finalize()))
+        }
+
+        fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+            let gcm = Aes256Gcm::new_from_slice(&self.config.encryption_key).expect("valid key");
+            let nonce_bytes = Uuid::new_v4().into_bytes();
+            let nonce = Nonce::from_slice(&nonce_bytes[..12]);
+            let ciphertext = gcm
+                .encrypt(
+                    nonce,
+                    Payload {
+                        msg: plaintext,
+                        aad: b"elizaos:long-term-memory:v1",
+                    },
+                )
+                .expect("encryption must succeed");
+
+            let mut out = Vec::with_capacity(12 + ciphertext.len());
+            out.extend_from_slice(&nonce_bytes[..12]);
+            out.extend_from_slice(&ciphertext);
+            out
+        }
+
+        fn decrypt(&self, blob: &[u8]) -> Result<Vec<u8>> {
+            if blob.len() < 12 {
+                return Err(MemoryError::Database(
+                    "Encrypted memory blob too short".to_string(),
+                ));
+            }
+            let (nonce_bytes, ciphertext) = blob.split_at(12);
+            let gcm = Aes256Gcm::new_from_slice(&self.config.encryption_key)
+                .map_err(|e| MemoryError::Database(format!("Invalid encryption key: {e}")))?;
+            let nonce = Nonce::from_slice(nonce_bytes);
+            gcm.decrypt(
+                nonce,
+                Payload {
+                    msg: ciphertext,
+                    aad: b"elizaos:long-term-memory:v1",
+                },
+            )
+            .map_err(|_| MemoryError::Database("Failed to decrypt memory blob".to_string()))
+        }
+
+        /// List and decrypt every object under `{entity_id}/` (optionally narrowed to one
+        /// `{entity_id}/{category}/` prefix), skipping any object that fails to download,
+        /// decrypt, or parse rather than failing the whole listing.
+        async fn list_entity(
+            &self,
+            entity_id: Uuid,
+            category: Option<LongTermMemoryCategory>,
+        ) -> Result<Vec<LongTermMemory>> {
+            let prefix = match category {
+                Some(category) => format!("{}/{}/", entity_id, category),
+                None => format!("{}/", entity_id),
+            };
+
+            let listing = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.config.bucket)
+                .prefix(&prefix)
+                .send()
+                .await
+                .map_err(|e| MemoryError::Database(format!("Failed to list memory blobs: {e}")))?;
+
+            let mut memories = Vec::new();
+            for object in listing.contents() {
+                let Some(key) = object.key() else {
+                    continue;
+                };
+
+                let response = match self
+                    .client
+                    .get_object()
+                    .bucket(&self.config.bucket)
+                    .key(key)
+                    .send()
+                    .await
+                {
+                    Ok(response) => response,
+                    Err(_) => continue,
+                };
+
+                let Ok(bytes) = response.body.collect().await else {
+                    continue;
+                };
+                let Ok(plaintext) = self.decrypt(&bytes.into_bytes()) else {
+                    continue;
+                };
+                let Ok(memory) = serde_json::from_slice::<LongTermMemory>(&plaintext) else {
+                    continue;
+                };
+
+                memories.push(memory);
+            }
+
+            Ok(memories)
+        }
+    }
+
+    #[async_trait]
+    impl LongTermMemoryStore for ObjectStoreMemoryStore {
+        async fn put(&self, memory: &LongTermMemory) -> Result<()> {
+            let key = Self::object_key(memory.entity_id, memory.category, &memory.content);
+            let plaintext = serde_json::to_vec(memory)?;
+            let ciphertext = self.encrypt(&plaintext);
+
+            self.client
+                .put_object()
+                .bucket(&self.config.bucket)
+                .key(&key)
+                .body(ByteStream::from(ciphertext))
+                .content_type("application/octet-stream")
+                .send()
+                .await
+                .map_err(|e| MemoryError::Database(format!("Failed to upload memory blob: {e}")))?;
+
+            Ok(())
+        }
+
+        async fn put_batch(&self, memories: &[LongTermMemory]) -> Result<()> {
+            // Each memory is its own object, so "batching" here means issuing the uploads
+            // concurrently over the single shared client rather than one-at-a-time.
+            let uploads = memories.iter().map(|memory| self.put(memory));
+            futures::future::try_join_all(uploads).await?;
+            Ok(())
+        }
+
+        async fn get(&self, entity_id: Uuid, id: Uuid) -> Result<Option<LongTermMemory>> {
+            // Objects are content-addressed, not ID-addressed; list the entity's memories and
+            // match on `id` rather than maintaining a separate ID index.
+            let candidates = self.list_entity(entity_id, None).await?;
+            Ok(candidates.into_iter().find(|m| m.id == id))
+        }
+
+        async fn search(
+            &self,
+            entity_id: Uuid,
+            category: Option<LongTermMemoryCategory>,
+            limit: i32,
+        ) -> Result<Vec<LongTermMemory>> {
+            let mut memories = self.list_entity(entity_id, category).await?;
+            memories.sort_by(|a, b| {
+                b.confidence
+                    .partial_cmp(&a.confidence)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            memories.truncate(limit.max(0) as usize);
+            Ok(memories)
+        }
+
+        async fn delete(&self, entity_id: Uuid, id: Uuid) -> Result<()> {
+            let memories = self.list_entity(entity_id, None).await?;
+            if let Some(memory) = memories.into_iter().find(|m| m.id == id) {
+                let key = Self::object_key(memory.entity_id, memory.category, &memory.content);
+                self.client
+                    .delete_object()
+                    .bucket(&self.config.bucket)
+                    .key(&key)
+                    .send()
+                    .await
+                    .map_err(|e| MemoryError::Database(format!("Failed to delete memory blob: {e}")))?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -3,6 +3,40 @@
 use crate::types::{ExecutionModel, RetryPolicy};
 use serde::{Deserialize, Serialize};
 
+/// Coefficient weights for taskwarrior-style urgency scoring of ready DAG steps: when more than
+/// one step is eligible to run at once, `execute_dag` sorts them by urgency descending before
+/// dispatch so steps on the critical path (or close to a deadline) tend to go first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrgencyWeights {
+    /// Coefficient applied to the step's priority score (H=6.0, M=3.9, L=1.8; unset treated as M).
+    pub priority_weight: f64,
+    /// Coefficient applied to the step's age (time since plan creation, divided by `max_age_ms`
+    /// and capped at 1.0) — older steps get pushed ahead of newer ones over time.
+    pub age_weight: f64,
+    /// The age (in milliseconds) at which the age and deadline terms saturate at their maximum
+    /// contribution.
+    pub max_age_ms: i64,
+    /// Coefficient applied to the count of not-yet-completed steps a given step directly
+    /// unblocks, rewarding steps on the critical path.
+    pub blocking_weight: f64,
+    /// Coefficient applied to proximity to `ActionStep::deadline_ms` (1.0 at or past the
+    /// deadline, scaling down to 0 at `max_age_ms` before it; steps without a deadline score 0
+    /// on this term).
+    pub deadline_weight: f64,
+}
+
+impl Default for UrgencyWeights {
+    fn default() -> Self {
+        Self {
+            priority_weight: 1.0,
+            age_weight: 1.0,
+            max_age_ms: 5 * 60 * 1000,
+            blocking_weight: 1.0,
+            deadline_weight: 1.0,
+        }
+    }
+}
+
 /// Configuration for planning service.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlanningConfig {
@@ -22,6 +56,13 @@ pub struct PlanningConfig {
     pub planning_temperature: f32,
     /// Max tokens for planning response
     pub planning_max_tokens: i32,
+    /// Maximum number of DAG steps to run concurrently
+    pub max_parallelism: usize,
+    /// Maximum number of dynamic replans (see `enable_adaptation`) a single plan execution may
+    /// trigger, to prevent a model that keeps proposing failing steps from replanning forever
+    pub max_adaptations: usize,
+    /// Coefficient weights for ordering ready DAG steps by urgency before dispatch
+    pub urgency: UrgencyWeights,
 }
 
 impl Default for PlanningConfig {
@@ -35,6 +76,9 @@ impl Default for PlanningConfig {
             planning_model_type: "TEXT_LARGE".to_string(),
             planning_temperature: 0.3,
             planning_max_tokens: 2000,
+            max_parallelism: 4,
+            max_adaptations: 3,
+            urgency: UrgencyWeights::default(),
         }
     }
 }
@@ -78,6 +122,18 @@ impl PlanningConfig {
             }
         }
 
+        if let Ok(val) = std::env::var("PLANNING_MAX_PARALLELISM") {
+            if let Ok(n) = val.parse() {
+                config.max_parallelism = n;
+            }
+        }
+
+        if let Ok(val) = std::env::var("PLANNING_MAX_ADAPTATIONS") {
+            if let Ok(n) = val.parse() {
+                config.max_adaptations = n;
+            }
+        }
+
         config
     }
 }
@@ -0,0 +1,91 @@
+//! Assert/retract fact store for reactive replanning.
+//!
+//! Recasts the dataspace model from syndicate-rs onto the planner: `execute_dag` publishes each
+//! completed step's result as an assertion, callers can publish arbitrary external events the
+//! same way, and an [`ActionStep`](crate::types::ActionStep) can declare a
+//! [`TriggerPattern`](crate::types::TriggerPattern) that must match a currently-asserted fact
+//! before the step is scheduled — in addition to, not instead of, its static `dependencies`.
+//! This lets a plan branch on conditions or wait on external events rather than only executing a
+//! static DAG.
+
+use std::collections::HashMap;
+
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::types::TriggerPattern;
+
+/// An assert/retract fact store, scoped to a single plan execution. Facts are opaque
+/// `serde_json::Value`s identified by a handle, so they can be retracted individually (e.g. an
+/// external event whose condition stops holding) without clearing everything else asserted.
+#[derive(Default)]
+pub struct Dataspace {
+    assertions: RwLock<HashMap<Uuid, serde_json::Value>>,
+}
+
+impl Dataspace {
+    /// Create an empty dataspace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assert `fact`, returning a handle that can later be passed to `retract`.
+    pub async fn assert(&self, fact: serde_json::Value) -> Uuid {
+        let handle = Uuid::new_v4();
+        self.assertions.write().await.insert(handle, fact);
+        handle
+    }
+
+    /// Withdraw a previously asserted fact. A no-op if `handle` is unknown or already retracted.
+    pub async fn retract(&self, handle: Uuid) {
+        self.assertions.write().await.remove(&handle);
+    }
+
+    /// Whether any currently-asserted fact matches `pattern`.
+    pub async fn matches(&self, pattern: &TriggerPattern) -> bool {
+        self.assertions
+            .read()
+            .await
+            .values()
+            .any(|fact| pattern.matches(fact))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_matches_sees_asserted_fact() {
+        let dataspace = Dataspace::new();
+        dataspace.assert(json!({"status": "ready"})).await;
+
+        let pattern = TriggerPattern::Literal(json!({"status": "ready"}));
+        assert!(dataspace.matches(&pattern).await);
+    }
+
+    #[tokio::test]
+    async fn test_matches_false_when_nothing_asserted() {
+        let dataspace = Dataspace::new();
+        assert!(!dataspace.matches(&TriggerPattern::Wildcard).await);
+    }
+
+    #[tokio::test]
+    async fn test_retract_removes_fact_from_matching() {
+        let dataspace = Dataspace::new();
+        let handle = dataspace.assert(json!({"status": "ready"})).await;
+
+        let pattern = TriggerPattern::Literal(json!({"status": "ready"}));
+        assert!(dataspace.matches(&pattern).await);
+
+        dataspace.retract(handle).await;
+        assert!(!dataspace.matches(&pattern).await);
+    }
+
+    #[tokio::test]
+    async fn test_retract_unknown_handle_is_a_no_op() {
+        let dataspace = Dataspace::new();
+        dataspace.retract(Uuid::new_v4()).await;
+    }
+}
@@ -2,6 +2,8 @@
 
 use thiserror::Error;
 
+use crate::types::StepErrorKind;
+
 /// Result type for planning operations.
 pub type Result<T> = std::result::Result<T, PlanningError>;
 
@@ -53,3 +55,23 @@ pub enum PlanningError {
     General(String),
 }
 
+impl PlanningError {
+    /// Classify this error for retry purposes: only `StepErrorKind::Transient` consumes a
+    /// `RetryPolicy` attempt; every other kind short-circuits regardless of `max_retries`.
+    pub fn kind(&self) -> StepErrorKind {
+        match self {
+            PlanningError::ActionNotFound(_) => StepErrorKind::ActionNotFound,
+            PlanningError::Cancelled => StepErrorKind::Cancelled,
+            PlanningError::CircularDependency
+            | PlanningError::InvalidContext(_)
+            | PlanningError::ValidationFailed(_)
+            | PlanningError::Parse(_)
+            | PlanningError::Serialization(_) => StepErrorKind::Permanent,
+            PlanningError::ExecutionFailed(_)
+            | PlanningError::Timeout
+            | PlanningError::Model(_)
+            | PlanningError::General(_) => StepErrorKind::Transient,
+        }
+    }
+}
+
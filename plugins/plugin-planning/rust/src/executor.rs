@@ -0,0 +1,310 @@
+//! Distributed step dispatch to a pool of registered workers.
+//!
+//! By default every step runs locally (the synthetic execution in [`crate::service`]). Wiring
+//! up an [`ExecutorRegistry`] of [`Worker`]s lets steps run remotely instead. Assignment follows
+//! the "task-first" philosophy used by distributed DAG schedulers like arrow-ballista: rather
+//! than iterating workers and filling each one in turn, callers iterate ready steps and ask the
+//! registry for the least-loaded alive worker that supports that step's action, so load spreads
+//! evenly across the pool as steps become ready.
+//!
+//! Liveness is heartbeat/lease based, also mirroring Ballista's scheduler-to-executor heartbeats:
+//! a worker is only eligible for assignment while its lease hasn't expired, and `heartbeat` (not
+//! just `mark_dead`) is the expected way for a worker to keep itself eligible. Each assignment is
+//! remembered by step id so a cancelled plan can propagate cancellation to the specific worker
+//! running a given step via `cancel`.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::service::Message;
+use crate::types::{ActionResult, ActionStep};
+
+/// A worker capable of executing plan steps, in-process or backed by an RPC call to a remote
+/// executor process.
+#[async_trait]
+pub trait Worker: Send + Sync {
+    /// Stable identifier for this worker, used for load tracking and logging.
+    fn id(&self) -> &str;
+    /// Whether this worker can execute `action_name`.
+    fn supports(&self, action_name: &str) -> bool;
+    /// Run `step` on this worker and return its result.
+    async fn submit_step(&self, step: &ActionStep, message: &Message) -> Result<ActionResult>;
+
+    /// Best-effort request to stop the in-progress step `step_id` (e.g. because the plan it
+    /// belongs to was cancelled). Workers that can't interrupt in-flight work may just ignore
+    /// this; the default does nothing.
+    async fn cancel_step(&self, _step_id: Uuid) {}
+}
+
+/// Default heartbeat lease: a worker that hasn't called `ExecutorRegistry::heartbeat` within this
+/// long is treated as dead for assignment purposes, even without an explicit `mark_dead`.
+const DEFAULT_LEASE: Duration = Duration::from_secs(30);
+
+struct WorkerEntry {
+    worker: Arc<dyn Worker>,
+    running: usize,
+    alive: bool,
+    lease: Duration,
+    lease_expires_at: Instant,
+}
+
+/// A worker's current load, for callers that want to observe queue depth (analogous to
+/// Ballista's PENDING/RUNNING job metrics).
+#[derive(Debug, Clone)]
+pub struct WorkerStatus {
+    /// Worker id.
+    pub id: String,
+    /// Steps currently assigned to this worker.
+    pub running: usize,
+    /// Whether the worker is alive and its lease hasn't expired.
+    pub alive: bool,
+}
+
+/// Registry of workers steps can be dispatched to. Empty by default, in which case every step
+/// runs locally.
+#[derive(Default)]
+pub struct ExecutorRegistry {
+    workers: RwLock<HashMap<String, WorkerEntry>>,
+    /// Which worker a given in-flight step was assigned to, so `cancel` can find it.
+    active_assignments: RwLock<HashMap<Uuid, String>>,
+}
+
+impl ExecutorRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `worker` with the default heartbeat lease, marking it alive with zero load.
+    pub async fn register(&self, worker: Arc<dyn Worker>) {
+        self.register_with_lease(worker, DEFAULT_LEASE).await;
+    }
+
+    /// Register `worker` with a custom heartbeat `lease`: if `heartbeat` isn't called again
+    /// within `lease`, the worker stops being eligible for assignment even though it's still in
+    /// the registry (and still shows up, marked not-alive, in `snapshot`).
+    pub async fn register_with_lease(&self, worker: Arc<dyn Worker>, lease: Duration) {
+        let id = worker.id().to_string();
+        self.workers.write().await.insert(
+            id,
+            WorkerEntry {
+                worker,
+                running: 0,
+                alive: true,
+                lease,
+                lease_expires_at: Instant::now() + lease,
+            },
+        );
+    }
+
+    /// Refresh a worker's lease. Callers should invoke this on every heartbeat received from the
+    /// worker; letting the lease lapse is how a silently-dead worker drops out of assignment
+    /// without anyone having to explicitly call `mark_dead`.
+    pub async fn heartbeat(&self, worker_id: &str) {
+        if let Some(entry) = self.workers.write().await.get_mut(worker_id) {
+            entry.lease_expires_at = Instant::now() + entry.lease;
+        }
+    }
+
+    /// Remove a worker from the registry entirely.
+    pub async fn unregister(&self, worker_id: &str) {
+        self.workers.write().await.remove(worker_id);
+    }
+
+    /// Mark a worker dead immediately so it's no longer considered for assignment (e.g. after a
+    /// failed RPC), without waiting for its lease to expire or losing it from `snapshot`.
+    pub async fn mark_dead(&self, worker_id: &str) {
+        if let Some(entry) = self.workers.write().await.get_mut(worker_id) {
+            entry.alive = false;
+        }
+    }
+
+    /// Claim the least-loaded alive, unexpired worker that supports `action_name` for `step_id`,
+    /// incrementing its load and recording the assignment so `cancel` can later reach it. Returns
+    /// `None` if no capable worker is currently registered, alive, and leased, in which case the
+    /// caller should fall back to running the step locally rather than blocking on one becoming
+    /// free.
+    pub async fn assign(
+        &self,
+        step_id: Uuid,
+        action_name: &str,
+    ) -> Option<(String, Arc<dyn Worker>)> {
+        let now = Instant::now();
+        let mut workers = self.workers.write().await;
+        let picked_id = workers
+            .iter()
+            .filter(|(_, entry)| {
+                entry.alive && entry.lease_expires_at > now && entry.worker.supports(action_name)
+            })
+            .min_by_key(|(_, entry)| entry.running)
+            .map(|(id, _)| id.clone())?;
+        let entry = workers.get_mut(&picked_id)?;
+        entry.running += 1;
+        let worker = entry.worker.clone();
+        drop(workers);
+        self.active_assignments
+            .write()
+            .await
+            .insert(step_id, picked_id.clone());
+        Some((picked_id, worker))
+    }
+
+    /// Release a worker claimed via `assign`, decrementing its load and forgetting the
+    /// assignment once `step_id` completes (successfully or not).
+    pub async fn release(&self, step_id: Uuid, worker_id: &str) {
+        if let Some(entry) = self.workers.write().await.get_mut(worker_id) {
+            entry.running = entry.running.saturating_sub(1);
+        }
+        self.active_assignments.write().await.remove(&step_id);
+    }
+
+    /// Propagate cancellation to whatever worker `step_id` is currently assigned to, if any.
+    /// A no-op if the step was never dispatched to a worker (e.g. it ran locally).
+    pub async fn cancel(&self, step_id: Uuid) {
+        let worker_id = match self.active_assignments.read().await.get(&step_id) {
+            Some(id) => id.clone(),
+            None => return,
+        };
+        let worker = self.workers.read().await.get(&worker_id).map(|e| e.worker.clone());
+        if let Some(worker) = worker {
+            worker.cancel_step(step_id).await;
+        }
+    }
+
+    /// Snapshot of every registered worker's current load, for observability.
+    pub async fn snapshot(&self) -> Vec<WorkerStatus> {
+        let now = Instant::now();
+        self.workers
+            .read()
+            .await
+            .iter()
+            .map(|(id, entry)| WorkerStatus {
+                id: id.clone(),
+                running: entry.running,
+                alive: entry.alive && entry.lease_expires_at > now,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeWorker {
+        id: String,
+        action: String,
+    }
+
+    #[async_trait]
+    impl Worker for FakeWorker {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn supports(&self, action_name: &str) -> bool {
+            self.action == action_name
+        }
+
+        async fn submit_step(&self, _step: &ActionStep, _message: &Message) -> Result<ActionResult> {
+            unimplemented!("not exercised by these tests")
+        }
+    }
+
+    fn worker(id: &str, action: &str) -> Arc<dyn Worker> {
+        Arc::new(FakeWorker {
+            id: id.to_string(),
+            action: action.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_assign_picks_least_loaded_capable_worker() {
+        let registry = ExecutorRegistry::new();
+        registry.register(worker("busy", "SEARCH")).await;
+        registry.register(worker("idle", "SEARCH")).await;
+
+        // Load up "busy" so "idle" should win the next assignment.
+        registry.assign(Uuid::new_v4(), "SEARCH").await.unwrap();
+
+        let (picked, _) = registry.assign(Uuid::new_v4(), "SEARCH").await.unwrap();
+        assert_eq!(picked, "idle");
+    }
+
+    #[tokio::test]
+    async fn test_assign_ignores_worker_that_does_not_support_action() {
+        let registry = ExecutorRegistry::new();
+        registry.register(worker("only-search", "SEARCH")).await;
+
+        assert!(registry.assign(Uuid::new_v4(), "SUMMARIZE").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_assign_skips_dead_worker() {
+        let registry = ExecutorRegistry::new();
+        registry.register(worker("w1", "SEARCH")).await;
+        registry.mark_dead("w1").await;
+
+        assert!(registry.assign(Uuid::new_v4(), "SEARCH").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_assign_skips_worker_with_expired_lease() {
+        let registry = ExecutorRegistry::new();
+        registry
+            .register_with_lease(worker("w1", "SEARCH"), Duration::from_millis(10))
+            .await;
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        assert!(registry.assign(Uuid::new_v4(), "SEARCH").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_keeps_worker_eligible_past_its_original_lease() {
+        let registry = ExecutorRegistry::new();
+        registry
+            .register_with_lease(worker("w1", "SEARCH"), Duration::from_millis(20))
+            .await;
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        registry.heartbeat("w1").await;
+        tokio::time::sleep(Duration::from_millis(15)).await;
+
+        // 25ms have elapsed since registration (more than the original 20ms lease), but the
+        // heartbeat at 10ms refreshed it, so the worker should still be assignable.
+        assert!(registry.assign(Uuid::new_v4(), "SEARCH").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_release_decrements_load_and_forgets_assignment() {
+        let registry = ExecutorRegistry::new();
+        registry.register(worker("w1", "SEARCH")).await;
+
+        let step_id = Uuid::new_v4();
+        let (worker_id, _) = registry.assign(step_id, "SEARCH").await.unwrap();
+        assert_eq!(registry.snapshot().await[0].running, 1);
+
+        registry.release(step_id, &worker_id).await;
+        assert_eq!(registry.snapshot().await[0].running, 0);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_reflects_dead_and_expired_workers() {
+        let registry = ExecutorRegistry::new();
+        registry.register(worker("alive", "SEARCH")).await;
+        registry.register(worker("dead", "SEARCH")).await;
+        registry.mark_dead("dead").await;
+
+        let snapshot = registry.snapshot().await;
+        let alive = snapshot.iter().find(|s| s.id == "alive").unwrap();
+        let dead = snapshot.iter().find(|s| s.id == "dead").unwrap();
+        assert!(alive.alive);
+        assert!(!dead.alive);
+    }
+}
@@ -0,0 +1,29 @@
+//! Opt-in flame-graph tracing for plan execution.
+//!
+//! `execute_plan`, `execute_dag`, `execute_parallel`, and `execute_step` are all
+//! `#[tracing::instrument]`ed (and per-step DAG/parallel tasks get their own manually-built span
+//! via `tracing::info_span!` + `Instrument::instrument`, since they call the static
+//! `PlanningService::execute_dag_step_with_retries` directly rather than going through the
+//! instrumented `execute_step` method). This module turns those spans into a flame graph: install
+//! the layer once at startup, run a plan, then render the output file with `inferno-flamegraph`
+//! (or any other folded-stack-compatible tool) to see which steps dominate wall-clock time and
+//! how much parallelism a DAG execution actually achieved.
+//!
+//! Disabled by default; enable the `tracing-flame` feature to use it.
+
+use std::path::Path;
+
+use tracing_flame::FlameLayer;
+use tracing_subscriber::prelude::*;
+
+/// Install a process-wide flame-graph subscriber that writes folded stack samples to
+/// `output_path`. Drop the returned guard (e.g. at the end of `main`) to flush the file to disk
+/// before exiting; an unflushed file will be empty or truncated.
+///
+/// Render the result with `inferno-flamegraph`, e.g.:
+/// `cat <output_path> | inferno-flamegraph > flamegraph.svg`.
+pub fn install(output_path: impl AsRef<Path>) -> impl Drop {
+    let (flame_layer, guard) = FlameLayer::with_file(output_path).expect("failed to create flame layer output file");
+    tracing_subscriber::registry().with(flame_layer).init();
+    guard
+}
@@ -7,6 +7,8 @@
 //!
 //! - `native` (default): Enables full async support with tokio
 //! - `wasm`: Enables WebAssembly support with JavaScript interop
+//! - `tracing-flame`: Enables [`flame::install`], which records plan execution spans to a
+//!   folded-stack file for flame-graph rendering
 //!
 //! # Example
 //!
@@ -36,20 +38,32 @@
 #![deny(unsafe_code)]
 
 pub mod config;
+pub mod dataspace;
 pub mod error;
+pub mod executor;
 pub mod service;
+pub mod state_backend;
 pub mod types;
 
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
+#[cfg(feature = "tracing-flame")]
+pub mod flame;
+
 // Re-export main types
-pub use config::PlanningConfig;
+pub use config::{PlanningConfig, UrgencyWeights};
+pub use dataspace::Dataspace;
 pub use error::{PlanningError, Result};
+pub use executor::{ExecutorRegistry, Worker, WorkerStatus};
 pub use service::PlanningService;
+pub use state_backend::{
+    InMemoryKvStore, InMemoryStateBackend, KvStateBackend, KvStore, PlanProgress, StateBackend,
+};
 pub use types::{
     ActionPlan, ActionStep, ExecutionModel, ExecutionResult, MessageClassification,
-    PlanExecutionResult, PlanState, PlanningContext, RetryPolicy,
+    PlanAdaptation, PlanExecutionResult, PlanState, PlanningContext, RetryPolicy, StepError,
+    StepErrorKind, StepStatus, TriggerPattern,
 };
 
 /// Plugin metadata
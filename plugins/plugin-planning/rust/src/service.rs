@@ -2,19 +2,32 @@
 
 use async_trait::async_trait;
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, error, info, warn};
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn, Instrument};
 use uuid::Uuid;
 
-use crate::config::PlanningConfig;
+use crate::config::{PlanningConfig, UrgencyWeights};
+use crate::dataspace::Dataspace;
 use crate::error::{PlanningError, Result};
+use crate::executor::ExecutorRegistry;
+use crate::state_backend::{InMemoryStateBackend, PlanProgress, StateBackend};
 use crate::types::{
-    ActionPlan, ActionResult, ActionStep, ExecutionModel, PlanExecutionResult, PlanState,
-    PlanningContext, RetryPolicy,
+    ActionPlan, ActionResult, ActionStep, ExecutionModel, PlanAdaptation, PlanExecutionResult,
+    PlanState, PlanningContext, RetryPolicy, StepError, StepErrorKind, StepStatus,
 };
 
+/// Default time to wait for a step's `trigger_pattern` (see `ActionStep`) to match a fact once
+/// its static `dependencies` are satisfied, if the step doesn't set its own
+/// `trigger_timeout_ms`.
+const DEFAULT_TRIGGER_TIMEOUT_MS: i64 = 5 * 60 * 1000;
+
+/// How often `execute_dag` polls the dataspace for newly-matching facts while one or more ready
+/// steps are parked waiting on a `trigger_pattern` and nothing else is in flight.
+const TRIGGER_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
 /// Runtime trait for LLM operations.
 #[async_trait]
 pub trait Runtime: Send + Sync {
@@ -53,6 +66,66 @@ struct PlanExecution {
     cancelled: bool,
 }
 
+/// Outcome broadcast to every waiter on a coalesced step. `PlanningError` isn't `Clone`, so
+/// failures are carried as their rendered message.
+type StepOutcome = std::result::Result<ActionResult, String>;
+
+/// Owns a coalesced step's `in_flight_steps` entry for the duration of the leader's dispatch and
+/// guarantees it's cleaned up no matter how the leader's task exits.
+///
+/// `execute_dag_step` calls [`Self::finish`] on the normal path, which removes the entry and
+/// broadcasts the real outcome. But if the leader's task is aborted mid-dispatch instead (a
+/// cancelled plan's `JoinSet` aborts every unfinished spawned task on drop), `finish` never runs
+/// and this guard is dropped still armed. `Drop` can't `.await` the map's lock, so it spawns a
+/// detached task that evicts the entry and broadcasts a cancellation error, waking any follower
+/// blocked on `rx.recv()` instead of leaving them waiting on a channel nobody will ever send on
+/// again.
+struct InFlightGuard {
+    key: String,
+    in_flight_steps: Arc<RwLock<HashMap<String, broadcast::Sender<StepOutcome>>>>,
+    tx: broadcast::Sender<StepOutcome>,
+    finished: bool,
+}
+
+impl InFlightGuard {
+    fn new(
+        key: String,
+        in_flight_steps: Arc<RwLock<HashMap<String, broadcast::Sender<StepOutcome>>>>,
+        tx: broadcast::Sender<StepOutcome>,
+    ) -> Self {
+        Self {
+            key,
+            in_flight_steps,
+            tx,
+            finished: false,
+        }
+    }
+
+    /// Evict the in-flight entry and broadcast the leader's real `outcome`. Disarms the guard so
+    /// `Drop` doesn't also broadcast a cancellation error afterwards.
+    async fn finish(mut self, outcome: StepOutcome) {
+        self.in_flight_steps.write().await.remove(&self.key);
+        let _ = self.tx.send(outcome);
+        self.finished = true;
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let key = self.key.clone();
+        let in_flight_steps = self.in_flight_steps.clone();
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            in_flight_steps.write().await.remove(&key);
+            let _ = tx.send(Err("Leader step execution was cancelled".to_string()));
+        });
+    }
+}
+
 /// Planning Service.
 ///
 /// Manages plan creation and execution with full runtime integration.
@@ -61,6 +134,20 @@ pub struct PlanningService {
     runtime: Option<Arc<dyn Runtime>>,
     active_plans: RwLock<HashMap<Uuid, ActionPlan>>,
     plan_executions: RwLock<HashMap<Uuid, PlanExecution>>,
+    /// Dedup layer for `idempotent` steps: identical (action name, parameters) pairs across
+    /// concurrent plans/branches share one execution instead of each running their own.
+    in_flight_steps: Arc<RwLock<HashMap<String, broadcast::Sender<StepOutcome>>>>,
+    /// Durable backing store for plans and execution progress. Defaults to
+    /// `InMemoryStateBackend` (equivalent to the old plain `RwLock<HashMap<...>>` fields);
+    /// swap it out via `with_state_backend` for crash-resumable plans.
+    state_backend: Arc<dyn StateBackend>,
+    /// Pool of remote workers steps can be dispatched to. Empty by default, in which case every
+    /// step runs locally.
+    executor_registry: Arc<ExecutorRegistry>,
+    /// Facts asserted during execution (completed step results plus any external events
+    /// published via `assert_fact`), matched against `ActionStep::trigger_pattern` to gate
+    /// reactive steps. See `crate::dataspace`.
+    dataspace: Arc<Dataspace>,
 }
 
 impl PlanningService {
@@ -73,6 +160,10 @@ impl PlanningService {
             runtime: None,
             active_plans: RwLock::new(HashMap::new()),
             plan_executions: RwLock::new(HashMap::new()),
+            in_flight_steps: Arc::new(RwLock::new(HashMap::new())),
+            state_backend: Arc::new(InMemoryStateBackend::new()),
+            executor_registry: Arc::new(ExecutorRegistry::new()),
+            dataspace: Arc::new(Dataspace::new()),
         }
     }
 
@@ -82,9 +173,38 @@ impl PlanningService {
             runtime: Some(runtime),
             active_plans: RwLock::new(HashMap::new()),
             plan_executions: RwLock::new(HashMap::new()),
+            in_flight_steps: Arc::new(RwLock::new(HashMap::new())),
+            state_backend: Arc::new(InMemoryStateBackend::new()),
+            executor_registry: Arc::new(ExecutorRegistry::new()),
+            dataspace: Arc::new(Dataspace::new()),
         }
     }
 
+    /// Swap in a durable `StateBackend` (e.g. `KvStateBackend` over a real store) so plans and
+    /// their execution progress survive a restart.
+    pub fn with_state_backend(mut self, backend: Arc<dyn StateBackend>) -> Self {
+        self.state_backend = backend;
+        self
+    }
+
+    /// Publish an external event as a fact, for steps whose `trigger_pattern` should react to
+    /// something other than another step's result. Returns a handle that can be passed to
+    /// `retract_fact` if the event later stops being true.
+    pub async fn assert_fact(&self, fact: serde_json::Value) -> Uuid {
+        self.dataspace.assert(fact).await
+    }
+
+    /// Withdraw a fact previously published via `assert_fact`.
+    pub async fn retract_fact(&self, handle: Uuid) {
+        self.dataspace.retract(handle).await;
+    }
+
+    /// Swap in an `ExecutorRegistry` of remote workers so steps can run outside this process.
+    pub fn with_executor_registry(mut self, registry: Arc<ExecutorRegistry>) -> Self {
+        self.executor_registry = registry;
+        self
+    }
+
     /// Start the service.
     pub async fn start(&self) {
         info!("PlanningService started successfully");
@@ -168,6 +288,11 @@ impl PlanningService {
                 dependencies: if i > 0 { vec![step_ids[i - 1]] } else { vec![] },
                 retry_policy: None,
                 on_error: None,
+                idempotent: action_name == "SEARCH",
+                priority: None,
+                deadline_ms: None,
+                trigger_pattern: None,
+                trigger_timeout_ms: None,
             });
         }
 
@@ -198,6 +323,11 @@ impl PlanningService {
         let mut plans: tokio::sync::RwLockWriteGuard<'_, HashMap<Uuid, ActionPlan>> =
             self.active_plans.write().await;
         plans.insert(plan_id, plan.clone());
+        drop(plans);
+
+        if let Err(e) = self.state_backend.save_plan(&plan).await {
+            warn!("[PlanningService] Failed to persist plan {}: {}", plan_id, e);
+        }
 
         debug!(
             "[PlanningService] Created simple plan {} with {} steps",
@@ -248,18 +378,128 @@ impl PlanningService {
         let mut plans: tokio::sync::RwLockWriteGuard<'_, HashMap<Uuid, ActionPlan>> =
             self.active_plans.write().await;
         plans.insert(enhanced_plan.id, enhanced_plan.clone());
+        drop(plans);
+
+        if let Err(e) = self.state_backend.save_plan(&enhanced_plan).await {
+            warn!(
+                "[PlanningService] Failed to persist plan {}: {}",
+                enhanced_plan.id, e
+            );
+        }
+
         Ok(enhanced_plan)
     }
 
-    /// Execute a plan with full runtime integration.
+    /// Execute `plan` with full runtime integration. If the state backend already has
+    /// `"running"` progress persisted for `plan.id` (e.g. this process crashed mid-execution and
+    /// was restarted with the same plan), the steps it already recorded as completed are
+    /// rehydrated from that progress and skipped instead of re-run, so only what's still pending
+    /// executes.
+    #[tracing::instrument(skip(self, plan, message), fields(plan_id = %plan.id))]
     pub async fn execute_plan(
         &self,
         plan: &ActionPlan,
         message: &Message,
+    ) -> Result<PlanExecutionResult> {
+        let completed = match self.state_backend.load_progress(plan.id).await? {
+            Some(p) if p.state.status == "running" => p.completed_results,
+            _ => HashMap::new(),
+        };
+
+        if completed.is_empty() {
+            return self.execute_plan_from(plan, message, HashMap::new()).await;
+        }
+
+        info!(
+            "[PlanningService] Rehydrating plan {} from persisted progress: {} step(s) already \
+             completed",
+            plan.id,
+            completed.len()
+        );
+
+        let remaining_steps: Vec<ActionStep> = plan
+            .steps
+            .iter()
+            .filter(|s| !completed.contains_key(&s.id))
+            .cloned()
+            .map(|mut step| {
+                step.dependencies.retain(|dep| !completed.contains_key(dep));
+                step
+            })
+            .collect();
+
+        if remaining_steps.is_empty() {
+            return Ok(PlanExecutionResult {
+                plan_id: plan.id,
+                success: true,
+                completed_steps: completed.len(),
+                total_steps: plan.steps.len(),
+                results: completed.into_values().collect(),
+                errors: None,
+                duration: 0.0,
+                adaptations: None,
+                failed_step_ids: Vec::new(),
+                skipped_step_ids: Vec::new(),
+            });
+        }
+
+        let remaining_plan = ActionPlan {
+            steps: remaining_steps,
+            ..plan.clone()
+        };
+
+        let mut tail = self
+            .execute_plan_from(&remaining_plan, message, completed)
+            .await?;
+        tail.total_steps = plan.steps.len();
+        tail.completed_steps = tail.results.len();
+        Ok(tail)
+    }
+
+    /// Reload a plan whose last persisted `PlanState::status` is `"running"` from the state
+    /// backend and hand it to `execute_plan`, which does the actual rehydration of already-
+    /// completed steps. Unlike `execute_plan`, this errors out if the persisted status isn't
+    /// `"running"` (e.g. the plan already finished or was cancelled), since resuming one of
+    /// those would silently replay it from scratch rather than continuing it.
+    pub async fn resume_plan(
+        &self,
+        plan_id: Uuid,
+        message: &Message,
+    ) -> Result<PlanExecutionResult> {
+        let plan = self
+            .state_backend
+            .load_plan(plan_id)
+            .await?
+            .ok_or_else(|| {
+                PlanningError::ExecutionFailed(format!("no persisted plan {plan_id}"))
+            })?;
+
+        if let Some(progress) = self.state_backend.load_progress(plan_id).await? {
+            if progress.state.status != "running" {
+                return Err(PlanningError::ExecutionFailed(format!(
+                    "plan {} is not resumable (status: {})",
+                    plan_id, progress.state.status
+                )));
+            }
+        }
+
+        self.execute_plan(&plan, message).await
+    }
+
+    /// Shared implementation of `execute_plan`/`resume_plan`: runs `plan`, seeding the upstream
+    /// results map with `seed_upstream` (already-completed steps from a prior run, if resuming),
+    /// and persists `PlanState`/progress at plan start, after each step, and on completion.
+    async fn execute_plan_from(
+        &self,
+        plan: &ActionPlan,
+        message: &Message,
+        seed_upstream: HashMap<Uuid, ActionResult>,
     ) -> Result<PlanExecutionResult> {
         let start_time = std::time::Instant::now();
-        let mut results: Vec<ActionResult> = Vec::new();
-        let mut errors: Vec<String> = Vec::new();
+        let mut results: Vec<ActionResult> = seed_upstream.values().cloned().collect();
+        let mut errors: Vec<StepError> = Vec::new();
+        let mut completed: HashMap<Uuid, ActionResult> = seed_upstream;
+        let mut adaptations: Vec<PlanAdaptation> = Vec::new();
 
         let execution = PlanExecution {
             state: PlanState {
@@ -274,36 +514,65 @@ impl PlanningService {
             let mut executions = self.plan_executions.write().await;
             executions.insert(plan.id, execution);
         }
+        self.persist_progress(plan.id, "running", &completed).await;
 
         let execution_result = match plan.execution_model {
             ExecutionModel::Sequential => {
-                self.execute_sequential(plan, message, &mut results, &mut errors)
-                    .await
+                self.execute_sequential(
+                    plan,
+                    message,
+                    &mut results,
+                    &mut errors,
+                    &mut completed,
+                    &mut adaptations,
+                )
+                .await
             }
             ExecutionModel::Parallel => {
                 self.execute_parallel(plan, message, &mut results, &mut errors)
                     .await
             }
             ExecutionModel::Dag => {
-                self.execute_dag(plan, message, &mut results, &mut errors)
-                    .await
+                self.execute_dag(
+                    plan,
+                    message,
+                    &mut results,
+                    &mut errors,
+                    &mut completed,
+                    &mut adaptations,
+                )
+                .await
             }
         };
 
         let duration = start_time.elapsed().as_millis() as f64;
+        let final_status = if errors.is_empty() {
+            "completed"
+        } else {
+            "failed"
+        };
 
         {
             let mut executions = self.plan_executions.write().await;
             if let Some(exec) = executions.get_mut(&plan.id) {
-                exec.state.status = if errors.is_empty() {
-                    "completed".to_string()
-                } else {
-                    "failed".to_string()
-                };
+                exec.state.status = final_status.to_string();
                 exec.state.end_time = Some(chrono::Utc::now().timestamp_millis());
             }
             executions.remove(&plan.id);
         }
+        self.persist_progress(plan.id, final_status, &completed)
+            .await;
+
+        let failed_step_ids: Vec<Uuid> = errors
+            .iter()
+            .filter(|e| e.kind != StepErrorKind::DependencyFailed)
+            .map(|e| e.step_id)
+            .collect();
+        let skipped_step_ids: Vec<Uuid> = errors
+            .iter()
+            .filter(|e| e.kind == StepErrorKind::DependencyFailed)
+            .map(|e| e.step_id)
+            .collect();
 
         let result = PlanExecutionResult {
             plan_id: plan.id,
@@ -317,7 +586,13 @@ impl PlanningService {
                 Some(errors)
             },
             duration,
-            adaptations: None,
+            failed_step_ids,
+            skipped_step_ids,
+            adaptations: if adaptations.is_empty() {
+                None
+            } else {
+                Some(adaptations)
+            },
         };
 
         info!(
@@ -328,6 +603,110 @@ impl PlanningService {
         Ok(result)
     }
 
+    /// Persist `plan_id`'s current execution progress so a crash can be resumed via
+    /// `resume_plan`. Best-effort: a backend error is logged and otherwise ignored, since losing
+    /// durability shouldn't fail an in-progress execution.
+    async fn persist_progress(
+        &self,
+        plan_id: Uuid,
+        status: &str,
+        completed: &HashMap<Uuid, ActionResult>,
+    ) {
+        let progress = PlanProgress {
+            state: PlanState {
+                status: status.to_string(),
+                current_step_index: completed.len(),
+                ..Default::default()
+            },
+            completed_results: completed.clone(),
+        };
+        if let Err(e) = self.state_backend.save_progress(plan_id, &progress).await {
+            warn!(
+                "[PlanningService] Failed to persist progress for plan {}: {}",
+                plan_id, e
+            );
+        }
+    }
+
+    /// Whether a non-aborting step failure should trigger a replan: adaptation must be enabled,
+    /// and this execution mustn't have already used up its `max_adaptations` budget.
+    async fn should_adapt(&self, adaptations_so_far: usize) -> bool {
+        let config = self.config.read().await;
+        config.enable_adaptation && adaptations_so_far < config.max_adaptations
+    }
+
+    /// Feed `failed_step`'s failure (plus the goal and every result completed so far) back
+    /// through the planning model to get a revised set of downstream steps. Returns `None` if
+    /// there's no runtime to ask, the model call fails, or the response doesn't parse into any
+    /// usable steps — callers should keep running the original plan in that case.
+    async fn adapt_plan(
+        &self,
+        plan: &ActionPlan,
+        failed_step: &ActionStep,
+        error: &str,
+        completed: &HashMap<Uuid, ActionResult>,
+    ) -> Option<Vec<ActionStep>> {
+        let runtime = self.runtime.as_ref()?;
+
+        let completed_summary: Vec<serde_json::Value> = completed
+            .values()
+            .map(|r| serde_json::json!({ "text": r.text, "data": r.data }))
+            .collect();
+
+        let available_actions: Vec<String> = plan
+            .steps
+            .iter()
+            .map(|s| s.action_name.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let adaptation_context = PlanningContext {
+            goal: format!(
+                "The original goal was: \"{}\". Step '{}' failed with error: {}. Completed \
+                 results so far: {}. Propose the remaining steps still needed to achieve the \
+                 original goal, working around this failure.",
+                plan.goal,
+                failed_step.action_name,
+                error,
+                serde_json::json!(completed_summary),
+            ),
+            constraints: Vec::new(),
+            available_actions,
+            available_providers: Vec::new(),
+            preferences: None,
+        };
+
+        let prompt = self.build_planning_prompt(&adaptation_context, None);
+        let (model_type, temperature, max_tokens) = {
+            let config = self.config.read().await;
+            (
+                config.planning_model_type.clone(),
+                config.planning_temperature,
+                config.planning_max_tokens,
+            )
+        };
+        let response = runtime
+            .use_model(
+                &model_type,
+                serde_json::json!({
+                    "prompt": prompt,
+                    "temperature": temperature,
+                    "maxTokens": max_tokens,
+                }),
+            )
+            .await
+            .ok()?;
+
+        let revised = self
+            .parse_planning_response(&response, &adaptation_context)
+            .ok()?;
+        if revised.steps.is_empty() {
+            return None;
+        }
+        Some(revised.steps)
+    }
+
     pub async fn validate_plan(&self, plan: &ActionPlan) -> (bool, Option<Vec<String>>) {
         let mut issues: Vec<String> = Vec::new();
 
@@ -348,8 +727,8 @@ impl PlanningService {
                 let actions = runtime.get_actions();
                 if !actions.contains(&step.action_name) {
                     issues.push(format!(
-                        "Action '{}' not found in runtime",
-                        step.action_name
+                        "{}",
+                        PlanningError::ActionNotFound(step.action_name.clone())
                     ));
                 }
             }
@@ -492,6 +871,7 @@ Focus on:
         let plan_id = Uuid::new_v4();
         let mut steps: Vec<ActionStep> = Vec::new();
         let mut step_id_map: HashMap<String, Uuid> = HashMap::new();
+        let mut raw_dependencies: Vec<Vec<String>> = Vec::new();
 
         let step_regex = Regex::new(r"<step>(.*?)</step>")
             .map_err(|e| PlanningError::Parse(format!("Failed to compile regex: {}", e)))?;
@@ -534,18 +914,28 @@ Focus on:
                     id: actual_id,
                     action_name: action,
                     parameters,
-                    dependencies: Vec::new(), // Will be resolved later
+                    dependencies: Vec::new(), // Resolved below, once every step's id is known
                     retry_policy: Some(RetryPolicy::default()),
                     on_error: None,
+                    idempotent: false,
+                    priority: None,
+                    deadline_ms: None,
+                    trigger_pattern: None,
+                    trigger_timeout_ms: None,
                 });
-
-                // Store for later dependency resolution
-                if !dependency_strings.is_empty() {
-                    // We'll handle this in a second pass
-                }
+                raw_dependencies.push(dependency_strings);
             }
         }
 
+        // Second pass: resolve each step's `<dependencies>` (the `step_N` labels used in the
+        // LLM response) into real `Uuid` edges now that `step_id_map` is fully populated.
+        for (step, dep_strings) in steps.iter_mut().zip(raw_dependencies.iter()) {
+            step.dependencies = dep_strings
+                .iter()
+                .filter_map(|dep| step_id_map.get(dep).copied())
+                .collect();
+        }
+
         // If no steps found, create fallback
         if steps.is_empty() {
             steps.push(ActionStep {
@@ -562,6 +952,11 @@ Focus on:
                 dependencies: Vec::new(),
                 retry_policy: Some(RetryPolicy::default()),
                 on_error: None,
+                idempotent: false,
+                priority: None,
+                deadline_ms: None,
+                trigger_pattern: None,
+                trigger_timeout_ms: None,
             });
 
             if context.goal.to_lowercase().contains("plan")
@@ -584,6 +979,11 @@ Focus on:
                     dependencies: vec![first_id],
                     retry_policy: Some(RetryPolicy::default()),
                     on_error: None,
+                    idempotent: false,
+                    priority: None,
+                    deadline_ms: None,
+                    trigger_pattern: None,
+                    trigger_timeout_ms: None,
                 });
 
                 steps.push(ActionStep {
@@ -600,6 +1000,11 @@ Focus on:
                     dependencies: vec![second_id],
                     retry_policy: Some(RetryPolicy::default()),
                     on_error: None,
+                    idempotent: false,
+                    priority: None,
+                    deadline_ms: None,
+                    trigger_pattern: None,
+                    trigger_timeout_ms: None,
                 });
             }
         }
@@ -663,9 +1068,16 @@ Focus on:
         plan: &ActionPlan,
         message: &Message,
         results: &mut Vec<ActionResult>,
-        errors: &mut Vec<String>,
+        errors: &mut Vec<StepError>,
+        completed: &mut HashMap<Uuid, ActionResult>,
+        adaptations: &mut Vec<PlanAdaptation>,
     ) -> Result<()> {
-        for (i, step) in plan.steps.iter().enumerate() {
+        // A `VecDeque` (rather than iterating `plan.steps` directly) so a replan can splice new
+        // steps into what's left to run without touching steps already completed.
+        let mut remaining: VecDeque<ActionStep> = plan.steps.iter().cloned().collect();
+        let mut completed_count = 0;
+
+        while let Some(step) = remaining.pop_front() {
             {
                 let executions = self.plan_executions.read().await;
                 if let Some(exec) = executions.get(&plan.id) {
@@ -675,21 +1087,78 @@ Focus on:
                 }
             }
 
-            match self.execute_step(step, message, results).await {
+            match self.execute_step(&step, message, completed).await {
                 Ok(result) => {
+                    completed.insert(step.id, result.clone());
                     results.push(result);
-                    let mut executions = self.plan_executions.write().await;
-                    if let Some(exec) = executions.get_mut(&plan.id) {
-                        exec.state.current_step_index = i + 1;
+                    completed_count += 1;
+                    {
+                        let mut executions = self.plan_executions.write().await;
+                        if let Some(exec) = executions.get_mut(&plan.id) {
+                            exec.state.current_step_index = completed_count;
+                        }
                     }
+                    self.persist_progress(plan.id, "running", completed).await;
                 }
                 Err(e) => {
-                    error!("[PlanningService] Step {} failed: {}", step.id, e);
-                    errors.push(format!("{}", e));
-                    if step.on_error.as_deref() == Some("abort")
-                        || step.retry_policy.as_ref().map(|p| p.on_error.as_str()) == Some("abort")
-                    {
-                        return Err(e);
+                    error!("[PlanningService] Step {} failed: {}", step.id, e.message);
+                    let should_abort = step.on_error.as_deref() == Some("abort")
+                        || step.retry_policy.as_ref().map(|p| p.on_error.as_str()) == Some("abort");
+                    errors.push(e.clone());
+                    if should_abort {
+                        return Err(PlanningError::ExecutionFailed(e.message));
+                    }
+
+                    if self.should_adapt(adaptations.len()).await {
+                        if let Some(new_steps) =
+                            self.adapt_plan(plan, &step, &e.message, completed).await
+                        {
+                            let removed_step_ids: Vec<Uuid> = remaining
+                                .iter()
+                                .filter(|s| s.dependencies.contains(&step.id))
+                                .map(|s| s.id)
+                                .collect();
+
+                            let candidate_steps: Vec<ActionStep> = remaining
+                                .iter()
+                                .filter(|s| !removed_step_ids.contains(&s.id))
+                                .cloned()
+                                .chain(new_steps.iter().cloned())
+                                .collect();
+                            let candidate_plan = ActionPlan {
+                                steps: candidate_steps,
+                                ..plan.clone()
+                            };
+
+                            let (valid, issues) = self.validate_plan(&candidate_plan).await;
+                            if valid {
+                                let inserted_step_ids: Vec<Uuid> =
+                                    new_steps.iter().map(|s| s.id).collect();
+                                remaining.retain(|s| !removed_step_ids.contains(&s.id));
+                                remaining.extend(new_steps);
+
+                                info!(
+                                    "[PlanningService] Adapted plan {} after step {} failed: \
+                                     +{} steps, -{} steps",
+                                    plan.id,
+                                    step.id,
+                                    inserted_step_ids.len(),
+                                    removed_step_ids.len()
+                                );
+                                adaptations.push(PlanAdaptation {
+                                    failed_step_id: step.id,
+                                    reason: e.message.clone(),
+                                    inserted_step_ids,
+                                    removed_step_ids,
+                                    timestamp: chrono::Utc::now().timestamp_millis(),
+                                });
+                            } else {
+                                warn!(
+                                    "[PlanningService] Discarding invalid adaptation for plan {}: {:?}",
+                                    plan.id, issues
+                                );
+                            }
+                        }
                     }
                 }
             }
@@ -697,129 +1166,719 @@ Focus on:
         Ok(())
     }
 
+    #[tracing::instrument(skip(self, plan, message, results, errors), fields(plan_id = %plan.id))]
     async fn execute_parallel(
         &self,
         plan: &ActionPlan,
-        _message: &Message,
+        message: &Message,
         results: &mut Vec<ActionResult>,
-        errors: &mut Vec<String>,
+        errors: &mut Vec<StepError>,
     ) -> Result<()> {
         let mut handles = Vec::new();
 
         for step in &plan.steps {
             let step_clone = step.clone();
+            let message_clone = message.clone();
+            let in_flight_steps = self.in_flight_steps.clone();
+            let executor_registry = self.executor_registry.clone();
+            let span = tracing::info_span!(
+                "execute_dag_step",
+                plan_id = %plan.id,
+                step_id = %step_clone.id,
+                action_name = %step_clone.action_name,
+            );
 
-            handles.push(tokio::spawn(async move {
-                // Simplified execution for parallel
-                Ok::<ActionResult, PlanningError>(ActionResult {
-                    text: format!("Executed {}", step_clone.action_name),
-                    data: {
-                        let mut d = HashMap::new();
-                        d.insert(
-                            "stepId".to_string(),
-                            serde_json::json!(step_clone.id.to_string()),
-                        );
-                        d.insert(
-                            "actionName".to_string(),
-                            serde_json::json!(step_clone.action_name),
-                        );
-                        d
-                    },
-                })
-            }));
+            handles.push(tokio::spawn(
+                async move {
+                    Self::execute_dag_step_with_retries(
+                        &step_clone,
+                        &message_clone,
+                        &HashMap::new(),
+                        &in_flight_steps,
+                        &executor_registry,
+                    )
+                    .await
+                }
+                .instrument(span),
+            ));
         }
 
         for handle in handles {
             match handle.await {
                 Ok(Ok(result)) => results.push(result),
-                Ok(Err(e)) => {
-                    errors.push(format!("{}", e));
-                }
-                Err(e) => errors.push(format!("Task join error: {}", e)),
+                Ok(Err(e)) => errors.push(e),
+                Err(e) => errors.push(StepError {
+                    step_id: Uuid::nil(),
+                    action_name: String::new(),
+                    kind: StepErrorKind::Transient,
+                    message: format!("Task join error: {}", e),
+                }),
             }
         }
 
         Ok(())
     }
 
+    /// Run `plan.steps` as a real DAG: track each step's in-degree and status
+    /// (`Pending`/`Ready`/`Running`/`Completed`/`Failed`), seed the ready queue with every
+    /// zero-in-degree step, and keep up to `max_parallelism` of them in flight at once via a
+    /// `JoinSet`. Each time a step finishes, its `ActionResult` is recorded in `completed` (so
+    /// dependents can read it back out of their own execution, and so progress survives a crash
+    /// via `persist_progress`) and the in-degree of its dependents is decremented, pushing any
+    /// that hit zero straight onto the ready queue. A step whose `on_error` (or
+    /// `retry_policy.on_error`) is `"abort"` marks every step that transitively depends on it as
+    /// `Skipped` without scheduling them, while independent branches keep running to completion.
+    ///
+    /// Note this dispatches continuously rather than strictly level-by-level: as soon as a slot
+    /// frees up, the next ready step (from this wave or, once its own dependencies clear, a
+    /// later one) fills it immediately, instead of waiting for every step in the current wave to
+    /// finish before starting the next. For a wide, unevenly-sized DAG this keeps
+    /// `max_parallelism` workers saturated throughout, which is strictly less idle time than
+    /// draining one full wave before advancing to the next.
+    #[tracing::instrument(skip(self, plan, message, results, errors, completed, adaptations), fields(plan_id = %plan.id))]
     async fn execute_dag(
         &self,
         plan: &ActionPlan,
         message: &Message,
         results: &mut Vec<ActionResult>,
-        errors: &mut Vec<String>,
+        errors: &mut Vec<StepError>,
+        completed: &mut HashMap<Uuid, ActionResult>,
+        adaptations: &mut Vec<PlanAdaptation>,
     ) -> Result<()> {
-        let mut completed: HashSet<Uuid> = HashSet::new();
-        let mut pending: HashSet<Uuid> = plan.steps.iter().map(|s| s.id).collect();
+        let max_parallelism = self.config.read().await.max_parallelism.max(1);
+        let plan_created_at = plan
+            .metadata
+            .get("createdAt")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0);
+
+        let mut steps_by_id: HashMap<Uuid, ActionStep> =
+            plan.steps.iter().map(|s| (s.id, s.clone())).collect();
+        let mut in_degree: HashMap<Uuid, usize> = HashMap::new();
+        let mut dependents: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        let mut status: HashMap<Uuid, StepStatus> = HashMap::new();
+
+        for step in &plan.steps {
+            in_degree.insert(step.id, step.dependencies.len());
+            status.insert(step.id, StepStatus::Pending);
+            for dep in &step.dependencies {
+                dependents.entry(*dep).or_default().push(step.id);
+            }
+        }
+
+        let mut ready: VecDeque<Uuid> = VecDeque::new();
+        // Steps whose `dependencies` are satisfied but whose `trigger_pattern` hasn't matched a
+        // fact yet, each mapped to the epoch-millisecond deadline by which it must match.
+        let mut pending_trigger: HashMap<Uuid, i64> = HashMap::new();
+        for step in &plan.steps {
+            if step.dependencies.is_empty() {
+                Self::try_ready_step(
+                    step.id,
+                    &steps_by_id,
+                    &mut status,
+                    &mut ready,
+                    &mut pending_trigger,
+                    &self.dataspace,
+                )
+                .await;
+            }
+        }
+
+        let mut in_flight: JoinSet<(Uuid, std::result::Result<ActionResult, StepError>)> =
+            JoinSet::new();
+        let mut scheduled: HashSet<Uuid> = HashSet::new();
 
-        while !pending.is_empty() {
+        loop {
             {
                 let executions = self.plan_executions.read().await;
                 if let Some(exec) = executions.get(&plan.id) {
                     if exec.cancelled {
+                        for (step_id, step_status) in status.iter() {
+                            if *step_status == StepStatus::Running {
+                                self.executor_registry.cancel(*step_id).await;
+                            }
+                        }
                         return Err(PlanningError::Cancelled);
                     }
                 }
             }
 
-            let ready_steps: Vec<&ActionStep> = plan
-                .steps
-                .iter()
-                .filter(|step| {
-                    pending.contains(&step.id)
-                        && step.dependencies.iter().all(|dep| completed.contains(dep))
-                })
-                .collect();
+            if !pending_trigger.is_empty() {
+                let now = chrono::Utc::now().timestamp_millis();
+                let mut newly_ready = Vec::new();
+                let mut timed_out = Vec::new();
+                for (&step_id, &deadline) in pending_trigger.iter() {
+                    let pattern = steps_by_id[&step_id]
+                        .trigger_pattern
+                        .as_ref()
+                        .expect("pending_trigger only holds steps with a trigger_pattern");
+                    if self.dataspace.matches(pattern).await {
+                        newly_ready.push(step_id);
+                    } else if now >= deadline {
+                        timed_out.push(step_id);
+                    }
+                }
+                for step_id in newly_ready {
+                    pending_trigger.remove(&step_id);
+                    status.insert(step_id, StepStatus::Ready);
+                    ready.push_back(step_id);
+                }
+                for step_id in timed_out {
+                    pending_trigger.remove(&step_id);
+                    let step = steps_by_id[&step_id].clone();
+                    let trigger_error = StepError {
+                        step_id,
+                        action_name: step.action_name.clone(),
+                        kind: StepErrorKind::Transient,
+                        message: format!(
+                            "Step {} timed out waiting for its trigger_pattern to match",
+                            step_id
+                        ),
+                    };
+                    errors.push(trigger_error.clone());
+                    status.insert(step_id, StepStatus::Failed);
+
+                    let should_abort = step.on_error.as_deref() == Some("abort")
+                        || step.retry_policy.as_ref().map(|p| p.on_error.as_str())
+                            == Some("abort");
+                    if should_abort {
+                        Self::skip_dependents(step_id, &dependents, &mut status, &steps_by_id, errors);
+                    } else if let Some(next_steps) = dependents.get(&step_id) {
+                        for next_id in next_steps.clone() {
+                            if let Some(remaining) = in_degree.get_mut(&next_id) {
+                                if *remaining > 0 {
+                                    *remaining -= 1;
+                                    if *remaining == 0
+                                        && status.get(&next_id) == Some(&StepStatus::Pending)
+                                    {
+                                        Self::try_ready_step(
+                                            next_id,
+                                            &steps_by_id,
+                                            &mut status,
+                                            &mut ready,
+                                            &mut pending_trigger,
+                                            &self.dataspace,
+                                        )
+                                        .await;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
 
-            if ready_steps.is_empty() {
-                return Err(PlanningError::CircularDependency);
+            if ready.len() > 1 {
+                let weights = self.config.read().await.urgency.clone();
+                let now = chrono::Utc::now().timestamp_millis();
+                let mut ready_vec: Vec<Uuid> = ready.drain(..).collect();
+                ready_vec.sort_by(|a, b| {
+                    let urgency_a = Self::step_urgency(
+                        &steps_by_id[a],
+                        plan_created_at,
+                        now,
+                        dependents.get(a).map(|d| d.len()).unwrap_or(0),
+                        &weights,
+                    );
+                    let urgency_b = Self::step_urgency(
+                        &steps_by_id[b],
+                        plan_created_at,
+                        now,
+                        dependents.get(b).map(|d| d.len()).unwrap_or(0),
+                        &weights,
+                    );
+                    urgency_b
+                        .partial_cmp(&urgency_a)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                ready = ready_vec.into();
             }
 
-            for step in ready_steps {
-                match self.execute_step(step, message, results).await {
-                    Ok(result) => {
-                        results.push(result);
-                        pending.remove(&step.id);
-                        completed.insert(step.id);
+            while in_flight.len() < max_parallelism {
+                let Some(step_id) = ready.pop_front() else {
+                    break;
+                };
+                if !scheduled.insert(step_id) {
+                    continue;
+                }
+                status.insert(step_id, StepStatus::Running);
+                let step = steps_by_id[&step_id].clone();
+                let step_message = message.clone();
+                let step_upstream = completed.clone();
+                let in_flight_steps = self.in_flight_steps.clone();
+                let executor_registry = self.executor_registry.clone();
+                let span = tracing::info_span!(
+                    "execute_dag_step",
+                    plan_id = %plan.id,
+                    step_id = %step_id,
+                    action_name = %step.action_name,
+                );
+                in_flight.spawn(
+                    async move {
+                        let result = Self::execute_dag_step_with_retries(
+                            &step,
+                            &step_message,
+                            &step_upstream,
+                            &in_flight_steps,
+                            &executor_registry,
+                        )
+                        .await;
+                        (step_id, result)
                     }
-                    Err(e) => {
-                        errors.push(format!("{}", e));
-                        pending.remove(&step.id);
-                        completed.insert(step.id);
+                    .instrument(span),
+                );
+            }
+
+            if in_flight.is_empty() && ready.is_empty() {
+                if pending_trigger.is_empty() {
+                    break;
+                }
+                // Nothing is running and nothing is dispatchable, but one or more steps are
+                // still waiting on a trigger_pattern that hasn't matched (or timed out) yet —
+                // poll again shortly rather than mistaking this for plan completion.
+                tokio::time::sleep(TRIGGER_POLL_INTERVAL).await;
+                continue;
+            }
+
+            let Some(joined) = in_flight.join_next().await else {
+                break;
+            };
+            let (step_id, step_result) =
+                joined.map_err(|e| PlanningError::ExecutionFailed(format!("task join error: {e}")))?;
+
+            match step_result {
+                Ok(result) => {
+                    status.insert(step_id, StepStatus::Completed);
+                    completed.insert(step_id, result.clone());
+
+                    let step = &steps_by_id[&step_id];
+                    self.dataspace
+                        .assert(serde_json::json!({
+                            "kind": "step_completed",
+                            "step_id": step_id.to_string(),
+                            "action_name": step.action_name,
+                            "result": &result,
+                        }))
+                        .await;
+                    results.push(result);
+
+                    if let Some(next_steps) = dependents.get(&step_id) {
+                        for next_id in next_steps.clone() {
+                            if let Some(remaining) = in_degree.get_mut(&next_id) {
+                                if *remaining > 0 {
+                                    *remaining -= 1;
+                                    if *remaining == 0
+                                        && status.get(&next_id) == Some(&StepStatus::Pending)
+                                    {
+                                        Self::try_ready_step(
+                                            next_id,
+                                            &steps_by_id,
+                                            &mut status,
+                                            &mut ready,
+                                            &mut pending_trigger,
+                                            &self.dataspace,
+                                        )
+                                        .await;
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    self.persist_progress(plan.id, "running", completed).await;
+                }
+                Err(e) => {
+                    errors.push(e.clone());
+                    status.insert(step_id, StepStatus::Failed);
+
+                    let step = steps_by_id[&step_id].clone();
+                    let should_abort = step.on_error.as_deref() == Some("abort")
+                        || step.retry_policy.as_ref().map(|p| p.on_error.as_str())
+                            == Some("abort");
+
+                    if should_abort {
+                        Self::skip_dependents(step_id, &dependents, &mut status, &steps_by_id, errors);
+                    } else {
+                        if let Some(next_steps) = dependents.get(&step_id) {
+                            for next_id in next_steps.clone() {
+                                if let Some(remaining) = in_degree.get_mut(&next_id) {
+                                    if *remaining > 0 {
+                                        *remaining -= 1;
+                                        if *remaining == 0
+                                            && status.get(&next_id) == Some(&StepStatus::Pending)
+                                        {
+                                            Self::try_ready_step(
+                                                next_id,
+                                                &steps_by_id,
+                                                &mut status,
+                                                &mut ready,
+                                                &mut pending_trigger,
+                                                &self.dataspace,
+                                            )
+                                            .await;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        if self.should_adapt(adaptations.len()).await {
+                            if let Some(new_steps) =
+                                self.adapt_plan(plan, &step, &e.message, completed).await
+                            {
+                                let candidate_plan = ActionPlan {
+                                    steps: steps_by_id
+                                        .values()
+                                        .cloned()
+                                        .chain(new_steps.iter().cloned())
+                                        .collect(),
+                                    ..plan.clone()
+                                };
+                                let (valid, issues) = self.validate_plan(&candidate_plan).await;
+
+                                if valid {
+                                    let inserted_step_ids: Vec<Uuid> =
+                                        new_steps.iter().map(|s| s.id).collect();
+                                    for new_step in new_steps {
+                                        let new_id = new_step.id;
+                                        in_degree.insert(new_id, new_step.dependencies.len());
+                                        status.insert(new_id, StepStatus::Pending);
+                                        for dep in &new_step.dependencies {
+                                            dependents.entry(*dep).or_default().push(new_id);
+                                        }
+                                        let is_seed = new_step.dependencies.is_empty();
+                                        steps_by_id.insert(new_id, new_step);
+                                        if is_seed {
+                                            Self::try_ready_step(
+                                                new_id,
+                                                &steps_by_id,
+                                                &mut status,
+                                                &mut ready,
+                                                &mut pending_trigger,
+                                                &self.dataspace,
+                                            )
+                                            .await;
+                                        }
+                                    }
+
+                                    info!(
+                                        "[PlanningService] Adapted plan {} after step {} failed: +{} steps",
+                                        plan.id,
+                                        step_id,
+                                        inserted_step_ids.len()
+                                    );
+                                    adaptations.push(PlanAdaptation {
+                                        failed_step_id: step_id,
+                                        reason: e.message.clone(),
+                                        inserted_step_ids,
+                                        removed_step_ids: Vec::new(),
+                                        timestamp: chrono::Utc::now().timestamp_millis(),
+                                    });
+                                } else {
+                                    warn!(
+                                        "[PlanningService] Discarding invalid adaptation for plan {}: {:?}",
+                                        plan.id, issues
+                                    );
+                                }
+                            }
+                        }
                     }
                 }
             }
         }
 
+        if status.values().any(|s| *s == StepStatus::Pending) {
+            return Err(PlanningError::CircularDependency);
+        }
+
         Ok(())
     }
 
+    /// Mark `step_id`'s dependents (and their dependents, transitively) as `Failed` without
+    /// ever scheduling them, recording why each one was skipped.
+    fn skip_dependents(
+        step_id: Uuid,
+        dependents: &HashMap<Uuid, Vec<Uuid>>,
+        status: &mut HashMap<Uuid, StepStatus>,
+        steps_by_id: &HashMap<Uuid, ActionStep>,
+        errors: &mut Vec<StepError>,
+    ) {
+        let Some(next_steps) = dependents.get(&step_id) else {
+            return;
+        };
+        for next_id in next_steps.clone() {
+            if status.get(&next_id) == Some(&StepStatus::Skipped) {
+                continue;
+            }
+            status.insert(next_id, StepStatus::Skipped);
+            errors.push(StepError {
+                step_id: next_id,
+                action_name: steps_by_id
+                    .get(&next_id)
+                    .map(|s| s.action_name.clone())
+                    .unwrap_or_default(),
+                kind: StepErrorKind::DependencyFailed,
+                message: format!(
+                    "Step {} skipped because dependency {} aborted",
+                    next_id, step_id
+                ),
+            });
+            Self::skip_dependents(next_id, dependents, status, steps_by_id, errors);
+        }
+    }
+
+    /// Attempt to ready `step_id` once its static `dependencies` are satisfied: if it has no
+    /// `trigger_pattern`, it's immediately marked `Ready` and queued, exactly as before
+    /// reactive triggers existed. If it has one, it's only queued now if the pattern already
+    /// matches a fact currently asserted into `dataspace`; otherwise it's parked in
+    /// `pending_trigger` with a deadline, and `execute_dag`'s main loop polls it on every
+    /// iteration until it matches or times out.
+    async fn try_ready_step(
+        step_id: Uuid,
+        steps_by_id: &HashMap<Uuid, ActionStep>,
+        status: &mut HashMap<Uuid, StepStatus>,
+        ready: &mut VecDeque<Uuid>,
+        pending_trigger: &mut HashMap<Uuid, i64>,
+        dataspace: &Dataspace,
+    ) {
+        let step = &steps_by_id[&step_id];
+        let Some(pattern) = &step.trigger_pattern else {
+            status.insert(step_id, StepStatus::Ready);
+            ready.push_back(step_id);
+            return;
+        };
+        if dataspace.matches(pattern).await {
+            status.insert(step_id, StepStatus::Ready);
+            ready.push_back(step_id);
+        } else {
+            let timeout_ms = step.trigger_timeout_ms.unwrap_or(DEFAULT_TRIGGER_TIMEOUT_MS);
+            pending_trigger.insert(step_id, chrono::Utc::now().timestamp_millis() + timeout_ms);
+        }
+    }
+
+    #[tracing::instrument(
+        skip(self, step, message, upstream),
+        fields(step_id = %step.id, action_name = %step.action_name)
+    )]
     async fn execute_step(
         &self,
         step: &ActionStep,
-        _message: &Message,
-        _previous_results: &[ActionResult],
+        message: &Message,
+        upstream: &HashMap<Uuid, ActionResult>,
+    ) -> std::result::Result<ActionResult, StepError> {
+        Self::execute_dag_step_with_retries(
+            step,
+            message,
+            upstream,
+            &self.in_flight_steps,
+            &self.executor_registry,
+        )
+        .await
+    }
+
+    /// Taskwarrior-style urgency score for `step`: a weighted sum of its priority, age since
+    /// plan creation, how many not-yet-completed dependents it directly unblocks, and proximity
+    /// to its deadline (if any). `execute_dag` sorts `ready` by this, descending, before picking
+    /// steps to dispatch; the sort is stable so ties keep insertion order.
+    fn step_urgency(
+        step: &ActionStep,
+        plan_created_at: i64,
+        now: i64,
+        blocking_count: usize,
+        weights: &UrgencyWeights,
+    ) -> f64 {
+        let priority_score = match step.priority.as_deref() {
+            Some("H") | Some("high") => 6.0,
+            Some("L") | Some("low") => 1.8,
+            _ => 3.9,
+        };
+
+        let max_age_ms = weights.max_age_ms.max(1) as f64;
+        let age_score = ((now - plan_created_at).max(0) as f64 / max_age_ms).min(1.0);
+        let deadline_score = step
+            .deadline_ms
+            .map(|deadline| (1.0 - (deadline - now) as f64 / max_age_ms).clamp(0.0, 1.0))
+            .unwrap_or(0.0);
+
+        weights.priority_weight * priority_score
+            + weights.age_weight * age_score
+            + weights.blocking_weight * blocking_count as f64
+            + weights.deadline_weight * deadline_score
+    }
+
+    /// A stable dedup key for `step`: its action name plus its parameters serialized with
+    /// sorted keys, so two steps with the same arguments in different insertion order still
+    /// coalesce. Returns `None` for non-`idempotent` steps, which always run on their own.
+    fn stable_step_key(step: &ActionStep) -> Option<String> {
+        if !step.idempotent {
+            return None;
+        }
+        let mut entries: Vec<(&String, &serde_json::Value)> = step.parameters.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        let canonical = serde_json::to_string(&entries).ok()?;
+        Some(format!("{}:{}", step.action_name, canonical))
+    }
+
+    /// Build the (synthetic) result of running `step`, exposing any dependency outputs found in
+    /// `upstream` so a step can read its upstream results back out of its own parameters. When
+    /// `step.idempotent` is set, identical in-flight steps (by `stable_step_key`) coalesce onto
+    /// a single execution: the first caller becomes the leader and runs the step, subsequent
+    /// callers just await its `broadcast` outcome. The in-flight entry is evicted as soon as the
+    /// leader finishes (or is cancelled, via [`InFlightGuard`]), so later calls re-run rather
+    /// than replaying a stale result; if the leader is cancelled before it sends an outcome,
+    /// waiting followers are woken with an error instead of hanging forever.
+    async fn execute_dag_step(
+        step: &ActionStep,
+        message: &Message,
+        upstream: &HashMap<Uuid, ActionResult>,
+        in_flight_steps: &Arc<RwLock<HashMap<String, broadcast::Sender<StepOutcome>>>>,
+        executor_registry: &Arc<ExecutorRegistry>,
     ) -> Result<ActionResult> {
-        let result = ActionResult {
-            text: format!("Executed {}", step.action_name),
-            data: {
-                let mut d = HashMap::new();
-                d.insert("stepId".to_string(), serde_json::json!(step.id.to_string()));
-                d.insert(
-                    "actionName".to_string(),
-                    serde_json::json!(step.action_name),
-                );
-                d.insert(
-                    "executedAt".to_string(),
-                    serde_json::json!(chrono::Utc::now().timestamp_millis()),
-                );
-                d
-            },
+        let Some(key) = Self::stable_step_key(step) else {
+            return Self::dispatch_step(step, message, upstream, executor_registry).await;
         };
 
-        Ok(result)
+        if let Some(mut rx) = in_flight_steps.read().await.get(&key).map(|tx| tx.subscribe()) {
+            return match rx.recv().await {
+                Ok(outcome) => outcome.map_err(PlanningError::ExecutionFailed),
+                Err(_) => Self::dispatch_step(step, message, upstream, executor_registry).await,
+            };
+        }
+
+        let (tx, _rx) = broadcast::channel(1);
+        {
+            let mut in_flight = in_flight_steps.write().await;
+            if let Some(existing) = in_flight.get(&key) {
+                let mut rx = existing.subscribe();
+                drop(in_flight);
+                return match rx.recv().await {
+                    Ok(outcome) => outcome.map_err(PlanningError::ExecutionFailed),
+                    Err(_) => Self::dispatch_step(step, message, upstream, executor_registry).await,
+                };
+            }
+            in_flight.insert(key.clone(), tx.clone());
+        }
+
+        let guard = InFlightGuard::new(key.clone(), in_flight_steps.clone(), tx);
+
+        let result = Self::dispatch_step(step, message, upstream, executor_registry).await;
+        let outcome: StepOutcome = match &result {
+            Ok(r) => Ok(r.clone()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        guard.finish(outcome).await;
+
+        result
     }
 
+    /// Run `step` via `execute_dag_step`, retrying with exponential backoff while the failure
+    /// classifies as `StepErrorKind::Transient` and `step.retry_policy`'s `max_retries` hasn't
+    /// been exhausted. Any other kind (or an exhausted retry budget) returns immediately as a
+    /// classified `StepError` instead of a raw `PlanningError`.
+    async fn execute_dag_step_with_retries(
+        step: &ActionStep,
+        message: &Message,
+        upstream: &HashMap<Uuid, ActionResult>,
+        in_flight_steps: &Arc<RwLock<HashMap<String, broadcast::Sender<StepOutcome>>>>,
+        executor_registry: &Arc<ExecutorRegistry>,
+    ) -> std::result::Result<ActionResult, StepError> {
+        let retry_policy = step.retry_policy.clone().unwrap_or_default();
+        let max_retries = retry_policy.max_retries.max(0) as usize;
+        let mut backoff_ms = retry_policy.backoff_ms.max(0) as u64;
+        let mut attempt = 0;
+
+        loop {
+            match Self::execute_dag_step(step, message, upstream, in_flight_steps, executor_registry)
+                .await
+            {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    let kind = e.kind();
+                    if kind != StepErrorKind::Transient || attempt >= max_retries {
+                        return Err(StepError {
+                            step_id: step.id,
+                            action_name: step.action_name.clone(),
+                            kind,
+                            message: e.to_string(),
+                        });
+                    }
+                    attempt += 1;
+                    warn!(
+                        "[PlanningService] Step {} failed transiently (attempt {}/{}): {} — \
+                         retrying in {}ms",
+                        step.id, attempt, max_retries, e, backoff_ms
+                    );
+                    tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
+                    backoff_ms = (backoff_ms as f64 * retry_policy.backoff_multiplier) as u64;
+                }
+            }
+        }
+    }
+
+    /// Run `step` on a registered worker if one is free to take it (least-loaded alive worker
+    /// supporting `step.action_name`, per `ExecutorRegistry::assign`'s task-first assignment),
+    /// otherwise fall back to the local synthetic execution in `run_step`.
+    async fn dispatch_step(
+        step: &ActionStep,
+        message: &Message,
+        upstream: &HashMap<Uuid, ActionResult>,
+        executor_registry: &Arc<ExecutorRegistry>,
+    ) -> Result<ActionResult> {
+        if let Some((worker_id, worker)) = executor_registry.assign(step.id, &step.action_name).await {
+            let result = worker.submit_step(step, message).await;
+            executor_registry.release(step.id, &worker_id).await;
+            return result;
+        }
+
+        Self::run_step(step, upstream).await
+    }
+
+    /// Actually "execute" `step` (a synthetic result in this mock runtime), exposing any
+    /// dependency outputs found in `upstream` so a step can read its upstream results back out
+    /// of its own parameters.
+    async fn run_step(
+        step: &ActionStep,
+        upstream: &HashMap<Uuid, ActionResult>,
+    ) -> Result<ActionResult> {
+        let mut data = HashMap::new();
+        data.insert("stepId".to_string(), serde_json::json!(step.id.to_string()));
+        data.insert(
+            "actionName".to_string(),
+            serde_json::json!(step.action_name),
+        );
+        data.insert(
+            "executedAt".to_string(),
+            serde_json::json!(chrono::Utc::now().timestamp_millis()),
+        );
+
+        let upstream_results: HashMap<String, serde_json::Value> = step
+            .dependencies
+            .iter()
+            .filter_map(|dep_id| {
+                upstream
+                    .get(dep_id)
+                    .map(|r| (dep_id.to_string(), serde_json::json!(r.data)))
+            })
+            .collect();
+        if !upstream_results.is_empty() {
+            data.insert(
+                "upstreamResults".to_string(),
+                serde_json::json!(upstream_results),
+            );
+        }
+
+        Ok(ActionResult {
+            text: format!("Executed {}", step.action_name),
+            data,
+        })
+    }
+
+    /// Static cycle check over `dependencies` edges only. A `trigger_pattern` can't introduce a
+    /// cycle this catches (it gates *readiness*, not the dependency graph), so a plan where two
+    /// steps' patterns each wait on a fact the other would produce isn't flagged here — that
+    /// case is instead bounded by each step's `trigger_timeout_ms` in `execute_dag`, which fails
+    /// the step rather than letting it wait forever.
     fn detect_cycles(&self, steps: &[ActionStep]) -> bool {
         let mut visited: HashSet<Uuid> = HashSet::new();
         let mut recursion_stack: HashSet<Uuid> = HashSet::new();
@@ -880,3 +1939,105 @@ Focus on:
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_step(action_name: &str, parameters: HashMap<String, serde_json::Value>) -> ActionStep {
+        ActionStep {
+            id: Uuid::new_v4(),
+            action_name: action_name.to_string(),
+            parameters,
+            dependencies: vec![],
+            retry_policy: None,
+            on_error: None,
+            idempotent: false,
+            priority: None,
+            deadline_ms: None,
+            trigger_pattern: None,
+            trigger_timeout_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_step_urgency_orders_high_priority_above_low() {
+        let weights = UrgencyWeights::default();
+        let mut high = sample_step("A", HashMap::new());
+        high.priority = Some("H".to_string());
+        let mut low = sample_step("B", HashMap::new());
+        low.priority = Some("L".to_string());
+
+        let urgency_high = PlanningService::step_urgency(&high, 0, 0, 0, &weights);
+        let urgency_low = PlanningService::step_urgency(&low, 0, 0, 0, &weights);
+        assert!(urgency_high > urgency_low);
+    }
+
+    #[test]
+    fn test_step_urgency_rewards_steps_closer_to_their_deadline() {
+        let weights = UrgencyWeights::default();
+        let mut near_deadline = sample_step("A", HashMap::new());
+        near_deadline.deadline_ms = Some(1000);
+        let mut no_deadline = sample_step("B", HashMap::new());
+        no_deadline.deadline_ms = None;
+
+        let urgency_near = PlanningService::step_urgency(&near_deadline, 0, 1000, 0, &weights);
+        let urgency_none = PlanningService::step_urgency(&no_deadline, 0, 1000, 0, &weights);
+        assert!(urgency_near > urgency_none);
+    }
+
+    #[test]
+    fn test_step_urgency_rewards_steps_blocking_more_dependents() {
+        let weights = UrgencyWeights::default();
+        let step = sample_step("A", HashMap::new());
+
+        let urgency_many_deps = PlanningService::step_urgency(&step, 0, 0, 5, &weights);
+        let urgency_no_deps = PlanningService::step_urgency(&step, 0, 0, 0, &weights);
+        assert!(urgency_many_deps > urgency_no_deps);
+    }
+
+    #[test]
+    fn test_stable_step_key_none_for_non_idempotent_step() {
+        let step = sample_step("SEARCH", HashMap::new());
+        assert!(PlanningService::stable_step_key(&step).is_none());
+    }
+
+    #[test]
+    fn test_stable_step_key_matches_regardless_of_parameter_insertion_order() {
+        let mut params_a = HashMap::new();
+        params_a.insert("query".to_string(), serde_json::json!("eliza"));
+        params_a.insert("limit".to_string(), serde_json::json!(10));
+
+        let mut params_b = HashMap::new();
+        params_b.insert("limit".to_string(), serde_json::json!(10));
+        params_b.insert("query".to_string(), serde_json::json!("eliza"));
+
+        let mut step_a = sample_step("SEARCH", params_a);
+        step_a.idempotent = true;
+        let mut step_b = sample_step("SEARCH", params_b);
+        step_b.idempotent = true;
+
+        assert_eq!(
+            PlanningService::stable_step_key(&step_a),
+            PlanningService::stable_step_key(&step_b)
+        );
+    }
+
+    #[test]
+    fn test_stable_step_key_differs_for_different_parameters() {
+        let mut params_a = HashMap::new();
+        params_a.insert("query".to_string(), serde_json::json!("eliza"));
+        let mut params_b = HashMap::new();
+        params_b.insert("query".to_string(), serde_json::json!("other"));
+
+        let mut step_a = sample_step("SEARCH", params_a);
+        step_a.idempotent = true;
+        let mut step_b = sample_step("SEARCH", params_b);
+        step_b.idempotent = true;
+
+        assert_ne!(
+            PlanningService::stable_step_key(&step_a),
+            PlanningService::stable_step_key(&step_b)
+        );
+    }
+}
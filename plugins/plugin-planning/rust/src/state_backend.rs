@@ -0,0 +1,301 @@
+//! Pluggable persistence for plan durability and resume.
+//!
+//! `PlanningService` keeps its working copy of plans and execution progress in memory, but
+//! routes every read/write through a [`StateBackend`] so that copy can be backed by durable
+//! storage instead. This mirrors the scheduler state store found in distributed DAG engines
+//! (e.g. Ballista): plans and their progress are saved at step boundaries, so a crashed process
+//! can reload `PlanState::status == "running"` plans via [`StateBackend::list_incomplete`] and
+//! resume them with [`crate::service::PlanningService::resume_plan`].
+//!
+//! This crate ships two backends: [`InMemoryStateBackend`] (the default, equivalent to the old
+//! `RwLock<HashMap<...>>` fields) and [`KvStateBackend`], which serializes plans/progress as
+//! JSON into any [`KvStore`]. A durable `KvStore` (Redis, sled, a SQL table) can be plugged in
+//! without any other change to this crate; only [`InMemoryKvStore`] is provided here.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::types::{ActionPlan, ActionResult, PlanState};
+
+/// A plan's execution progress: its current [`PlanState`] plus the results of every step that
+/// has completed so far, keyed by step id. Enough to resume without re-running finished steps.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct PlanProgress {
+    /// Current execution state.
+    pub state: PlanState,
+    /// Results of steps that have already completed, keyed by step id.
+    pub completed_results: HashMap<Uuid, ActionResult>,
+}
+
+/// Durable storage for plans and their execution progress.
+#[async_trait]
+pub trait StateBackend: Send + Sync {
+    /// Persist (or overwrite) a plan.
+    async fn save_plan(&self, plan: &ActionPlan) -> Result<()>;
+    /// Load a previously saved plan, if one exists.
+    async fn load_plan(&self, plan_id: Uuid) -> Result<Option<ActionPlan>>;
+    /// Remove a plan and its progress.
+    async fn delete_plan(&self, plan_id: Uuid) -> Result<()>;
+
+    /// Persist (or overwrite) a plan's execution progress.
+    async fn save_progress(&self, plan_id: Uuid, progress: &PlanProgress) -> Result<()>;
+    /// Load a plan's last-saved execution progress, if any.
+    async fn load_progress(&self, plan_id: Uuid) -> Result<Option<PlanProgress>>;
+
+    /// IDs of plans whose last-saved progress has `PlanState::status == "running"` (i.e. they
+    /// were interrupted mid-execution, typically by a crash or restart).
+    async fn list_incomplete(&self) -> Result<Vec<Uuid>>;
+}
+
+/// In-memory [`StateBackend`]: the default, equivalent to keeping plans/progress in a
+/// `RwLock<HashMap<...>>` directly on the service. Nothing survives a process restart.
+#[derive(Default)]
+pub struct InMemoryStateBackend {
+    plans: RwLock<HashMap<Uuid, ActionPlan>>,
+    progress: RwLock<HashMap<Uuid, PlanProgress>>,
+}
+
+impl InMemoryStateBackend {
+    /// Create an empty in-memory backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl StateBackend for InMemoryStateBackend {
+    async fn save_plan(&self, plan: &ActionPlan) -> Result<()> {
+        self.plans.write().await.insert(plan.id, plan.clone());
+        Ok(())
+    }
+
+    async fn load_plan(&self, plan_id: Uuid) -> Result<Option<ActionPlan>> {
+        Ok(self.plans.read().await.get(&plan_id).cloned())
+    }
+
+    async fn delete_plan(&self, plan_id: Uuid) -> Result<()> {
+        self.plans.write().await.remove(&plan_id);
+        self.progress.write().await.remove(&plan_id);
+        Ok(())
+    }
+
+    async fn save_progress(&self, plan_id: Uuid, progress: &PlanProgress) -> Result<()> {
+        self.progress
+            .write()
+            .await
+            .insert(plan_id, progress.clone());
+        Ok(())
+    }
+
+    async fn load_progress(&self, plan_id: Uuid) -> Result<Option<PlanProgress>> {
+        Ok(self.progress.read().await.get(&plan_id).cloned())
+    }
+
+    async fn list_incomplete(&self) -> Result<Vec<Uuid>> {
+        Ok(self
+            .progress
+            .read()
+            .await
+            .iter()
+            .filter(|(_, p)| p.state.status == "running")
+            .map(|(id, _)| *id)
+            .collect())
+    }
+}
+
+/// A minimal byte-oriented key/value store that [`KvStateBackend`] serializes plan state into.
+/// Implement this against a real store (Redis, sled, a SQL table) to get durable plan storage;
+/// this crate ships only [`InMemoryKvStore`].
+#[async_trait]
+pub trait KvStore: Send + Sync {
+    /// Fetch the bytes stored at `key`, if any.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    /// Store `value` at `key`, overwriting any existing entry.
+    async fn set(&self, key: &str, value: Vec<u8>) -> Result<()>;
+    /// Remove the entry at `key`, if any.
+    async fn delete(&self, key: &str) -> Result<()>;
+    /// List every key currently starting with `prefix`.
+    async fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>>;
+}
+
+/// In-memory [`KvStore`] reference implementation, used by [`KvStateBackend`] in tests and as a
+/// drop-in stand-in before a durable store is wired up.
+#[derive(Default)]
+pub struct InMemoryKvStore {
+    entries: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+impl InMemoryKvStore {
+    /// Create an empty in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl KvStore for InMemoryKvStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.entries.read().await.get(key).cloned())
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        self.entries.write().await.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.entries.write().await.remove(key);
+        Ok(())
+    }
+
+    async fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .entries
+            .read()
+            .await
+            .keys()
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+}
+
+/// [`StateBackend`] that serializes plans and progress as JSON into any [`KvStore`], so the
+/// backing store can be swapped out without touching `PlanningService`.
+pub struct KvStateBackend {
+    store: Arc<dyn KvStore>,
+}
+
+impl KvStateBackend {
+    /// Wrap `store` as a [`StateBackend`].
+    pub fn new(store: Arc<dyn KvStore>) -> Self {
+        Self { store }
+    }
+
+    fn plan_key(plan_id: Uuid) -> String {
+        format!("plan:{plan_id}")
+    }
+
+    fn progress_key(plan_id: Uuid) -> String {
+        format!("progress:{plan_id}")
+    }
+}
+
+#[async_trait]
+impl StateBackend for KvStateBackend {
+    async fn save_plan(&self, plan: &ActionPlan) -> Result<()> {
+        let bytes = serde_json::to_vec(plan)?;
+        self.store.set(&Self::plan_key(plan.id), bytes).await
+    }
+
+    async fn load_plan(&self, plan_id: Uuid) -> Result<Option<ActionPlan>> {
+        match self.store.get(&Self::plan_key(plan_id)).await? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_plan(&self, plan_id: Uuid) -> Result<()> {
+        self.store.delete(&Self::plan_key(plan_id)).await?;
+        self.store.delete(&Self::progress_key(plan_id)).await
+    }
+
+    async fn save_progress(&self, plan_id: Uuid, progress: &PlanProgress) -> Result<()> {
+        let bytes = serde_json::to_vec(progress)?;
+        self.store.set(&Self::progress_key(plan_id), bytes).await
+    }
+
+    async fn load_progress(&self, plan_id: Uuid) -> Result<Option<PlanProgress>> {
+        match self.store.get(&Self::progress_key(plan_id)).await? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list_incomplete(&self) -> Result<Vec<Uuid>> {
+        let mut incomplete = Vec::new();
+        for key in self.store.keys_with_prefix("progress:").await? {
+            let Some(id_str) = key.strip_prefix("progress:") else {
+                continue;
+            };
+            let Ok(plan_id) = Uuid::parse_str(id_str) else {
+                continue;
+            };
+            if let Some(bytes) = self.store.get(&key).await? {
+                if let Ok(progress) = serde_json::from_slice::<PlanProgress>(&bytes) {
+                    if progress.state.status == "running" {
+                        incomplete.push(plan_id);
+                    }
+                }
+            }
+        }
+        Ok(incomplete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_plan() -> ActionPlan {
+        ActionPlan {
+            id: Uuid::new_v4(),
+            goal: "test goal".to_string(),
+            steps: vec![],
+            execution_model: crate::types::ExecutionModel::Sequential,
+            state: PlanState::default(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn in_memory_backend_round_trips_plan_and_progress() {
+        let backend = InMemoryStateBackend::new();
+        let plan = sample_plan();
+
+        backend.save_plan(&plan).await.unwrap();
+        let loaded = backend.load_plan(plan.id).await.unwrap().unwrap();
+        assert_eq!(loaded.id, plan.id);
+
+        let mut progress = PlanProgress {
+            state: PlanState {
+                status: "running".to_string(),
+                ..Default::default()
+            },
+            completed_results: HashMap::new(),
+        };
+        backend.save_progress(plan.id, &progress).await.unwrap();
+        assert_eq!(backend.list_incomplete().await.unwrap(), vec![plan.id]);
+
+        progress.state.status = "completed".to_string();
+        backend.save_progress(plan.id, &progress).await.unwrap();
+        assert!(backend.list_incomplete().await.unwrap().is_empty());
+
+        backend.delete_plan(plan.id).await.unwrap();
+        assert!(backend.load_plan(plan.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn kv_backend_round_trips_plan_and_progress() {
+        let backend = KvStateBackend::new(Arc::new(InMemoryKvStore::new()));
+        let plan = sample_plan();
+
+        backend.save_plan(&plan).await.unwrap();
+        let loaded = backend.load_plan(plan.id).await.unwrap().unwrap();
+        assert_eq!(loaded.goal, plan.goal);
+
+        let progress = PlanProgress {
+            state: PlanState {
+                status: "running".to_string(),
+                ..Default::default()
+            },
+            completed_results: HashMap::new(),
+        };
+        backend.save_progress(plan.id, &progress).await.unwrap();
+        assert_eq!(backend.list_incomplete().await.unwrap(), vec![plan.id]);
+    }
+}
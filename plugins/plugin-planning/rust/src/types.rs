@@ -52,6 +52,55 @@ impl std::fmt::Display for ExecutionModel {
     }
 }
 
+/// Execution status of a single step during DAG scheduling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// Waiting on one or more unfinished dependencies.
+    Pending,
+    /// All dependencies satisfied; eligible to be scheduled.
+    Ready,
+    /// Currently executing.
+    Running,
+    /// Finished successfully.
+    Completed,
+    /// Finished with an error (after exhausting any retries).
+    Failed,
+    /// Never ran because an upstream dependency aborted the branch it's on.
+    Skipped,
+}
+
+/// Classification of a step failure, driving whether `RetryPolicy` spends a retry attempt on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepErrorKind {
+    /// Likely to succeed on retry (e.g. a model or network hiccup). Only these consume a
+    /// `RetryPolicy` attempt.
+    Transient,
+    /// Won't succeed no matter how many times it's retried (e.g. invalid input). Short-circuits
+    /// immediately regardless of `max_retries`.
+    Permanent,
+    /// The plan (or this step) was cancelled.
+    Cancelled,
+    /// Skipped because a dependency this step needed failed and aborted the branch.
+    DependencyFailed,
+    /// The step named an action the runtime doesn't recognize.
+    ActionNotFound,
+}
+
+/// A classified step failure, as recorded in `PlanExecutionResult::errors`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepError {
+    /// The step that failed.
+    pub step_id: Uuid,
+    /// Its action name, for readability without cross-referencing the plan.
+    pub action_name: String,
+    /// Whether this failure is worth retrying.
+    pub kind: StepErrorKind,
+    /// Rendered error message.
+    pub message: String,
+}
+
 /// Retry policy for action steps.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetryPolicy {
@@ -76,6 +125,42 @@ impl Default for RetryPolicy {
     }
 }
 
+/// A structured-value pattern matched against facts asserted into a
+/// [`Dataspace`](crate::dataspace::Dataspace), gating an [`ActionStep`] that declares one via
+/// `trigger_pattern`. Modeled on syndicate-rs's pattern language, scaled down to what plan
+/// triggers need: wildcards, field binding, and nested object matching.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerPattern {
+    /// Matches any fact.
+    Wildcard,
+    /// Matches a fact equal to this literal value.
+    Literal(serde_json::Value),
+    /// Matches any fact, binding it under `name`. Behaves like `Wildcard` for matching purposes;
+    /// the binding only matters to callers that want to read back what satisfied the trigger.
+    Binding(String),
+    /// Matches a JSON object containing (at least) these named fields, each satisfying its own
+    /// sub-pattern. Extra fields on the fact are ignored. Fails to match anything that isn't a
+    /// JSON object.
+    Object(HashMap<String, TriggerPattern>),
+}
+
+impl TriggerPattern {
+    /// Whether `fact` satisfies this pattern.
+    pub fn matches(&self, fact: &serde_json::Value) -> bool {
+        match self {
+            Self::Wildcard | Self::Binding(_) => true,
+            Self::Literal(expected) => fact == expected,
+            Self::Object(fields) => match fact.as_object() {
+                Some(map) => fields
+                    .iter()
+                    .all(|(key, sub)| map.get(key).is_some_and(|v| sub.matches(v))),
+                None => false,
+            },
+        }
+    }
+}
+
 /// Action step in a plan.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionStep {
@@ -95,6 +180,31 @@ pub struct ActionStep {
     /// Error handling behavior
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub on_error: Option<String>,
+    /// Whether this step is safe to coalesce: if another in-flight step has the same
+    /// `action_name` and identical `parameters`, both executions share one result instead of
+    /// running twice. Only set this for actions with no side effects (e.g. `SEARCH`).
+    #[serde(default)]
+    pub idempotent: bool,
+    /// Scheduling priority ("H", "M", or "L"), feeding into ready-step urgency ordering (see
+    /// `PlanningConfig::urgency`). Unset is treated the same as "M".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// Optional absolute deadline in epoch milliseconds. Steps at or past their deadline score
+    /// maximum urgency on the deadline term; steps without one score zero on it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_ms: Option<i64>,
+    /// Reactive gate: once `dependencies` are satisfied, this step only becomes `Ready` when
+    /// this pattern additionally matches a fact currently asserted into the plan's `Dataspace`
+    /// (see `crate::dataspace`). `None` means the step is gated purely by `dependencies`, as
+    /// before this field existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_pattern: Option<TriggerPattern>,
+    /// How long, in milliseconds from when `dependencies` are satisfied, to wait for
+    /// `trigger_pattern` to match before giving up and failing the step (as a transient error,
+    /// so retry policy or plan adaptation can take over) rather than waiting forever. Ignored if
+    /// `trigger_pattern` is `None`. Unset defaults to 5 minutes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_timeout_ms: Option<i64>,
 }
 
 /// Plan execution state.
@@ -207,15 +317,41 @@ pub struct PlanExecutionResult {
     /// Results from each step
     #[serde(default)]
     pub results: Vec<ActionResult>,
-    /// Errors encountered
+    /// Classified errors encountered, one per failed step
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub errors: Option<Vec<String>>,
+    pub errors: Option<Vec<StepError>>,
     /// Duration in milliseconds
     #[serde(default)]
     pub duration: f64,
-    /// Adaptations made during execution
+    /// Dynamic replans triggered by non-aborting step failures during execution
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub adaptations: Option<Vec<String>>,
+    pub adaptations: Option<Vec<PlanAdaptation>>,
+    /// IDs of steps that actually ran and errored (a subset of `errors`; excludes dependency
+    /// skips, see `skipped_step_ids`)
+    #[serde(default)]
+    pub failed_step_ids: Vec<Uuid>,
+    /// IDs of steps never run because an upstream dependency they needed aborted the branch
+    #[serde(default)]
+    pub skipped_step_ids: Vec<Uuid>,
+}
+
+/// Record of a single dynamic-replanning intervention: a step failed without aborting the plan,
+/// so its failure (plus everything completed so far) was fed back through the planning model to
+/// produce revised downstream steps, which were spliced into the remaining plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanAdaptation {
+    /// The step whose failure triggered this replan.
+    pub failed_step_id: Uuid,
+    /// Why the replan was triggered (the failed step's rendered error).
+    pub reason: String,
+    /// Steps added to the plan by this replan.
+    pub inserted_step_ids: Vec<Uuid>,
+    /// Steps dropped from the remaining plan by this replan (e.g. stale downstream steps that
+    /// depended on the failed step).
+    #[serde(default)]
+    pub removed_step_ids: Vec<Uuid>,
+    /// When this replan happened.
+    pub timestamp: i64,
 }
 
 /// Result from a single action.
@@ -73,7 +73,7 @@ impl Action for ClearHistoryAction {
             .or_else(|| message.get("agent_id").and_then(|a| a.as_str()))
             .unwrap_or("default");
 
-        service.clear_command_history(conversation_id);
+        service.clear_command_history(conversation_id).await;
 
         ActionResult {
             success: true,
@@ -0,0 +1,208 @@
+#![allow(missing_docs)]
+//! Sandboxed container execution backend.
+//!
+//! When `ShellConfig::backend` is `ShellBackend::Container`, commands run
+//! inside an ephemeral container instead of directly on the host: a
+//! container is created from the configured image with `allowed_directory`
+//! bind-mounted as its working directory, the command is `exec`'d inside it,
+//! and the container is removed once the command completes (or is killed on
+//! timeout). Modeled on the container lifecycle `shiplift` exposes.
+
+use std::path::Path;
+use std::time::Duration;
+
+use bollard::container::{Config, LogOutput, RemoveContainerOptions};
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use bollard::models::HostConfig;
+use bollard::Docker;
+use futures::StreamExt;
+use tokio::time::timeout;
+
+use crate::error::ShellError;
+use crate::types::CommandResult;
+use crate::Result;
+
+/// Working directory inside the container that `allowed_directory` is
+/// bind-mounted to.
+const CONTAINER_WORKDIR: &str = "/workspace";
+
+/// Run `command` inside an ephemeral container built from `image`, with
+/// `allowed_directory` bind-mounted at [`CONTAINER_WORKDIR`]. The container
+/// is always removed before returning, success or failure.
+pub async fn run_in_container(
+    image: &str,
+    mounts: &[String],
+    network: Option<&str>,
+    memory_limit: Option<&str>,
+    command: &str,
+    allowed_directory: &Path,
+    timeout_ms: u64,
+) -> Result<CommandResult> {
+    let docker = Docker::connect_with_local_defaults()
+        .map_err(|e| ShellError::ExecutionFailed(format!("failed to connect to Docker: {e}")))?;
+
+    let mut binds = vec![format!(
+        "{}:{}",
+        allowed_directory.display(),
+        CONTAINER_WORKDIR
+    )];
+    binds.extend(mounts.iter().cloned());
+
+    let host_config = HostConfig {
+        binds: Some(binds),
+        network_mode: network.map(|n| n.to_string()),
+        memory: memory_limit.and_then(parse_memory_limit),
+        ..Default::default()
+    };
+
+    let container = docker
+        .create_container::<String, _>(
+            None,
+            Config {
+                image: Some(image.to_string()),
+                working_dir: Some(CONTAINER_WORKDIR.to_string()),
+                host_config: Some(host_config),
+                tty: Some(false),
+                // Keep the container alive between `exec`s; the actual
+                // command runs via `exec_create`/`exec_start` below.
+                cmd: Some(vec!["sleep".to_string(), "infinity".to_string()]),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| ShellError::ExecutionFailed(format!("failed to create container: {e}")))?;
+
+    let result = run_and_await(&docker, &container.id, command, timeout_ms).await;
+
+    let _ = docker
+        .remove_container(
+            &container.id,
+            Some(RemoveContainerOptions {
+                force: true,
+                ..Default::default()
+            }),
+        )
+        .await;
+
+    result
+}
+
+async fn run_and_await(
+    docker: &Docker,
+    container_id: &str,
+    command: &str,
+    timeout_ms: u64,
+) -> Result<CommandResult> {
+    docker
+        .start_container::<String>(container_id, None)
+        .await
+        .map_err(|e| ShellError::ExecutionFailed(format!("failed to start container: {e}")))?;
+
+    let exec_and_collect = async {
+        let exec = docker
+            .create_exec(
+                container_id,
+                CreateExecOptions {
+                    cmd: Some(vec!["sh".to_string(), "-c".to_string(), command.to_string()]),
+                    attach_stdout: Some(true),
+                    attach_stderr: Some(true),
+                    tty: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to create exec: {e}")))?;
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        if let StartExecResults::Attached { mut output, .. } = docker
+            .start_exec(&exec.id, None)
+            .await
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to start exec: {e}")))?
+        {
+            // The attach stream is multiplexed frame-by-frame (an 8-byte
+            // header per frame: stream-type byte, 3 reserved zero bytes,
+            // then a big-endian u32 payload length); bollard parses that
+            // header for us into `LogOutput`, so we just bucket each frame's
+            // payload by stream type.
+            while let Some(frame) = output.next().await {
+                match frame.map_err(|e| {
+                    ShellError::ExecutionFailed(format!("exec stream error: {e}"))
+                })? {
+                    LogOutput::StdOut { message } => stdout.extend_from_slice(&message),
+                    LogOutput::StdErr { message } => stderr.extend_from_slice(&message),
+                    _ => {}
+                }
+            }
+        }
+
+        let inspect = docker
+            .inspect_exec(&exec.id)
+            .await
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to inspect exec: {e}")))?;
+
+        Ok::<_, ShellError>((stdout, stderr, inspect.exit_code.map(|c| c as i32)))
+    };
+
+    match timeout(Duration::from_millis(timeout_ms), exec_and_collect).await {
+        Ok(Ok((stdout, stderr, exit_code))) => Ok(CommandResult {
+            success: exit_code == Some(0),
+            stdout: String::from_utf8_lossy(&stdout).to_string(),
+            stderr: String::from_utf8_lossy(&stderr).to_string(),
+            exit_code,
+            error: None,
+            executed_in: CONTAINER_WORKDIR.to_string(),
+        }),
+        Ok(Err(e)) => Ok(CommandResult::error(
+            &e.to_string(),
+            &e.to_string(),
+            CONTAINER_WORKDIR,
+        )),
+        Err(_) => {
+            let _ = docker.kill_container::<String>(container_id, None).await;
+            Ok(CommandResult {
+                success: false,
+                stdout: String::new(),
+                stderr: "Command timed out".to_string(),
+                exit_code: None,
+                error: Some("Command execution timeout".to_string()),
+                executed_in: CONTAINER_WORKDIR.to_string(),
+            })
+        }
+    }
+}
+
+/// Parse a Docker-style memory limit string (`"512m"`, `"1g"`, `"2048k"`, or
+/// a bare byte count) into bytes.
+fn parse_memory_limit(limit: &str) -> Option<i64> {
+    let lower = limit.to_lowercase();
+    let (digits, multiplier) = if let Some(n) = lower.strip_suffix('g') {
+        (n, 1024 * 1024 * 1024)
+    } else if let Some(n) = lower.strip_suffix('m') {
+        (n, 1024 * 1024)
+    } else if let Some(n) = lower.strip_suffix('k') {
+        (n, 1024)
+    } else {
+        (lower.as_str(), 1)
+    };
+    digits.parse::<i64>().ok().map(|n| n * multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory_limit_suffixes() {
+        assert_eq!(parse_memory_limit("512m"), Some(512 * 1024 * 1024));
+        assert_eq!(parse_memory_limit("1g"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_memory_limit("2048k"), Some(2048 * 1024));
+        assert_eq!(parse_memory_limit("100"), Some(100));
+    }
+
+    #[test]
+    fn test_parse_memory_limit_invalid() {
+        assert_eq!(parse_memory_limit("lots"), None);
+    }
+}
@@ -40,6 +40,10 @@ pub enum ShellError {
     /// Configuration error
     #[error("Configuration error: {0}")]
     Config(String),
+
+    /// Failed to (de)serialize a message to/from a remote shell daemon
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
 }
 
 /// Result type alias
@@ -0,0 +1,141 @@
+#![allow(missing_docs)]
+//! Pluggable persistence for command history.
+//!
+//! `ShellService` always keeps a bounded in-memory window of recent
+//! commands per conversation (see `command_history` in `service.rs`), which
+//! is lost on restart. A `CommandHistoryStore` lets that window be backed
+//! by something durable instead, so a later session (or another agent) can
+//! still audit what an earlier shell session did. `ShellService::new`
+//! leaves this unset; `ShellService::with_history_store` wires one in.
+//!
+//! This crate ships only `InMemoryHistoryStore`, an unbounded reference
+//! implementation useful for tests and for composing with other stores. A
+//! durable implementation (e.g. one backed by a SQL `command_history`
+//! table with columns for `conversation_id`, `command`, truncated
+//! `stdout`/`stderr`, `exit_code`, `timestamp`, `working_directory`, and a
+//! JSON column of `file_operations`) implements the same trait and can be
+//! passed to `with_history_store` without any other change to this crate.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::error::Result;
+use crate::types::CommandHistoryEntry;
+
+/// Durable backing store for command history. Implementations are queried
+/// by `ShellService::get_command_history` once the in-memory window has
+/// nothing for a conversation (e.g. after a restart), and are written to
+/// on every `add_to_history`/`clear_command_history` call alongside the
+/// in-memory window.
+#[async_trait]
+pub trait CommandHistoryStore: Send + Sync {
+    /// Persist a single history entry for `conversation_id`.
+    async fn append(&self, conversation_id: &str, entry: &CommandHistoryEntry) -> Result<()>;
+
+    /// Load history for `conversation_id`, oldest first, capped to the last
+    /// `limit` entries if given.
+    async fn get(&self, conversation_id: &str, limit: Option<usize>) -> Result<Vec<CommandHistoryEntry>>;
+
+    /// Remove all history for `conversation_id`.
+    async fn clear(&self, conversation_id: &str) -> Result<()>;
+}
+
+/// Reference `CommandHistoryStore` that keeps everything in memory,
+/// unbounded. Useful in tests and as a starting point for composing with a
+/// durable store.
+#[derive(Default)]
+pub struct InMemoryHistoryStore {
+    entries: RwLock<HashMap<String, Vec<CommandHistoryEntry>>>,
+}
+
+impl InMemoryHistoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CommandHistoryStore for InMemoryHistoryStore {
+    async fn append(&self, conversation_id: &str, entry: &CommandHistoryEntry) -> Result<()> {
+        self.entries
+            .write()
+            .await
+            .entry(conversation_id.to_string())
+            .or_default()
+            .push(entry.clone());
+        Ok(())
+    }
+
+    async fn get(&self, conversation_id: &str, limit: Option<usize>) -> Result<Vec<CommandHistoryEntry>> {
+        let history = self
+            .entries
+            .read()
+            .await
+            .get(conversation_id)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(match limit {
+            Some(n) if n > 0 => history.into_iter().rev().take(n).rev().collect(),
+            _ => history,
+        })
+    }
+
+    async fn clear(&self, conversation_id: &str) -> Result<()> {
+        self.entries.write().await.remove(conversation_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CommandResult;
+
+    fn sample_entry(command: &str) -> CommandHistoryEntry {
+        let result = CommandResult::success("ok".to_string(), "/tmp");
+        CommandHistoryEntry {
+            command: command.to_string(),
+            stdout: result.stdout,
+            stderr: result.stderr,
+            exit_code: result.exit_code,
+            timestamp: 0.0,
+            working_directory: result.executed_in,
+            file_operations: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_append_and_get() {
+        let store = InMemoryHistoryStore::new();
+        store.append("conv", &sample_entry("echo hi")).await.unwrap();
+        store.append("conv", &sample_entry("ls")).await.unwrap();
+
+        let history = store.get("conv", None).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].command, "ls");
+    }
+
+    #[tokio::test]
+    async fn test_get_respects_limit() {
+        let store = InMemoryHistoryStore::new();
+        for i in 0..5 {
+            store.append("conv", &sample_entry(&format!("cmd{i}"))).await.unwrap();
+        }
+
+        let history = store.get("conv", Some(2)).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].command, "cmd3");
+        assert_eq!(history[1].command, "cmd4");
+    }
+
+    #[tokio::test]
+    async fn test_clear() {
+        let store = InMemoryHistoryStore::new();
+        store.append("conv", &sample_entry("echo hi")).await.unwrap();
+        store.clear("conv").await.unwrap();
+        assert!(store.get("conv", None).await.unwrap().is_empty());
+    }
+}
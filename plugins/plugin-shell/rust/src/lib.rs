@@ -23,24 +23,37 @@
 use async_trait::async_trait;
 use serde_json::Value;
 
+mod container;
 mod error;
+mod history_store;
+mod manager;
 mod path_utils;
+mod pty;
+mod search;
 mod service;
+mod transport;
 mod types;
+mod watch;
 
 pub mod actions;
 pub mod providers;
 
 // Re-export public API
 pub use error::{Result, ShellError};
+pub use history_store::{CommandHistoryStore, InMemoryHistoryStore};
+pub use manager::{ShellConnection, ShellManager, SshConnection, SshConnectionConfig};
 pub use path_utils::{
     extract_base_command, is_forbidden_command, is_safe_command, validate_path,
     DEFAULT_FORBIDDEN_COMMANDS,
 };
+pub use pty::{SessionRegistry, ShellSession};
+pub use search::{MatchValue, SearchMatch, SearchQuery};
 pub use service::ShellService;
+pub use watch::{WatchEvent, WatchId};
+pub use transport::RemoteVersion;
 pub use types::{
-    CommandHistoryEntry, CommandResult, FileOperation, FileOperationType, ShellConfig,
-    ShellConfigBuilder,
+    BatchResult, BatchStep, CommandChunk, CommandHistoryEntry, CommandResult, FileOperation,
+    FileOperationType, OnFailure, PermissionChange, ShellBackend, ShellConfig, ShellConfigBuilder,
 };
 
 // Re-export actions and providers
@@ -0,0 +1,485 @@
+#![allow(missing_docs)]
+//! Multi-host command execution.
+//!
+//! `ShellService` assumes every command runs on the local machine under one
+//! `allowed_directory`. `ShellManager` sits above it, holding a registry of
+//! named connections — each satisfying [`ShellConnection`] — so a single
+//! agent can dispatch to whichever host a `target` name selects while every
+//! connection still enforces its own sandboxing policy.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex as StdMutex;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ShellError;
+use crate::path_utils::{is_forbidden_command, is_safe_command};
+use crate::service::ShellService;
+use crate::types::{CommandHistoryEntry, CommandResult};
+use crate::Result;
+
+/// A single named backend a [`ShellManager`] can dispatch commands to.
+///
+/// `ShellService` itself satisfies this trait for the local-machine case;
+/// [`SshConnection`] satisfies it for a single SSH-reachable remote host.
+/// Command-history and current-directory state are owned by the
+/// implementation, so they're naturally tracked per connection (and, for
+/// history, per conversation within that connection).
+#[async_trait]
+pub trait ShellConnection: Send + Sync {
+    /// Run `command`, recording it in this connection's history under
+    /// `conversation_id` if given.
+    async fn execute_command(
+        &mut self,
+        command: &str,
+        conversation_id: Option<&str>,
+    ) -> Result<CommandResult>;
+
+    /// Handle a `cd` command, updating this connection's working directory.
+    fn handle_cd(&mut self, command: &str) -> CommandResult;
+
+    /// Recent history for `conversation_id` on this connection.
+    async fn get_command_history(
+        &self,
+        conversation_id: &str,
+        limit: Option<usize>,
+    ) -> Vec<CommandHistoryEntry>;
+}
+
+#[async_trait]
+impl ShellConnection for ShellService {
+    async fn execute_command(
+        &mut self,
+        command: &str,
+        conversation_id: Option<&str>,
+    ) -> Result<CommandResult> {
+        ShellService::execute_command(self, command, conversation_id).await
+    }
+
+    fn handle_cd(&mut self, command: &str) -> CommandResult {
+        self.handle_cd_command(command)
+    }
+
+    async fn get_command_history(
+        &self,
+        conversation_id: &str,
+        limit: Option<usize>,
+    ) -> Vec<CommandHistoryEntry> {
+        ShellService::get_command_history(self, conversation_id, limit).await
+    }
+}
+
+/// The name `ShellManager` falls back to when `execute_command` is called
+/// with `target: None`.
+const DEFAULT_CONNECTION: &str = "local";
+
+/// Registry of named [`ShellConnection`]s an agent can dispatch commands
+/// across, e.g. a `"local"` `ShellService` plus one `SshConnection` per
+/// remote host.
+#[derive(Default)]
+pub struct ShellManager {
+    connections: HashMap<String, Box<dyn ShellConnection>>,
+}
+
+impl ShellManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a connection under `name` (e.g. `"local"`, `"build-box"`),
+    /// replacing any existing connection of that name.
+    pub fn register(&mut self, name: impl Into<String>, connection: Box<dyn ShellConnection>) {
+        self.connections.insert(name.into(), connection);
+    }
+
+    /// Remove a registered connection.
+    pub fn unregister(&mut self, name: &str) {
+        self.connections.remove(name);
+    }
+
+    /// Run `command` against `target` (or `"local"` if `None`), enforcing
+    /// that connection's own policy before dispatch.
+    pub async fn execute_command(
+        &mut self,
+        target: Option<&str>,
+        command: &str,
+        conversation_id: Option<&str>,
+    ) -> Result<CommandResult> {
+        self.connection_mut(target)?
+            .execute_command(command, conversation_id)
+            .await
+    }
+
+    /// Recent history for `conversation_id` on `target` (or `"local"` if
+    /// `None`).
+    pub async fn get_command_history(
+        &self,
+        target: Option<&str>,
+        conversation_id: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<CommandHistoryEntry>> {
+        Ok(self
+            .connection(target)?
+            .get_command_history(conversation_id, limit)
+            .await)
+    }
+
+    fn connection_mut(&mut self, target: Option<&str>) -> Result<&mut Box<dyn ShellConnection>> {
+        let name = target.unwrap_or(DEFAULT_CONNECTION);
+        self.connections
+            .get_mut(name)
+            .ok_or_else(|| ShellError::Config(format!("no such shell connection: {name}")))
+    }
+
+    fn connection(&self, target: Option<&str>) -> Result<&Box<dyn ShellConnection>> {
+        let name = target.unwrap_or(DEFAULT_CONNECTION);
+        self.connections
+            .get(name)
+            .ok_or_else(|| ShellError::Config(format!("no such shell connection: {name}")))
+    }
+}
+
+/// Configuration for a single SSH-backed remote connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshConnectionConfig {
+    pub host: String,
+    #[serde(default = "default_ssh_port")]
+    pub port: u16,
+    pub username: String,
+    /// Path to a private key file. If unset, ssh2's agent-based
+    /// authentication is attempted instead.
+    #[serde(default)]
+    pub private_key_path: Option<PathBuf>,
+    /// Directory commands are confined to on the remote host.
+    pub allowed_directory: String,
+    /// Forbidden commands/patterns enforced before anything is sent to the
+    /// remote host.
+    #[serde(default)]
+    pub forbidden_commands: Vec<String>,
+    /// Maximum time to wait for a command to complete.
+    #[serde(default = "default_ssh_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_ssh_port() -> u16 {
+    22
+}
+
+fn default_ssh_timeout_ms() -> u64 {
+    30000
+}
+
+/// An SSH-backed [`ShellConnection`] to a single remote host.
+///
+/// Each `exec` opens a fresh channel with no shared shell state, so the
+/// current directory is threaded manually: every non-`cd` command is run as
+/// `cd '<current_directory>' && <command>`, mirroring how
+/// `ShellService::handle_cd_command` tracks `current_directory` locally.
+/// `handle_cd` only updates that local bookkeeping — it does not validate
+/// the path on the remote host, so a bad `cd` surfaces as a failure on the
+/// next command instead.
+///
+/// `ssh2` is a blocking library; `config.timeout_ms` is applied as the
+/// session's own libssh2 timeout (set once in `connect`) rather than a
+/// `tokio::time::timeout` around each call, since the exec/read calls below
+/// block the calling thread for their duration regardless.
+pub struct SshConnection {
+    config: SshConnectionConfig,
+    session: StdMutex<ssh2::Session>,
+    current_directory: String,
+    command_history: StdMutex<HashMap<String, Vec<CommandHistoryEntry>>>,
+}
+
+impl SshConnection {
+    /// Open and authenticate an SSH session against `config`.
+    pub fn connect(config: SshConnectionConfig) -> Result<Self> {
+        let tcp = std::net::TcpStream::connect((config.host.as_str(), config.port))
+            .map_err(ShellError::Io)?;
+
+        let mut session = ssh2::Session::new()
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to create ssh session: {e}")))?;
+        session.set_tcp_stream(tcp);
+        session.set_timeout(config.timeout_ms as u32);
+        session
+            .handshake()
+            .map_err(|e| ShellError::ExecutionFailed(format!("ssh handshake failed: {e}")))?;
+
+        match &config.private_key_path {
+            Some(key_path) => session
+                .userauth_pubkey_file(&config.username, None, key_path, None)
+                .map_err(|e| ShellError::ExecutionFailed(format!("ssh key auth failed: {e}")))?,
+            None => session
+                .userauth_agent(&config.username)
+                .map_err(|e| ShellError::ExecutionFailed(format!("ssh agent auth failed: {e}")))?,
+        }
+
+        if !session.authenticated() {
+            return Err(ShellError::ExecutionFailed(
+                "ssh authentication failed".to_string(),
+            ));
+        }
+
+        let current_directory = config.allowed_directory.clone();
+        Ok(Self {
+            config,
+            session: StdMutex::new(session),
+            current_directory,
+            command_history: StdMutex::new(HashMap::new()),
+        })
+    }
+
+    fn record_history(&self, conversation_id: &str, command: &str, result: &CommandResult) {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+
+        let entry = CommandHistoryEntry {
+            command: command.to_string(),
+            stdout: result.stdout.clone(),
+            stderr: result.stderr.clone(),
+            exit_code: result.exit_code,
+            timestamp,
+            working_directory: result.executed_in.clone(),
+            file_operations: None,
+        };
+
+        self.command_history
+            .lock()
+            .expect("command history lock poisoned")
+            .entry(conversation_id.to_string())
+            .or_default()
+            .push(entry);
+    }
+
+    /// Run `remote_command` over a fresh SSH channel, blocking the calling
+    /// thread while it does so (ssh2 has no async API; see the timeout note
+    /// on this struct).
+    async fn run_remote(&self, remote_command: String) -> Result<CommandResult> {
+        let cwd = self.current_directory.clone();
+        let session = self.session.lock().expect("ssh session lock poisoned");
+
+        let mut channel = match session.channel_session() {
+            Ok(channel) => channel,
+            Err(e) => return Ok(timed_out_or_failed(&e, &cwd)),
+        };
+        if let Err(e) = channel.exec(&remote_command) {
+            return Ok(timed_out_or_failed(&e, &cwd));
+        }
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        if let Err(e) = std::io::Read::read_to_string(&mut channel, &mut stdout) {
+            return Ok(timed_out_or_failed(&e, &cwd));
+        }
+        if let Err(e) = std::io::Read::read_to_string(&mut channel.stderr(), &mut stderr) {
+            return Ok(timed_out_or_failed(&e, &cwd));
+        }
+        if let Err(e) = channel.wait_close() {
+            return Ok(timed_out_or_failed(&e, &cwd));
+        }
+        let exit_status = channel
+            .exit_status()
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to read ssh exit status: {e}")))?;
+
+        Ok(CommandResult {
+            success: exit_status == 0,
+            stdout,
+            stderr,
+            exit_code: Some(exit_status),
+            error: None,
+            executed_in: cwd,
+        })
+    }
+}
+
+#[async_trait]
+impl ShellConnection for SshConnection {
+    async fn execute_command(
+        &mut self,
+        command: &str,
+        conversation_id: Option<&str>,
+    ) -> Result<CommandResult> {
+        let trimmed = command.trim();
+        if trimmed.is_empty() {
+            return Ok(CommandResult::error(
+                "Invalid command",
+                "Command must be a non-empty string",
+                &self.current_directory,
+            ));
+        }
+        if !is_safe_command(trimmed) {
+            return Ok(CommandResult::error(
+                "Security policy violation",
+                "Command contains forbidden patterns",
+                &self.current_directory,
+            ));
+        }
+        if is_forbidden_command(trimmed, &self.config.forbidden_commands) {
+            return Ok(CommandResult::error(
+                "Forbidden command",
+                "Command is forbidden by security policy",
+                &self.current_directory,
+            ));
+        }
+
+        if trimmed.starts_with("cd ") {
+            let result = self.handle_cd(trimmed);
+            if let Some(conv_id) = conversation_id {
+                self.record_history(conv_id, trimmed, &result);
+            }
+            return Ok(result);
+        }
+
+        let remote_command = format!(
+            "cd {} && {}",
+            shell_quote(&self.current_directory),
+            trimmed
+        );
+        let result = self.run_remote(remote_command).await?;
+
+        if let Some(conv_id) = conversation_id {
+            self.record_history(conv_id, trimmed, &result);
+        }
+
+        Ok(result)
+    }
+
+    fn handle_cd(&mut self, command: &str) -> CommandResult {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let target = if parts.len() < 2 {
+            self.config.allowed_directory.clone()
+        } else if parts[1].starts_with('/') {
+            parts[1].to_string()
+        } else {
+            format!("{}/{}", self.current_directory.trim_end_matches('/'), parts[1])
+        };
+
+        self.current_directory = target.clone();
+        CommandResult::success(format!("Changed directory to: {target}"), &target)
+    }
+
+    async fn get_command_history(
+        &self,
+        conversation_id: &str,
+        limit: Option<usize>,
+    ) -> Vec<CommandHistoryEntry> {
+        let history = self
+            .command_history
+            .lock()
+            .expect("command history lock poisoned")
+            .get(conversation_id)
+            .cloned()
+            .unwrap_or_default();
+
+        match limit {
+            Some(n) if n > 0 => history.into_iter().rev().take(n).rev().collect(),
+            _ => history,
+        }
+    }
+}
+
+/// Single-quote `path` for safe interpolation into a remote shell command.
+fn shell_quote(path: &str) -> String {
+    format!("'{}'", path.replace('\'', "'\\''"))
+}
+
+/// libssh2 surfaces a blocking call exceeding the session timeout as a
+/// plain error with no distinct variant to match on; treat every failure
+/// from a channel operation as the same "command timed out" shape the
+/// rest of the crate uses, since that's overwhelmingly what causes one.
+fn timed_out_or_failed(error: &impl std::fmt::Display, cwd: &str) -> CommandResult {
+    CommandResult {
+        success: false,
+        stdout: String::new(),
+        stderr: format!("Command timed out or failed: {error}"),
+        exit_code: None,
+        error: Some("Command execution timeout".to_string()),
+        executed_in: cwd.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal in-memory `ShellConnection` used to exercise `ShellManager`'s
+    /// dispatch/history-routing logic without a real host.
+    struct FakeConnection {
+        history: HashMap<String, Vec<CommandHistoryEntry>>,
+    }
+
+    #[async_trait]
+    impl ShellConnection for FakeConnection {
+        async fn execute_command(
+            &mut self,
+            command: &str,
+            conversation_id: Option<&str>,
+        ) -> Result<CommandResult> {
+            let result = CommandResult::success(format!("ran: {command}"), "/fake");
+            if let Some(conv_id) = conversation_id {
+                self.history.entry(conv_id.to_string()).or_default().push(CommandHistoryEntry {
+                    command: command.to_string(),
+                    stdout: result.stdout.clone(),
+                    stderr: String::new(),
+                    exit_code: result.exit_code,
+                    timestamp: 0.0,
+                    working_directory: result.executed_in.clone(),
+                    file_operations: None,
+                });
+            }
+            Ok(result)
+        }
+
+        fn handle_cd(&mut self, _command: &str) -> CommandResult {
+            CommandResult::success("ok".to_string(), "/fake")
+        }
+
+        async fn get_command_history(
+            &self,
+            conversation_id: &str,
+            _limit: Option<usize>,
+        ) -> Vec<CommandHistoryEntry> {
+            self.history.get(conversation_id).cloned().unwrap_or_default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatches_to_named_connection() {
+        let mut manager = ShellManager::new();
+        manager.register(
+            "build-box",
+            Box::new(FakeConnection { history: HashMap::new() }),
+        );
+
+        let result = manager
+            .execute_command(Some("build-box"), "echo hi", Some("conv"))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.stdout, "ran: echo hi");
+
+        let history = manager
+            .get_command_history(Some("build-box"), "conv", None)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_target_is_an_error() {
+        let mut manager = ShellManager::new();
+        let result = manager.execute_command(Some("nope"), "echo hi", None).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("/tmp/a b"), "'/tmp/a b'");
+        assert_eq!(shell_quote("/tmp/o'brien"), "'/tmp/o'\\''brien'");
+    }
+}
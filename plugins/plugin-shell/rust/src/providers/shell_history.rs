@@ -61,7 +61,7 @@ impl Provider for ShellHistoryProvider {
             .or_else(|| message.get("agent_id").and_then(|a| a.as_str()))
             .unwrap_or("default");
 
-        let history = service.get_command_history(conversation_id, Some(10));
+        let history = service.get_command_history(conversation_id, Some(10)).await;
         let cwd = service.get_current_directory(None);
         let allowed_dir = service.get_allowed_directory();
 
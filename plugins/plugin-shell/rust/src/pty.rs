@@ -0,0 +1,237 @@
+#![allow(missing_docs)]
+//! Interactive PTY sessions.
+//!
+//! `CommandResult` only models batch execution (full stdout/stderr captured
+//! after exit), so interactive tools (REPLs, `ssh`, `top`, anything that
+//! prompts) can't be driven by the agent that way. `ShellSession` allocates
+//! a pseudo-terminal, spawns the command under it, and streams output
+//! incrementally so the agent can react to prompts as they appear, rather
+//! than waiting for the process to exit. Sessions are kept in a
+//! `SessionRegistry` keyed by `Uuid` so they can be referenced across
+//! multiple agent turns.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Mutex as StdMutex;
+use std::time::{Duration, Instant};
+
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use tokio::sync::{mpsc, RwLock};
+use uuid::Uuid;
+
+use crate::error::ShellError;
+use crate::types::PtyConfig;
+use crate::Result;
+
+/// A single interactive PTY-backed session.
+pub struct ShellSession {
+    id: Uuid,
+    master: StdMutex<Box<dyn MasterPty + Send>>,
+    writer: StdMutex<Box<dyn Write + Send>>,
+    child: StdMutex<Box<dyn Child + Send + Sync>>,
+    last_activity: StdMutex<Instant>,
+    idle_timeout: Duration,
+}
+
+impl ShellSession {
+    /// Allocate a PTY and spawn `command` under it. Returns the session and
+    /// a channel of output chunks as they arrive off the PTY's read side
+    /// (not buffered until exit).
+    pub fn spawn(
+        command: &str,
+        cwd: &Path,
+        pty_config: &PtyConfig,
+        idle_timeout_ms: u64,
+    ) -> Result<(Self, mpsc::Receiver<Vec<u8>>)> {
+        let pty_system = native_pty_system();
+        let pair = pty_system
+            .openpty(PtySize {
+                rows: pty_config.rows,
+                cols: pty_config.cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to allocate pty: {e}")))?;
+
+        let mut builder = CommandBuilder::new("sh");
+        builder.arg("-c");
+        builder.arg(command);
+        builder.cwd(cwd);
+        builder.env("TERM", &pty_config.term);
+
+        let child = pair
+            .slave
+            .spawn_command(builder)
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to spawn pty command: {e}")))?;
+        // The slave side belongs to the child now; dropping our handle to it
+        // lets the child own the controlling terminal exclusively.
+        drop(pair.slave);
+
+        let mut reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to clone pty reader: {e}")))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to take pty writer: {e}")))?;
+
+        let (tx, rx) = mpsc::channel::<Vec<u8>>(64);
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        if tx.blocking_send(buf[..n].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        let session = Self {
+            id: Uuid::new_v4(),
+            master: StdMutex::new(pair.master),
+            writer: StdMutex::new(writer),
+            child: StdMutex::new(child),
+            last_activity: StdMutex::new(Instant::now()),
+            idle_timeout: Duration::from_millis(idle_timeout_ms),
+        };
+
+        Ok((session, rx))
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Write bytes to the session's stdin, e.g. a line the agent wants to
+    /// type into a prompt.
+    pub fn write_stdin(&self, bytes: &[u8]) -> Result<()> {
+        *self.last_activity.lock().expect("session lock poisoned") = Instant::now();
+        self.writer
+            .lock()
+            .expect("session lock poisoned")
+            .write_all(bytes)
+            .map_err(ShellError::Io)
+    }
+
+    /// Resize the pseudo-terminal (e.g. the agent's view resized).
+    pub fn resize(&self, rows: u16, cols: u16) -> Result<()> {
+        self.master
+            .lock()
+            .expect("session lock poisoned")
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to resize pty: {e}")))
+    }
+
+    /// Terminate the session's process.
+    pub fn kill(&self) -> Result<()> {
+        self.child
+            .lock()
+            .expect("session lock poisoned")
+            .kill()
+            .map_err(ShellError::Io)
+    }
+
+    /// Whether the session has gone longer than `idle_timeout_ms` without a
+    /// `write_stdin` call.
+    pub fn is_idle_expired(&self) -> bool {
+        self.last_activity.lock().expect("session lock poisoned").elapsed() > self.idle_timeout
+    }
+
+    /// Non-blocking check for whether the child has exited.
+    pub fn try_wait(&self) -> Result<Option<portable_pty::ExitStatus>> {
+        self.child
+            .lock()
+            .expect("session lock poisoned")
+            .try_wait()
+            .map_err(ShellError::Io)
+    }
+}
+
+/// Registry of live PTY sessions, keyed by `Uuid`, so a session started in
+/// one agent turn can be written to/read from in a later turn.
+#[derive(Default)]
+pub struct SessionRegistry {
+    sessions: RwLock<HashMap<Uuid, ShellSession>>,
+}
+
+impl SessionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn insert(&self, session: ShellSession) -> Uuid {
+        let id = session.id();
+        self.sessions.write().await.insert(id, session);
+        id
+    }
+
+    pub async fn write_stdin(&self, id: &Uuid, bytes: &[u8]) -> Result<()> {
+        let sessions = self.sessions.read().await;
+        let session = sessions
+            .get(id)
+            .ok_or_else(|| ShellError::ExecutionFailed(format!("no such pty session: {id}")))?;
+        session.write_stdin(bytes)
+    }
+
+    pub async fn resize(&self, id: &Uuid, rows: u16, cols: u16) -> Result<()> {
+        let sessions = self.sessions.read().await;
+        let session = sessions
+            .get(id)
+            .ok_or_else(|| ShellError::ExecutionFailed(format!("no such pty session: {id}")))?;
+        session.resize(rows, cols)
+    }
+
+    pub async fn kill(&self, id: &Uuid) -> Result<()> {
+        let session = self.sessions.write().await.remove(id);
+        match session {
+            Some(session) => session.kill(),
+            None => Err(ShellError::ExecutionFailed(format!("no such pty session: {id}"))),
+        }
+    }
+
+    /// Non-blocking check for whether a session's child has exited.
+    pub async fn try_wait(&self, id: &Uuid) -> Result<Option<portable_pty::ExitStatus>> {
+        let sessions = self.sessions.read().await;
+        let session = sessions
+            .get(id)
+            .ok_or_else(|| ShellError::ExecutionFailed(format!("no such pty session: {id}")))?;
+        session.try_wait()
+    }
+
+    /// Remove a session from the registry without killing it (e.g. once its
+    /// child has already exited on its own).
+    pub async fn remove(&self, id: &Uuid) {
+        self.sessions.write().await.remove(id);
+    }
+
+    /// Tear down and remove any sessions that have been idle longer than
+    /// their configured timeout.
+    pub async fn sweep_idle(&self) {
+        let expired: Vec<Uuid> = {
+            let sessions = self.sessions.read().await;
+            sessions
+                .iter()
+                .filter(|(_, session)| session.is_idle_expired())
+                .map(|(id, _)| *id)
+                .collect()
+        };
+
+        for id in expired {
+            if let Some(session) = self.sessions.write().await.remove(&id) {
+                let _ = session.kill();
+            }
+        }
+    }
+}
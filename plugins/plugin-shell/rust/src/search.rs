@@ -0,0 +1,245 @@
+#![allow(missing_docs)]
+//! Recursive content search within the allowed directory.
+//!
+//! Gives agents a safe, structured way to locate code/config without
+//! shelling out to `grep` and parsing text. Honors the same path-confinement
+//! rules as command execution and compiles patterns with the `regex` crate.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, ShellError};
+
+/// A content search request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchQuery {
+    /// The text or regex pattern to search for.
+    pub pattern: String,
+    /// Paths (files or directories) to search, relative to or within
+    /// `allowed_directory`. An empty list searches the whole directory.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Treat `pattern` as a regular expression rather than a literal string.
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Stop once this many matches have been found.
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+    /// Whether to attempt matches inside files that aren't valid UTF-8.
+    #[serde(default)]
+    pub include_binary: bool,
+}
+
+fn default_max_results() -> usize {
+    100
+}
+
+/// The text or bytes a match was found in, serialized inline (not as a
+/// tagged `{type, value}` object) so consumers can cheaply distinguish a
+/// text match from a binary one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MatchValue {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+/// A single match produced by a search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchMatch {
+    /// Absolute path of the file the match was found in.
+    pub path: String,
+    /// 1-based line number within the file.
+    pub line_number: usize,
+    /// 0-based byte column of the match start within the line.
+    pub column: usize,
+    /// The matching line's contents.
+    pub value: MatchValue,
+}
+
+/// Search the tree rooted at `search_root` (itself confined to
+/// `allowed_directory`) for `query.pattern`, streaming matches up to
+/// `query.max_results`.
+pub fn search(
+    query: &SearchQuery,
+    allowed_directory: &Path,
+    current_directory: &Path,
+) -> Result<Vec<SearchMatch>> {
+    let pattern = if query.regex {
+        query.pattern.clone()
+    } else {
+        regex::escape(&query.pattern)
+    };
+
+    let re = RegexBuilder::new(&pattern)
+        .case_insensitive(!query.case_sensitive)
+        .build()
+        .map_err(|e| ShellError::InvalidCommand(format!("invalid search pattern: {e}")))?;
+
+    let roots = if query.paths.is_empty() {
+        vec![allowed_directory.to_path_buf()]
+    } else {
+        query
+            .paths
+            .iter()
+            .map(|p| {
+                crate::path_utils::validate_path(p, allowed_directory, current_directory)
+                    .ok_or_else(|| ShellError::PathValidationFailed)
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let mut matches = Vec::new();
+    for root in roots {
+        walk(&root, &re, query, &mut matches)?;
+        if matches.len() >= query.max_results {
+            break;
+        }
+    }
+    matches.truncate(query.max_results);
+    Ok(matches)
+}
+
+fn walk(path: &Path, re: &Regex, query: &SearchQuery, matches: &mut Vec<SearchMatch>) -> Result<()> {
+    if matches.len() >= query.max_results {
+        return Ok(());
+    }
+
+    let metadata = fs::symlink_metadata(path).map_err(ShellError::Io)?;
+
+    if metadata.is_dir() {
+        let mut entries: Vec<PathBuf> = fs::read_dir(path)
+            .map_err(ShellError::Io)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .collect();
+        entries.sort();
+        for entry in entries {
+            walk(&entry, re, query, matches)?;
+            if matches.len() >= query.max_results {
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    if !metadata.is_file() {
+        return Ok(());
+    }
+
+    search_file(path, re, query, matches)
+}
+
+fn search_file(path: &Path, re: &Regex, query: &SearchQuery, matches: &mut Vec<SearchMatch>) -> Result<()> {
+    let bytes = fs::read(path).map_err(ShellError::Io)?;
+
+    match String::from_utf8(bytes.clone()) {
+        Ok(text) => {
+            for (idx, line) in text.lines().enumerate() {
+                if matches.len() >= query.max_results {
+                    break;
+                }
+                if let Some(m) = re.find(line) {
+                    matches.push(SearchMatch {
+                        path: path.display().to_string(),
+                        line_number: idx + 1,
+                        column: m.start(),
+                        value: MatchValue::Text(line.to_string()),
+                    });
+                }
+            }
+        }
+        Err(_) if query.include_binary => {
+            if re.as_str().is_empty() {
+                return Ok(());
+            }
+            for (idx, line) in bytes.split(|b| *b == b'\n').enumerate() {
+                if matches.len() >= query.max_results {
+                    break;
+                }
+                let lossy = String::from_utf8_lossy(line);
+                if let Some(m) = re.find(&lossy) {
+                    matches.push(SearchMatch {
+                        path: path.display().to_string(),
+                        line_number: idx + 1,
+                        column: m.start(),
+                        value: MatchValue::Binary(line.to_vec()),
+                    });
+                }
+            }
+        }
+        Err(_) => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_search_literal_match() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello world\nfoo bar\n").unwrap();
+
+        let query = SearchQuery {
+            pattern: "foo".to_string(),
+            paths: vec![],
+            regex: false,
+            case_sensitive: true,
+            max_results: 10,
+            include_binary: false,
+        };
+
+        let results = search(&query, dir.path(), dir.path()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_number, 2);
+        match &results[0].value {
+            MatchValue::Text(t) => assert_eq!(t, "foo bar"),
+            MatchValue::Binary(_) => panic!("expected text match"),
+        }
+    }
+
+    #[test]
+    fn test_search_respects_max_results() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "match\nmatch\nmatch\n").unwrap();
+
+        let query = SearchQuery {
+            pattern: "match".to_string(),
+            paths: vec![],
+            regex: false,
+            case_sensitive: true,
+            max_results: 2,
+            include_binary: false,
+        };
+
+        let results = search(&query, dir.path(), dir.path()).unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_search_rejects_path_outside_allowed_directory() {
+        let dir = tempdir().unwrap();
+        let outside = tempdir().unwrap();
+
+        let query = SearchQuery {
+            pattern: "x".to_string(),
+            paths: vec![outside.path().display().to_string()],
+            regex: false,
+            case_sensitive: true,
+            max_results: 10,
+            include_binary: false,
+        };
+
+        assert!(search(&query, dir.path(), dir.path()).is_err());
+    }
+}
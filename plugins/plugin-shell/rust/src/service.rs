@@ -3,39 +3,421 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
+use tokio::sync::{mpsc, Mutex as AsyncMutex};
 use tokio::time::timeout;
 use tracing::info;
+use uuid::Uuid;
 
 use crate::error::Result;
+use crate::history_store::CommandHistoryStore;
 use crate::path_utils::{is_forbidden_command, is_safe_command, validate_path};
+use crate::pty::{SessionRegistry, ShellSession};
+use crate::search::{self, SearchMatch, SearchQuery};
+use crate::transport::{self, RemoteVersion};
+use crate::watch::{WatchEvent, WatchId, WatchRegistry};
 use crate::types::{
-    CommandHistoryEntry, CommandResult, FileOperation, FileOperationType, ShellConfig,
+    BatchResult, BatchStep, CommandChunk, CommandHistoryEntry, CommandResult, FileOperation,
+    FileOperationType, OnFailure, PermissionChange, ShellBackend, ShellConfig,
 };
 
+/// How much of each stream `execute_command_streaming` retains for the
+/// final `CommandHistoryEntry`, so history stays bounded even for commands
+/// that produce gigabytes of output.
+const STREAMING_HISTORY_TAIL_BYTES: usize = 64 * 1024;
+
 pub struct ShellService {
     config: ShellConfig,
     current_directory: PathBuf,
-    command_history: HashMap<String, Vec<CommandHistoryEntry>>,
+    /// Wrapped in `Arc<Mutex<_>>` (rather than a plain field) so the
+    /// background reader tasks spawned by `execute_command_streaming` can
+    /// append the final history entry without holding `&mut self` for the
+    /// lifetime of the command.
+    command_history: Arc<StdMutex<HashMap<String, Vec<CommandHistoryEntry>>>>,
     max_history_per_conversation: usize,
+    /// Durable backing store for command history, if one was wired in via
+    /// `with_history_store`. Consulted by `get_command_history` once the
+    /// in-memory window is empty for a conversation (e.g. after a
+    /// restart), and written to alongside the in-memory window on every
+    /// `add_to_history`/`clear_command_history` call.
+    history_store: Option<Arc<dyn CommandHistoryStore>>,
+    /// Protocol version negotiated with the remote shell daemon on the last
+    /// remote execution, if `config.target` isn't `"local"`.
+    last_remote_version: Option<RemoteVersion>,
+    /// Live interactive PTY sessions, keyed by `Uuid` so they can be
+    /// referenced across multiple agent turns.
+    pty_sessions: SessionRegistry,
+    /// The PTY-backed command currently running for a conversation, if any,
+    /// keyed by `conversation_id` so callers can `write_stdin`/`resize_pty`
+    /// into it while `run_command_pty` is still awaiting completion.
+    active_pty_commands: HashMap<String, Uuid>,
+    /// Live filesystem watches, keyed by `WatchId`.
+    watches: WatchRegistry,
 }
 
 impl ShellService {
     pub fn new(config: ShellConfig) -> Self {
         let current_directory = config.allowed_directory.clone();
+        let watches = WatchRegistry::new(Duration::from_millis(config.watch_debounce_ms));
         info!("Shell service initialized with history tracking");
 
         Self {
             config,
             current_directory,
-            command_history: HashMap::new(),
+            command_history: Arc::new(StdMutex::new(HashMap::new())),
             max_history_per_conversation: 100,
+            history_store: None,
+            last_remote_version: None,
+            pty_sessions: SessionRegistry::new(),
+            active_pty_commands: HashMap::new(),
+            watches,
         }
     }
 
+    /// Like `new`, but additionally persists command history through
+    /// `store` so it survives a restart and can be audited across
+    /// sessions. The in-memory window (capped at
+    /// `max_history_per_conversation` entries) is still kept for fast
+    /// access; `get_command_history` falls back to `store` once that
+    /// window is empty for a conversation.
+    pub fn with_history_store(config: ShellConfig, store: Arc<dyn CommandHistoryStore>) -> Self {
+        Self {
+            history_store: Some(store),
+            ..Self::new(config)
+        }
+    }
+
+    /// Spawn an interactive PTY session running `command`, returning its id
+    /// and a stream of output chunks as they arrive (not buffered until
+    /// exit). Use the id with `write_to_session`/`resize_session`/
+    /// `kill_session` in later calls, even across agent turns.
+    pub async fn spawn_session(&self, command: &str) -> Result<(Uuid, mpsc::Receiver<Vec<u8>>)> {
+        let (session, rx) = ShellSession::spawn(
+            command,
+            &self.current_directory,
+            &self.config.pty,
+            self.config.timeout_ms,
+        )?;
+        let id = self.pty_sessions.insert(session).await;
+        Ok((id, rx))
+    }
+
+    /// Write bytes to a session's stdin, e.g. a line typed into a prompt.
+    pub async fn write_to_session(&self, id: &Uuid, bytes: &[u8]) -> Result<()> {
+        self.pty_sessions.write_stdin(id, bytes).await
+    }
+
+    /// Resize a session's pseudo-terminal.
+    pub async fn resize_session(&self, id: &Uuid, rows: u16, cols: u16) -> Result<()> {
+        self.pty_sessions.resize(id, rows, cols).await
+    }
+
+    /// Terminate and remove a session.
+    pub async fn kill_session(&self, id: &Uuid) -> Result<()> {
+        self.pty_sessions.kill(id).await
+    }
+
+    /// Tear down any sessions that have gone idle longer than
+    /// `config.timeout_ms` without a `write_to_session` call.
+    pub async fn sweep_idle_sessions(&self) {
+        self.pty_sessions.sweep_idle().await;
+    }
+
+    /// Run `command` attached to a pseudo-terminal instead of piped
+    /// stdout/stderr, so interactive tools (REPLs, prompts, password input)
+    /// can be driven instead of hanging or misbehaving. While this is
+    /// awaiting completion, `write_stdin`/`resize_pty` can be called with
+    /// the same `conversation_id` to interact with it. Captured output is
+    /// recorded into `CommandHistoryEntry` the same way a one-shot command
+    /// would be.
+    pub async fn run_command_pty(
+        &mut self,
+        conversation_id: &str,
+        command: &str,
+    ) -> Result<CommandResult> {
+        let cwd = self.current_directory.display().to_string();
+        let (session, mut rx) = ShellSession::spawn(
+            command,
+            &self.current_directory,
+            &self.config.pty,
+            self.config.timeout_ms,
+        )?;
+        let id = self.pty_sessions.insert(session).await;
+        self.active_pty_commands.insert(conversation_id.to_string(), id);
+
+        let output = Arc::new(AsyncMutex::new(Vec::new()));
+        let output_writer = output.clone();
+        tokio::spawn(async move {
+            while let Some(chunk) = rx.recv().await {
+                output_writer.lock().await.extend_from_slice(&chunk);
+            }
+        });
+
+        let deadline = Instant::now() + Duration::from_millis(self.config.timeout_ms);
+        let result = loop {
+            if let Some(status) = self.pty_sessions.try_wait(&id).await? {
+                let captured = output.lock().await.clone();
+                break CommandResult {
+                    success: status.success(),
+                    stdout: String::from_utf8_lossy(&captured).to_string(),
+                    stderr: String::new(),
+                    exit_code: Some(status.exit_code() as i32),
+                    error: None,
+                    executed_in: cwd.clone(),
+                };
+            }
+
+            if Instant::now() >= deadline {
+                let _ = self.pty_sessions.kill(&id).await;
+                break CommandResult {
+                    success: false,
+                    stdout: String::from_utf8_lossy(&output.lock().await).to_string(),
+                    stderr: "Command timed out".to_string(),
+                    exit_code: None,
+                    error: Some("Command execution timeout".to_string()),
+                    executed_in: cwd.clone(),
+                };
+            }
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+
+        self.pty_sessions.remove(&id).await;
+        self.active_pty_commands.remove(conversation_id);
+        self.add_to_history(conversation_id, command, &result, None).await;
+
+        Ok(result)
+    }
+
+    /// Write bytes to the stdin of the PTY command currently running for
+    /// `conversation_id`.
+    pub async fn write_stdin(&self, conversation_id: &str, bytes: &[u8]) -> Result<()> {
+        let id = self.active_pty_commands.get(conversation_id).ok_or_else(|| {
+            crate::error::ShellError::ExecutionFailed(format!(
+                "no pty command is running for conversation {conversation_id}"
+            ))
+        })?;
+        self.pty_sessions.write_stdin(id, bytes).await
+    }
+
+    /// Resize the pseudo-terminal of the PTY command currently running for
+    /// `conversation_id`.
+    pub async fn resize_pty(&self, conversation_id: &str, rows: u16, cols: u16) -> Result<()> {
+        let id = self.active_pty_commands.get(conversation_id).ok_or_else(|| {
+            crate::error::ShellError::ExecutionFailed(format!(
+                "no pty command is running for conversation {conversation_id}"
+            ))
+        })?;
+        self.pty_sessions.resize(id, rows, cols).await
+    }
+
+    /// Run `command` with stdout/stderr streamed as they arrive instead of
+    /// buffered until the process exits, so long-running commands (builds,
+    /// tests, tailed logs) produce visible output immediately and huge
+    /// output doesn't sit unbounded in memory. The final `CommandHistoryEntry`
+    /// retains only the last `STREAMING_HISTORY_TAIL_BYTES` of each stream.
+    pub async fn execute_command_streaming(
+        &self,
+        command: &str,
+        conversation_id: Option<&str>,
+    ) -> Result<mpsc::Receiver<CommandChunk>> {
+        if !self.config.enabled {
+            return Err(crate::error::ShellError::Disabled);
+        }
+
+        let trimmed_command = command.trim();
+        if trimmed_command.is_empty() {
+            return Err(crate::error::ShellError::InvalidCommand(
+                "Command must be a non-empty string".to_string(),
+            ));
+        }
+        if !is_safe_command(trimmed_command) {
+            return Err(crate::error::ShellError::SecurityViolation(
+                "Command contains forbidden patterns".to_string(),
+            ));
+        }
+        if is_forbidden_command(trimmed_command, &self.config.forbidden_commands) {
+            return Err(crate::error::ShellError::ForbiddenCommand);
+        }
+
+        let cwd = self.current_directory.display().to_string();
+        let use_shell =
+            trimmed_command.contains('>') || trimmed_command.contains('<') || trimmed_command.contains('|');
+
+        let mut cmd = if use_shell {
+            let mut c = Command::new("sh");
+            c.args(["-c", trimmed_command]);
+            c
+        } else {
+            let parts: Vec<&str> = trimmed_command.split_whitespace().collect();
+            let mut c = Command::new(parts[0]);
+            if parts.len() > 1 {
+                c.args(&parts[1..]);
+            }
+            c
+        };
+
+        cmd.current_dir(&self.current_directory)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| crate::error::ShellError::ExecutionFailed(e.to_string()))?;
+        let mut stdout = child.stdout.take().expect("child spawned with piped stdout");
+        let mut stderr = child.stderr.take().expect("child spawned with piped stderr");
+
+        let (tx, rx) = mpsc::channel::<CommandChunk>(64);
+        let tail = Arc::new(StdMutex::new((Vec::<u8>::new(), Vec::<u8>::new())));
+
+        let tx_stdout = tx.clone();
+        let tail_stdout = tail.clone();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 4096];
+            loop {
+                match stdout.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let chunk = buf[..n].to_vec();
+                        append_tail(&tail_stdout, true, &chunk);
+                        if tx_stdout.send(CommandChunk::Stdout { bytes: chunk }).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        let tx_stderr = tx.clone();
+        let tail_stderr = tail.clone();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 4096];
+            loop {
+                match stderr.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let chunk = buf[..n].to_vec();
+                        append_tail(&tail_stderr, false, &chunk);
+                        if tx_stderr.send(CommandChunk::Stderr { bytes: chunk }).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        let timeout_duration = Duration::from_millis(self.config.timeout_ms);
+        let history = self.command_history.clone();
+        let history_store = self.history_store.clone();
+        let max_history = self.max_history_per_conversation;
+        let conv_id = conversation_id.map(|s| s.to_string());
+        let command_owned = trimmed_command.to_string();
+        tokio::spawn(async move {
+            let result = match timeout(timeout_duration, child.wait()).await {
+                Ok(Ok(status)) => {
+                    let _ = tx.send(CommandChunk::Exit { code: status.code() }).await;
+                    let (stdout_tail, stderr_tail) = {
+                        let guard = tail.lock().expect("tail lock poisoned");
+                        (guard.0.clone(), guard.1.clone())
+                    };
+                    CommandResult {
+                        success: status.success(),
+                        stdout: String::from_utf8_lossy(&stdout_tail).to_string(),
+                        stderr: String::from_utf8_lossy(&stderr_tail).to_string(),
+                        exit_code: status.code(),
+                        error: None,
+                        executed_in: cwd.clone(),
+                    }
+                }
+                Ok(Err(e)) => {
+                    let _ = tx.send(CommandChunk::Exit { code: None }).await;
+                    CommandResult::error("Failed to execute command", &e.to_string(), &cwd)
+                }
+                Err(_) => {
+                    let _ = child.kill().await;
+                    let _ = tx.send(CommandChunk::TimedOut).await;
+                    CommandResult {
+                        success: false,
+                        stdout: String::new(),
+                        stderr: "Command timed out".to_string(),
+                        exit_code: None,
+                        error: Some("Command execution timeout".to_string()),
+                        executed_in: cwd.clone(),
+                    }
+                }
+            };
+
+            if let Some(conv_id) = conv_id {
+                append_history_entry(
+                    &history,
+                    history_store.as_ref(),
+                    max_history,
+                    &conv_id,
+                    &command_owned,
+                    &result,
+                    None,
+                )
+                .await;
+            }
+        });
+
+        Ok(rx)
+    }
+
+    /// Recursively search files under `allowed_directory` for `query`,
+    /// honoring the same path-confinement rules as command execution.
+    pub fn search(&self, query: &SearchQuery) -> Result<Vec<SearchMatch>> {
+        search::search(query, &self.config.allowed_directory, &self.current_directory)
+    }
+
+    /// Watch `relative_path` (validated against `allowed_directory`) for
+    /// changes, returning an id to `unwatch` it later and a channel of
+    /// debounced events.
+    pub async fn watch_path(
+        &self,
+        conversation_id: &str,
+        relative_path: &str,
+        recursive: bool,
+    ) -> Result<(WatchId, mpsc::Receiver<WatchEvent>)> {
+        let validated = validate_path(relative_path, &self.config.allowed_directory, &self.current_directory)
+            .ok_or(crate::error::ShellError::PathValidationFailed)?;
+        self.watches.watch(conversation_id, &validated, recursive).await
+    }
+
+    /// Stop a single watch.
+    pub async fn unwatch(&self, id: &WatchId) -> Result<()> {
+        self.watches.unwatch(id).await
+    }
+
+    /// Stop every watch registered for `conversation_id`.
+    pub async fn clear_watches(&self, conversation_id: &str) {
+        self.watches.clear_watches(conversation_id).await;
+    }
+
+    /// Change a file's Unix permission bits without string-building a
+    /// `chmod` shell command. `path` is validated against
+    /// `allowed_directory` before the change is applied.
+    #[cfg(unix)]
+    pub fn set_permissions(&self, path: &str, mode: u32) -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let validated = validate_path(path, &self.config.allowed_directory, &self.current_directory)
+            .ok_or(crate::error::ShellError::PathValidationFailed)?;
+
+        std::fs::set_permissions(&validated, std::fs::Permissions::from_mode(mode))
+            .map_err(crate::error::ShellError::Io)
+    }
+
+    /// The remote shell daemon's negotiated protocol version, if the last
+    /// command ran against one (`config.target` isn't `"local"`).
+    pub fn remote_version(&self) -> Option<RemoteVersion> {
+        self.last_remote_version
+    }
+
     pub fn current_directory(&self) -> &Path {
         &self.current_directory
     }
@@ -85,7 +467,7 @@ impl ShellService {
         if trimmed_command.starts_with("cd ") {
             let result = self.handle_cd_command(trimmed_command);
             if let Some(conv_id) = conversation_id {
-                self.add_to_history(conv_id, trimmed_command, &result, None);
+                self.add_to_history(conv_id, trimmed_command, &result, None).await;
             }
             return Ok(result);
         }
@@ -98,13 +480,50 @@ impl ShellService {
             } else {
                 None
             };
-            self.add_to_history(conv_id, trimmed_command, &result, file_ops);
+            self.add_to_history(conv_id, trimmed_command, &result, file_ops).await;
         }
 
         Ok(result)
     }
 
-    fn handle_cd_command(&mut self, command: &str) -> CommandResult {
+    /// Run `steps` in order against `conversation_id`, one after another,
+    /// through the same gating (`is_safe_command`/`is_forbidden_command`)
+    /// and history recording as a standalone `execute_command`. A `cd` in
+    /// one step affects later steps, since every step runs via
+    /// `execute_command` against this same `&mut self`. Stops at the first
+    /// failed step whose `on_failure` is `OnFailure::Stop`; a failed step
+    /// with `OnFailure::Continue` just moves on to the next one.
+    pub async fn execute_batch(
+        &mut self,
+        conversation_id: Option<&str>,
+        steps: Vec<BatchStep>,
+    ) -> Result<BatchResult> {
+        let mut results = Vec::with_capacity(steps.len());
+        let mut success = true;
+        let mut stopped_at = None;
+
+        for (index, step) in steps.into_iter().enumerate() {
+            let result = self.execute_command(&step.command, conversation_id).await?;
+            let step_failed = !result.success;
+            results.push(result);
+
+            if step_failed {
+                success = false;
+                if step.on_failure == OnFailure::Stop {
+                    stopped_at = Some(index);
+                    break;
+                }
+            }
+        }
+
+        Ok(BatchResult {
+            results,
+            success,
+            stopped_at,
+        })
+    }
+
+    pub(crate) fn handle_cd_command(&mut self, command: &str) -> CommandResult {
         let parts: Vec<&str> = command.split_whitespace().collect();
 
         if parts.len() < 2 {
@@ -138,8 +557,45 @@ impl ShellService {
         }
     }
 
-    /// Run a command using tokio process.
-    async fn run_command(&self, command: &str) -> Result<CommandResult> {
+    /// Run a command, dispatching to the configured target and backend.
+    async fn run_command(&mut self, command: &str) -> Result<CommandResult> {
+        if self.config.target != "local" {
+            let (result, version) = transport::execute_remote(
+                &self.config.target,
+                command,
+                &self.current_directory.display().to_string(),
+                self.config.timeout_ms,
+                &self.config.forbidden_commands,
+            )
+            .await?;
+            self.last_remote_version = Some(version);
+            return Ok(result);
+        }
+
+        if let ShellBackend::Container {
+            image,
+            mounts,
+            network,
+            memory_limit,
+        } = &self.config.backend
+        {
+            return crate::container::run_in_container(
+                image,
+                mounts,
+                network.as_deref(),
+                memory_limit.as_deref(),
+                command,
+                &self.current_directory,
+                self.config.timeout_ms,
+            )
+            .await;
+        }
+
+        self.run_command_on_host(command).await
+    }
+
+    /// Run a command directly on the host using tokio process.
+    async fn run_command_on_host(&self, command: &str) -> Result<CommandResult> {
         let cwd = self.current_directory.display().to_string();
         let use_shell = command.contains('>') || command.contains('<') || command.contains('|');
 
@@ -224,38 +680,23 @@ impl ShellService {
         }
     }
 
-    fn add_to_history(
-        &mut self,
+    async fn add_to_history(
+        &self,
         conversation_id: &str,
         command: &str,
         result: &CommandResult,
         file_operations: Option<Vec<FileOperation>>,
     ) {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs_f64())
-            .unwrap_or(0.0);
-
-        let entry = CommandHistoryEntry {
-            command: command.to_string(),
-            stdout: result.stdout.clone(),
-            stderr: result.stderr.clone(),
-            exit_code: result.exit_code,
-            timestamp,
-            working_directory: result.executed_in.clone(),
+        append_history_entry(
+            &self.command_history,
+            self.history_store.as_ref(),
+            self.max_history_per_conversation,
+            conversation_id,
+            command,
+            result,
             file_operations,
-        };
-
-        let history = self
-            .command_history
-            .entry(conversation_id.to_string())
-            .or_default();
-
-        history.push(entry);
-
-        if history.len() > self.max_history_per_conversation {
-            history.remove(0);
-        }
+        )
+        .await;
     }
 
     fn detect_file_operations(&self, command: &str) -> Option<Vec<FileOperation>> {
@@ -282,6 +723,7 @@ impl ShellService {
                     op_type: FileOperationType::Create,
                     target: resolve_path(parts[1]),
                     secondary_target: None,
+                    permissions: None,
                 });
             }
             "echo" if command.contains('>') => {
@@ -293,6 +735,7 @@ impl ShellService {
                             op_type: FileOperationType::Write,
                             target: resolve_path(target),
                             secondary_target: None,
+                            permissions: None,
                         });
                     }
                 }
@@ -302,6 +745,7 @@ impl ShellService {
                     op_type: FileOperationType::Mkdir,
                     target: resolve_path(parts[1]),
                     secondary_target: None,
+                    permissions: None,
                 });
             }
             "cat" if parts.len() > 1 && !command.contains('>') => {
@@ -309,6 +753,7 @@ impl ShellService {
                     op_type: FileOperationType::Read,
                     target: resolve_path(parts[1]),
                     secondary_target: None,
+                    permissions: None,
                 });
             }
             "mv" if parts.len() > 2 => {
@@ -316,6 +761,7 @@ impl ShellService {
                     op_type: FileOperationType::Move,
                     target: resolve_path(parts[1]),
                     secondary_target: Some(resolve_path(parts[2])),
+                    permissions: None,
                 });
             }
             "cp" if parts.len() > 2 => {
@@ -323,6 +769,40 @@ impl ShellService {
                     op_type: FileOperationType::Copy,
                     target: resolve_path(parts[1]),
                     secondary_target: Some(resolve_path(parts[2])),
+                    permissions: None,
+                });
+            }
+            "chmod" if parts.len() > 2 => {
+                if let Ok(mode) = u32::from_str_radix(parts[1], 8) {
+                    operations.push(FileOperation {
+                        op_type: FileOperationType::SetPermissions,
+                        target: resolve_path(parts[2]),
+                        secondary_target: None,
+                        permissions: Some(PermissionChange {
+                            mode: Some(mode),
+                            owner: None,
+                            group: None,
+                        }),
+                    });
+                }
+            }
+            "chown" if parts.len() > 2 => {
+                let (owner, group) = match parts[1].split_once(':') {
+                    Some((owner, group)) => (
+                        (!owner.is_empty()).then(|| owner.to_string()),
+                        (!group.is_empty()).then(|| group.to_string()),
+                    ),
+                    None => (Some(parts[1].to_string()), None),
+                };
+                operations.push(FileOperation {
+                    op_type: FileOperationType::SetOwner,
+                    target: resolve_path(parts[2]),
+                    secondary_target: None,
+                    permissions: Some(PermissionChange {
+                        mode: None,
+                        owner,
+                        group,
+                    }),
                 });
             }
             _ => {}
@@ -335,25 +815,52 @@ impl ShellService {
         }
     }
 
-    pub fn get_command_history(
+    /// Recent history for `conversation_id`. Checked in-memory first; if
+    /// that window is empty (most commonly because the process restarted
+    /// since those commands ran) and a `history_store` is configured, falls
+    /// back to it so cross-session auditing still works.
+    pub async fn get_command_history(
         &self,
         conversation_id: &str,
         limit: Option<usize>,
     ) -> Vec<CommandHistoryEntry> {
         let history = self
             .command_history
+            .lock()
+            .expect("command history lock poisoned")
             .get(conversation_id)
             .cloned()
             .unwrap_or_default();
 
+        if history.is_empty() {
+            if let Some(store) = &self.history_store {
+                match store.get(conversation_id, limit).await {
+                    Ok(persisted) => return persisted,
+                    Err(e) => {
+                        tracing::warn!("failed to load persisted command history: {e}");
+                    }
+                }
+            }
+        }
+
         match limit {
             Some(n) if n > 0 => history.into_iter().rev().take(n).rev().collect(),
             _ => history,
         }
     }
 
-    pub fn clear_command_history(&mut self, conversation_id: &str) {
-        self.command_history.remove(conversation_id);
+    pub async fn clear_command_history(&self, conversation_id: &str) {
+        self.command_history
+            .lock()
+            .expect("command history lock poisoned")
+            .remove(conversation_id);
+
+        if let Some(store) = &self.history_store {
+            if let Err(e) = store.clear(conversation_id).await {
+                tracing::warn!("failed to clear persisted command history: {e}");
+            }
+        }
+
         info!(
             "Cleared command history for conversation: {}",
             conversation_id
@@ -369,6 +876,67 @@ impl ShellService {
     }
 }
 
+/// Append a history entry under `conversation_id`, evicting the oldest
+/// in-memory entry once `max_history_per_conversation` is exceeded, and
+/// persisting it to `history_store` (if any). Free-standing (not a
+/// `ShellService` method) so `execute_command_streaming`'s background
+/// completion task can call it with just the shared `Arc<Mutex<_>>`/
+/// `Arc<dyn CommandHistoryStore>` handles, without needing `&mut self` for
+/// the command's whole lifetime.
+async fn append_history_entry(
+    command_history: &StdMutex<HashMap<String, Vec<CommandHistoryEntry>>>,
+    history_store: Option<&Arc<dyn CommandHistoryStore>>,
+    max_history_per_conversation: usize,
+    conversation_id: &str,
+    command: &str,
+    result: &CommandResult,
+    file_operations: Option<Vec<FileOperation>>,
+) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+
+    let entry = CommandHistoryEntry {
+        command: command.to_string(),
+        stdout: result.stdout.clone(),
+        stderr: result.stderr.clone(),
+        exit_code: result.exit_code,
+        timestamp,
+        working_directory: result.executed_in.clone(),
+        file_operations,
+    };
+
+    {
+        let mut command_history = command_history.lock().expect("command history lock poisoned");
+        let history = command_history.entry(conversation_id.to_string()).or_default();
+
+        history.push(entry.clone());
+
+        if history.len() > max_history_per_conversation {
+            history.remove(0);
+        }
+    }
+
+    if let Some(store) = history_store {
+        if let Err(e) = store.append(conversation_id, &entry).await {
+            tracing::warn!("failed to persist command history entry: {e}");
+        }
+    }
+}
+
+/// Append `chunk` to the retained tail for a stream, trimming from the
+/// front once it exceeds `STREAMING_HISTORY_TAIL_BYTES`.
+fn append_tail(tail: &StdMutex<(Vec<u8>, Vec<u8>)>, is_stdout: bool, chunk: &[u8]) {
+    let mut guard = tail.lock().expect("tail lock poisoned");
+    let buf = if is_stdout { &mut guard.0 } else { &mut guard.1 };
+    buf.extend_from_slice(chunk);
+    if buf.len() > STREAMING_HISTORY_TAIL_BYTES {
+        let excess = buf.len() - STREAMING_HISTORY_TAIL_BYTES;
+        buf.drain(0..excess);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -381,6 +949,10 @@ mod tests {
             allowed_directory: dir.keep(),
             timeout_ms: 30000,
             forbidden_commands: vec!["rm".to_string(), "rmdir".to_string()],
+            backend: ShellBackend::Host,
+            target: "local".to_string(),
+            pty: crate::types::PtyConfig::default(),
+            watch_debounce_ms: 300,
         }
     }
 
@@ -416,7 +988,7 @@ mod tests {
             .await
             .unwrap();
 
-        let history = service.get_command_history(conv_id, None);
+        let history = service.get_command_history(conv_id, None).await;
         assert_eq!(history.len(), 1);
         assert_eq!(history[0].command, "echo hello");
     }
@@ -431,9 +1003,96 @@ mod tests {
             .execute_command("echo test", Some(conv_id))
             .await
             .unwrap();
-        assert_eq!(service.get_command_history(conv_id, None).len(), 1);
+        assert_eq!(service.get_command_history(conv_id, None).await.len(), 1);
+
+        service.clear_command_history(conv_id).await;
+        assert_eq!(service.get_command_history(conv_id, None).await.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_history_falls_back_to_persistent_store_after_restart() {
+        let config = test_config();
+        let store: Arc<dyn CommandHistoryStore> =
+            Arc::new(crate::history_store::InMemoryHistoryStore::new());
+        let conv_id = "test-conv";
+
+        let mut service = ShellService::with_history_store(config.clone(), store.clone());
+        service
+            .execute_command("echo persisted", Some(conv_id))
+            .await
+            .unwrap();
+
+        // A fresh service (simulating a restart) has no in-memory history,
+        // but shares the same durable store.
+        let service = ShellService::with_history_store(config, store);
+        let history = service.get_command_history(conv_id, None).await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].command, "echo persisted");
+    }
+
+    #[tokio::test]
+    async fn test_batch_stops_on_first_failure_by_default() {
+        let config = test_config();
+        let mut service = ShellService::new(config);
+
+        let batch = service
+            .execute_batch(
+                Some("test-conv"),
+                vec![
+                    BatchStep { command: "echo one".to_string(), on_failure: OnFailure::Stop },
+                    BatchStep { command: "rm file.txt".to_string(), on_failure: OnFailure::Stop },
+                    BatchStep { command: "echo two".to_string(), on_failure: OnFailure::Stop },
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert!(!batch.success);
+        assert_eq!(batch.stopped_at, Some(1));
+        assert_eq!(batch.results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_continues_past_failure_when_requested() {
+        let config = test_config();
+        let mut service = ShellService::new(config);
+
+        let batch = service
+            .execute_batch(
+                Some("test-conv"),
+                vec![
+                    BatchStep { command: "rm file.txt".to_string(), on_failure: OnFailure::Continue },
+                    BatchStep { command: "echo two".to_string(), on_failure: OnFailure::Stop },
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert!(!batch.success);
+        assert_eq!(batch.stopped_at, None);
+        assert_eq!(batch.results.len(), 2);
+        assert!(batch.results[1].success);
+    }
+
+    #[tokio::test]
+    async fn test_batch_preserves_cd_across_steps() {
+        let config = test_config();
+        let subdir = config.allowed_directory.join("sub");
+        std::fs::create_dir(&subdir).unwrap();
+        let mut service = ShellService::new(config);
+
+        let batch = service
+            .execute_batch(
+                Some("test-conv"),
+                vec![
+                    BatchStep { command: "cd sub".to_string(), on_failure: OnFailure::Stop },
+                    BatchStep { command: "pwd".to_string(), on_failure: OnFailure::Stop },
+                ],
+            )
+            .await
+            .unwrap();
 
-        service.clear_command_history(conv_id);
-        assert_eq!(service.get_command_history(conv_id, None).len(), 0);
+        assert!(batch.success);
+        assert!(batch.results[1].stdout.trim().ends_with("sub"));
     }
 }
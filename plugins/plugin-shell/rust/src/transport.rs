@@ -0,0 +1,149 @@
+#![allow(missing_docs)]
+//! Transport layer for where shell commands actually execute.
+//!
+//! `ShellConfig::target` selects between running locally in-process (the
+//! original behavior) or forwarding to a remote agent-side daemon over TCP,
+//! similar to how `distant` separates a client from the server that
+//! actually performs process/filesystem operations. Both sides exchange the
+//! existing `CommandResult` type, so callers don't need to care which
+//! transport is in play.
+
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::error::ShellError;
+use crate::types::CommandResult;
+use crate::Result;
+
+/// This client's protocol version. A major-version mismatch with the remote
+/// daemon is refused; minor differences are tolerated.
+pub const PROTOCOL_MAJOR: u16 = 1;
+pub const PROTOCOL_MINOR: u16 = 0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Handshake {
+    major: u16,
+    minor: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RemoteRequest {
+    command: String,
+    working_directory: String,
+    timeout_ms: u64,
+    forbidden_commands: Vec<String>,
+}
+
+/// The negotiated protocol version of a remote shell daemon, returned
+/// alongside the command result so callers can log which remote they ran
+/// against.
+#[derive(Debug, Clone, Copy)]
+pub struct RemoteVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+/// Execute `command` against the remote shell daemon at `address`
+/// (`host:port`), returning the same `CommandResult` a local execution
+/// would produce.
+///
+/// Performs the version handshake first: this client sends its
+/// major/minor, the daemon replies with its own, and a major-version
+/// mismatch is refused as `ShellError::Config` before any command is sent.
+pub async fn execute_remote(
+    address: &str,
+    command: &str,
+    working_directory: &str,
+    timeout_ms: u64,
+    forbidden_commands: &[String],
+) -> Result<(CommandResult, RemoteVersion)> {
+    let addr = address
+        .to_socket_addrs()
+        .map_err(|e| ShellError::Config(format!("invalid remote target {address}: {e}")))?
+        .next()
+        .ok_or_else(|| ShellError::Config(format!("could not resolve remote target {address}")))?;
+
+    let mut stream = TcpStream::connect(addr)
+        .await
+        .map_err(|e| ShellError::ExecutionFailed(format!("failed to connect to {address}: {e}")))?;
+
+    write_frame(
+        &mut stream,
+        &serde_json::to_vec(&Handshake {
+            major: PROTOCOL_MAJOR,
+            minor: PROTOCOL_MINOR,
+        })?,
+    )
+    .await?;
+    let their_handshake: Handshake = serde_json::from_slice(&read_frame(&mut stream).await?)?;
+
+    if their_handshake.major != PROTOCOL_MAJOR {
+        return Err(ShellError::Config(format!(
+            "remote shell daemon at {address} speaks protocol v{}.{}, this client speaks v{}.{}",
+            their_handshake.major, their_handshake.minor, PROTOCOL_MAJOR, PROTOCOL_MINOR
+        )));
+    }
+
+    let request = RemoteRequest {
+        command: command.to_string(),
+        working_directory: working_directory.to_string(),
+        timeout_ms,
+        forbidden_commands: forbidden_commands.to_vec(),
+    };
+    write_frame(&mut stream, &serde_json::to_vec(&request)?).await?;
+
+    // The daemon enforces `timeout_ms` itself; give it a little extra room
+    // to reply before the client gives up waiting.
+    let response = timeout(
+        Duration::from_millis(timeout_ms.saturating_add(5_000)),
+        read_frame(&mut stream),
+    )
+    .await
+    .map_err(|_| ShellError::Timeout)??;
+
+    let result: CommandResult = serde_json::from_slice(&response)?;
+    Ok((
+        result,
+        RemoteVersion {
+            major: their_handshake.major,
+            minor: their_handshake.minor,
+        },
+    ))
+}
+
+/// Length-prefixed frame: a big-endian u32 length followed by that many
+/// bytes of payload.
+async fn write_frame(stream: &mut TcpStream, payload: &[u8]) -> Result<()> {
+    stream.write_u32(payload.len() as u32).await?;
+    stream.write_all(payload).await?;
+    Ok(())
+}
+
+async fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>> {
+    let len = stream.read_u32().await? as usize;
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handshake_roundtrips() {
+        let handshake = Handshake {
+            major: PROTOCOL_MAJOR,
+            minor: PROTOCOL_MINOR,
+        };
+        let bytes = serde_json::to_vec(&handshake).unwrap();
+        let parsed: Handshake = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(parsed.major, handshake.major);
+        assert_eq!(parsed.minor, handshake.minor);
+    }
+}
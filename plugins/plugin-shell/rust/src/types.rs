@@ -19,6 +19,20 @@ pub enum FileOperationType {
     Mkdir,
     Move,
     Copy,
+    SetPermissions,
+    SetOwner,
+}
+
+/// A Unix permission/ownership change, as recorded by `chmod`/`chown` (or
+/// set explicitly via `ShellService::set_permissions`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionChange {
+    /// Octal permission bits (e.g. `0o644`), if the command changed mode.
+    pub mode: Option<u32>,
+    /// New owner user, if the command changed ownership.
+    pub owner: Option<String>,
+    /// New owner group, if the command changed ownership.
+    pub group: Option<String>,
 }
 
 /// File operation performed by a command
@@ -31,6 +45,10 @@ pub struct FileOperation {
     pub target: String,
     /// Secondary target for move/copy operations
     pub secondary_target: Option<String>,
+    /// Permission/ownership change, present for `SetPermissions`/`SetOwner`
+    /// operations.
+    #[serde(default)]
+    pub permissions: Option<PermissionChange>,
 }
 
 /// Result of a command execution
@@ -76,6 +94,57 @@ impl CommandResult {
     }
 }
 
+/// What to do when a `BatchStep` fails (non-zero exit or execution error).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFailure {
+    /// Abort the remaining steps.
+    Stop,
+    /// Run the remaining steps anyway.
+    Continue,
+}
+
+/// A single command in a batch, plus what to do if it fails.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchStep {
+    /// The command to run.
+    pub command: String,
+    /// What to do if this step fails.
+    #[serde(default = "default_on_failure")]
+    pub on_failure: OnFailure,
+}
+
+fn default_on_failure() -> OnFailure {
+    OnFailure::Stop
+}
+
+/// Outcome of `ShellService::execute_batch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    /// Result of each step that ran, in order.
+    pub results: Vec<CommandResult>,
+    /// Whether every step that ran succeeded.
+    pub success: bool,
+    /// Index of the step that aborted the batch via `OnFailure::Stop`, if
+    /// any.
+    pub stopped_at: Option<usize>,
+}
+
+/// A chunk of a command's output, delivered incrementally instead of
+/// waiting for the process to exit. See `ShellService::execute_command_streaming`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CommandChunk {
+    /// Bytes read from the command's stdout.
+    Stdout { bytes: Vec<u8> },
+    /// Bytes read from the command's stderr.
+    Stderr { bytes: Vec<u8> },
+    /// The command exited with this code (`None` if terminated abnormally).
+    Exit { code: Option<i32> },
+    /// The command was killed after exceeding `timeout_ms`.
+    TimedOut,
+}
+
 /// Entry in the command history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommandHistoryEntry {
@@ -95,6 +164,58 @@ pub struct CommandHistoryEntry {
     pub file_operations: Option<Vec<FileOperation>>,
 }
 
+/// Where commands actually run.
+///
+/// `Host` is the original behavior (the current process's host, confined
+/// only by `forbidden_commands`/`allowed_directory`). `Container` runs each
+/// command inside an ephemeral Docker/OCI container instead, with
+/// `allowed_directory` bind-mounted as the container's working directory,
+/// for real isolation from untrusted commands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ShellBackend {
+    Host,
+    Container {
+        /// Base image commands are executed against (e.g. `"alpine:latest"`).
+        image: String,
+        /// Extra bind mounts, in Docker's `host:container[:ro]` form, beyond
+        /// the implicit `allowed_directory` mount.
+        #[serde(default)]
+        mounts: Vec<String>,
+        /// Docker network mode (e.g. `"bridge"`, `"none"`). `None` uses the
+        /// Docker default.
+        #[serde(default)]
+        network: Option<String>,
+        /// Memory limit (e.g. `"512m"`, `"1g"`). `None` means unlimited.
+        #[serde(default)]
+        memory_limit: Option<String>,
+    },
+}
+
+impl Default for ShellBackend {
+    fn default() -> Self {
+        ShellBackend::Host
+    }
+}
+
+/// Pseudo-terminal sizing/environment for interactive `ShellSession`s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PtyConfig {
+    pub rows: u16,
+    pub cols: u16,
+    pub term: String,
+}
+
+impl Default for PtyConfig {
+    fn default() -> Self {
+        Self {
+            rows: 24,
+            cols: 80,
+            term: "xterm-256color".to_string(),
+        }
+    }
+}
+
 /// Shell plugin configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShellConfig {
@@ -106,8 +227,30 @@ pub struct ShellConfig {
     pub timeout_ms: u64,
     /// List of forbidden commands/patterns
     pub forbidden_commands: Vec<String>,
+    /// Where commands are executed (host process or an ephemeral container)
+    #[serde(default)]
+    pub backend: ShellBackend,
+    /// Where the command actually runs: `"local"` (default) or a
+    /// `host:port` address of a remote shell daemon.
+    #[serde(default = "default_target")]
+    pub target: String,
+    /// Sizing/environment for interactive PTY sessions (see `ShellSession`)
+    #[serde(default)]
+    pub pty: PtyConfig,
+    /// How long to coalesce rapid filesystem events for the same path
+    /// before delivering them (see `watch_path`), so e.g. an editor that
+    /// writes a temp file then renames it doesn't flood consumers.
+    #[serde(default = "default_watch_debounce_ms")]
+    pub watch_debounce_ms: u64,
+}
+
+fn default_target() -> String {
+    "local".to_string()
 }
 
+fn default_watch_debounce_ms() -> u64 {
+    300
+}
 
 impl Default for ShellConfig {
     fn default() -> Self {
@@ -119,6 +262,10 @@ impl Default for ShellConfig {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            backend: ShellBackend::Host,
+            target: default_target(),
+            pty: PtyConfig::default(),
+            watch_debounce_ms: default_watch_debounce_ms(),
         }
     }
 }
@@ -156,11 +303,33 @@ impl ShellConfig {
         forbidden_commands.sort();
         forbidden_commands.dedup();
 
+        let backend = match env::var("SHELL_BACKEND").unwrap_or_else(|_| "host".to_string()).as_str() {
+            "container" => ShellBackend::Container {
+                image: env::var("SHELL_CONTAINER_IMAGE")
+                    .unwrap_or_else(|_| "alpine:latest".to_string()),
+                mounts: Vec::new(),
+                network: None,
+                memory_limit: None,
+            },
+            _ => ShellBackend::Host,
+        };
+
+        let target = env::var("SHELL_TARGET").unwrap_or_else(|_| default_target());
+
+        let watch_debounce_ms = env::var("SHELL_WATCH_DEBOUNCE_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(default_watch_debounce_ms);
+
         Ok(Self {
             enabled,
             allowed_directory,
             timeout_ms,
             forbidden_commands,
+            backend,
+            target,
+            pty: PtyConfig::default(),
+            watch_debounce_ms,
         })
     }
 }
@@ -172,6 +341,10 @@ pub struct ShellConfigBuilder {
     allowed_directory: Option<PathBuf>,
     timeout_ms: Option<u64>,
     forbidden_commands: Option<Vec<String>>,
+    backend: Option<ShellBackend>,
+    target: Option<String>,
+    pty: Option<PtyConfig>,
+    watch_debounce_ms: Option<u64>,
 }
 
 impl ShellConfigBuilder {
@@ -207,6 +380,31 @@ impl ShellConfigBuilder {
         self
     }
 
+    /// Set the execution backend (host process or an ephemeral container)
+    pub fn backend(mut self, backend: ShellBackend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Set the execution target: `"local"` or a `host:port` remote shell
+    /// daemon address.
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Set the pseudo-terminal sizing/environment for interactive sessions
+    pub fn pty(mut self, pty: PtyConfig) -> Self {
+        self.pty = Some(pty);
+        self
+    }
+
+    /// Set the debounce window for coalescing filesystem watch events.
+    pub fn watch_debounce_ms(mut self, debounce_ms: u64) -> Self {
+        self.watch_debounce_ms = Some(debounce_ms);
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> Result<ShellConfig> {
         let allowed_directory = self.allowed_directory
@@ -244,6 +442,10 @@ impl ShellConfigBuilder {
             allowed_directory,
             timeout_ms: self.timeout_ms.unwrap_or(30000),
             forbidden_commands,
+            backend: self.backend.unwrap_or_default(),
+            target: self.target.unwrap_or_else(default_target),
+            pty: self.pty.unwrap_or_default(),
+            watch_debounce_ms: self.watch_debounce_ms.unwrap_or_else(default_watch_debounce_ms),
         })
     }
 }
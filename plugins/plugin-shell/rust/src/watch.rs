@@ -0,0 +1,167 @@
+#![allow(missing_docs)]
+//! Filesystem watch subsystem scoped to `allowed_directory`.
+//!
+//! `detect_file_operations` only guesses at what a command did by parsing
+//! its text; this watches real filesystem events (via `notify`) so agents
+//! can react to out-of-band edits too (an external editor saving a file, a
+//! build tool generating output), turning the guess into ground truth.
+//! Rapid bursts (e.g. an editor that writes a temp file then renames it
+//! over the original) are coalesced within a debounce window before being
+//! delivered, keyed by path.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc as std_mpsc;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, RwLock};
+use uuid::Uuid;
+
+use crate::error::{Result, ShellError};
+
+/// Identifies a single active filesystem watch.
+pub type WatchId = Uuid;
+
+/// A debounced filesystem change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WatchEvent {
+    Created { path: String },
+    Modified { path: String },
+    Removed { path: String },
+    Renamed { from: String, to: String },
+}
+
+struct WatchState {
+    conversation_id: String,
+    /// Kept alive only so the OS-level watch is torn down when the entry is
+    /// removed; `notify` stops watching once its `Watcher` is dropped.
+    _watcher: RecommendedWatcher,
+}
+
+/// Registry of active filesystem watches, keyed by `WatchId`.
+pub struct WatchRegistry {
+    watches: RwLock<HashMap<WatchId, WatchState>>,
+    debounce: Duration,
+}
+
+impl WatchRegistry {
+    pub fn new(debounce: Duration) -> Self {
+        Self {
+            watches: RwLock::new(HashMap::new()),
+            debounce,
+        }
+    }
+
+    /// Start watching `path` (already validated against `allowed_directory`
+    /// by the caller), returning its id and a channel of debounced events.
+    pub async fn watch(
+        &self,
+        conversation_id: &str,
+        path: &Path,
+        recursive: bool,
+    ) -> Result<(WatchId, mpsc::Receiver<WatchEvent>)> {
+        let (raw_tx, raw_rx) = std_mpsc::channel::<notify::Result<Event>>();
+
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = raw_tx.send(res);
+        })
+        .map_err(|e| ShellError::ExecutionFailed(format!("failed to create filesystem watcher: {e}")))?;
+
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher
+            .watch(path, mode)
+            .map_err(|e| ShellError::ExecutionFailed(format!("failed to watch {}: {e}", path.display())))?;
+
+        let (tx, rx) = mpsc::channel::<WatchEvent>(64);
+        let debounce = self.debounce;
+        std::thread::spawn(move || debounce_loop(raw_rx, tx, debounce));
+
+        let id = Uuid::new_v4();
+        self.watches.write().await.insert(
+            id,
+            WatchState {
+                conversation_id: conversation_id.to_string(),
+                _watcher: watcher,
+            },
+        );
+
+        Ok((id, rx))
+    }
+
+    /// Stop a single watch.
+    pub async fn unwatch(&self, id: &WatchId) -> Result<()> {
+        self.watches
+            .write()
+            .await
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| ShellError::ExecutionFailed(format!("no such watch: {id}")))
+    }
+
+    /// Stop every watch registered for `conversation_id`.
+    pub async fn clear_watches(&self, conversation_id: &str) {
+        self.watches
+            .write()
+            .await
+            .retain(|_, state| state.conversation_id != conversation_id);
+    }
+}
+
+/// Drains raw `notify` events, coalescing same-path events within `debounce`
+/// before forwarding the latest one for each path.
+fn debounce_loop(raw_rx: std_mpsc::Receiver<notify::Result<Event>>, tx: mpsc::Sender<WatchEvent>, debounce: Duration) {
+    let mut pending: HashMap<PathBuf, WatchEvent> = HashMap::new();
+    loop {
+        match raw_rx.recv_timeout(debounce) {
+            Ok(Ok(event)) => {
+                if let Some(watch_event) = classify(event) {
+                    pending.insert(watch_event_key(&watch_event), watch_event);
+                }
+            }
+            Ok(Err(_)) => {}
+            Err(std_mpsc::RecvTimeoutError::Timeout) => {
+                for (_, event) in pending.drain() {
+                    if tx.blocking_send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(std_mpsc::RecvTimeoutError::Disconnected) => {
+                for (_, event) in pending.drain() {
+                    let _ = tx.blocking_send(event);
+                }
+                return;
+            }
+        }
+    }
+}
+
+fn classify(event: Event) -> Option<WatchEvent> {
+    let path = event.paths.first()?.display().to_string();
+    match event.kind {
+        EventKind::Create(_) => Some(WatchEvent::Created { path }),
+        EventKind::Modify(notify::event::ModifyKind::Name(_)) if event.paths.len() >= 2 => Some(WatchEvent::Renamed {
+            from: event.paths[0].display().to_string(),
+            to: event.paths[1].display().to_string(),
+        }),
+        EventKind::Modify(_) => Some(WatchEvent::Modified { path }),
+        EventKind::Remove(_) => Some(WatchEvent::Removed { path }),
+        _ => None,
+    }
+}
+
+fn watch_event_key(event: &WatchEvent) -> PathBuf {
+    match event {
+        WatchEvent::Created { path } | WatchEvent::Modified { path } | WatchEvent::Removed { path } => {
+            PathBuf::from(path)
+        }
+        WatchEvent::Renamed { to, .. } => PathBuf::from(to),
+    }
+}
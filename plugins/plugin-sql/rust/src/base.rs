@@ -30,6 +30,34 @@ pub struct EmbeddingSearchResult {
     pub similarity: Option<f64>,
 }
 
+/// Parameters for [`DatabaseAdapter::hybrid_search_memories`].
+#[derive(Clone, Debug)]
+pub struct HybridSearchMemoriesParams {
+    /// Query embedding for the vector-similarity leg
+    pub embedding: Vec<f32>,
+    /// Query text for the full-text leg (tokenized with `to_tsvector`/`plainto_tsquery`)
+    pub query: String,
+    /// Table name
+    pub table_name: String,
+    /// Number of fused results to return
+    pub count: Option<i32>,
+    /// How many candidates each leg contributes before fusion (defaults to `count`, widened a
+    /// bit so fusion has more than `count` candidates per list to rank over)
+    pub candidate_count: Option<i32>,
+    /// Reciprocal rank fusion constant `k` (defaults to 60, the standard RRF choice)
+    pub rrf_k: Option<f64>,
+    /// Weight applied to the vector leg's contribution before summing (defaults to 1.0)
+    pub vector_weight: Option<f64>,
+    /// Weight applied to the full-text leg's contribution before summing (defaults to 1.0)
+    pub text_weight: Option<f64>,
+    /// Room ID filter
+    pub room_id: Option<UUID>,
+    /// World ID filter
+    pub world_id: Option<UUID>,
+    /// Entity ID filter
+    pub entity_id: Option<UUID>,
+}
+
 #[derive(Clone, Debug)]
 pub struct GetCachedEmbeddingsParams {
     pub table_name: String,
@@ -46,13 +74,117 @@ pub struct LogParams {
     pub log_type: String,
 }
 
+/// Reusable filter set for time-windowed, paginated queries over memories and logs, so both
+/// [`DatabaseAdapter::query_memories`] and [`DatabaseAdapter::get_logs`] can build their SQL the
+/// same way instead of each growing their own bespoke set of optional filters.
 #[derive(Clone, Debug, Default)]
-pub struct GetLogsParams {
-    pub entity_id: Option<UUID>,
+pub struct QueryFilters {
+    /// Only include rows created at or after this timestamp (ms since epoch)
+    pub after: Option<i64>,
+    /// Only include rows created at or before this timestamp (ms since epoch)
+    pub before: Option<i64>,
+    /// Restrict to these room IDs
+    pub room_ids: Option<Vec<UUID>>,
+    /// Restrict to these entity IDs
+    pub entity_ids: Option<Vec<UUID>>,
+    /// Restrict to these types (memory table name for `query_memories`, log type for `get_logs`)
+    pub types: Option<Vec<String>>,
+    /// Exclude these types
+    pub exclude_types: Option<Vec<String>>,
+    /// Maximum rows to return
+    pub limit: Option<i64>,
+    /// Rows to skip before collecting `limit`, for stable pagination
+    pub offset: Option<i64>,
+    /// Order ascending by `created_at` instead of the default descending
+    pub reverse: bool,
+}
+
+/// IRC-CHATHISTORY-style positional selector for [`DatabaseAdapter::get_memories_windowed`]: pages
+/// a room's memories by position (relative to a timestamp) rather than only fetching the newest N.
+#[derive(Clone, Copy, Debug)]
+pub enum MemoryWindowSelector {
+    /// The most recent `count` memories.
+    Latest {
+        /// Maximum memories to return.
+        count: i64,
+    },
+    /// Up to `count` memories created strictly before `timestamp` (ms since epoch).
+    Before {
+        /// Exclusive upper bound, ms since epoch.
+        timestamp: i64,
+        /// Maximum memories to return.
+        count: i64,
+    },
+    /// Up to `count` memories created strictly after `timestamp` (ms since epoch).
+    After {
+        /// Exclusive lower bound, ms since epoch.
+        timestamp: i64,
+        /// Maximum memories to return.
+        count: i64,
+    },
+    /// Up to `count` memories centered on `timestamp`, split evenly between the memories
+    /// immediately before and after it.
+    Around {
+        /// Center point, ms since epoch.
+        timestamp: i64,
+        /// Maximum memories to return.
+        count: i64,
+    },
+    /// Up to `count` memories with `start <= created_at <= end` (ms since epoch).
+    Between {
+        /// Inclusive lower bound, ms since epoch.
+        start: i64,
+        /// Inclusive upper bound, ms since epoch.
+        end: i64,
+        /// Maximum memories to return.
+        count: i64,
+    },
+}
+
+/// Parameters for [`DatabaseAdapter::get_memories_windowed`].
+#[derive(Clone, Debug)]
+pub struct MemoryWindowParams {
+    /// Table name
+    pub table_name: String,
+    /// Room ID filter
     pub room_id: Option<UUID>,
-    pub log_type: Option<String>,
-    pub count: Option<i32>,
-    pub offset: Option<i32>,
+    /// Entity ID filter
+    pub entity_id: Option<UUID>,
+    /// World ID filter
+    pub world_id: Option<UUID>,
+    /// Which window of memories to return
+    pub selector: MemoryWindowSelector,
+}
+
+/// Result of [`DatabaseAdapter::get_memories_windowed`]: a bounded batch of memories ordered
+/// oldest-to-newest, plus the boundary timestamps a caller needs to request the next page
+/// without gaps or overlaps (e.g. `Before { timestamp: oldest, .. }` to continue scrolling back).
+#[derive(Clone, Debug, Default)]
+pub struct MemoryWindowResult {
+    /// The memories in this window, ordered oldest-to-newest.
+    pub memories: Vec<Memory>,
+    /// `created_at` of the oldest memory returned (ms since epoch), or `None` if empty.
+    pub oldest: Option<i64>,
+    /// `created_at` of the newest memory returned (ms since epoch), or `None` if empty.
+    pub newest: Option<i64>,
+}
+
+/// Aggregate health/overview stats for a room's memory store, as returned by
+/// [`DatabaseAdapter::memory_stats`].
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStats {
+    /// Total number of memories
+    pub total: i64,
+    /// Number of memories with a row in `embeddings`
+    pub with_embedding: i64,
+    /// Number of memories with `unique = true`
+    pub unique_count: i64,
+    /// `created_at` of the oldest memory (ms since epoch), or `None` if there are no memories
+    pub earliest_created_at: Option<i64>,
+    /// `created_at` of the newest memory (ms since epoch), or `None` if there are no memories
+    pub latest_created_at: Option<i64>,
+    /// Per-`type` breakdown of `total`, keyed by memory table name
+    pub by_type: std::collections::HashMap<String, i64>,
 }
 
 #[derive(Clone, Debug)]
@@ -69,10 +201,39 @@ pub struct GetRelationshipParams {
     pub target_entity_id: UUID,
 }
 
+/// Which side of a relationship edge [`DatabaseAdapter::get_relationships_filtered`] should match
+/// against `entity_id`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RelationshipDirection {
+    /// `entity_id` is the source (relationships this entity points at others)
+    Outgoing,
+    /// `entity_id` is the target (relationships others point at this entity)
+    Incoming,
+    /// `entity_id` is either the source or the target
+    #[default]
+    Either,
+}
+
 #[derive(Clone, Debug)]
 pub struct GetRelationshipsParams {
     pub entity_id: UUID,
     pub tags: Option<Vec<String>>,
+    /// Restrict to outgoing, incoming, or either-direction edges. Defaults to [`RelationshipDirection::Either`].
+    pub direction: RelationshipDirection,
+    /// Only include relationships whose metadata `strength` is at least this value.
+    pub min_strength: Option<f64>,
+    /// Cap the number of rows returned, ordered by `strength` descending.
+    pub limit: Option<i64>,
+}
+
+/// How a task created via [`DatabaseAdapter::create_periodic_task`] should recur.
+#[derive(Clone, Debug)]
+pub enum TaskSchedule {
+    /// Re-run every `interval_ms` milliseconds after each completion
+    Interval(i64),
+    /// Re-run at this cron expression's next fire time (strictly after `now()`) after each
+    /// completion
+    Cron(String),
 }
 
 #[derive(Clone, Debug, Default)]
@@ -235,17 +396,47 @@ pub trait DatabaseAdapter: Send + Sync {
     /// Search memories by embedding
     async fn search_memories(&self, params: SearchMemoriesParams) -> Result<Vec<Memory>>;
 
+    /// Search memories by fusing vector-similarity and full-text relevance so exact keyword
+    /// matches that embeddings under-rank (names, IDs, rare tokens) aren't lost. Runs both
+    /// queries independently, then combines them with reciprocal rank fusion: each list
+    /// contributes `1 / (k + r)` per memory at rank `r` (0-indexed, most relevant first),
+    /// scaled by that list's weight, summed across lists, and the fused scores populate
+    /// `Memory.similarity`. A memory present in only one list still gets that list's
+    /// contribution.
+    async fn hybrid_search_memories(
+        &self,
+        params: HybridSearchMemoriesParams,
+    ) -> Result<Vec<Memory>>;
+
+    /// Query memories with a composable, paginated filter set (time window, room/entity
+    /// inclusion, type inclusion/exclusion, deterministic ordering) instead of
+    /// [`GetMemoriesParams`]'s fixed single-room/single-entity shape. `filters.types` scopes
+    /// which memory tables are searched the same way `table_name` does elsewhere.
+    async fn query_memories(&self, filters: QueryFilters) -> Result<Vec<Memory>>;
+
+    /// Page a room's memories by position via [`MemoryWindowSelector`] (CHATHISTORY-style
+    /// `Latest`/`Before`/`After`/`Around`/`Between`) instead of only fetching the newest N.
+    /// Results are always ordered oldest-to-newest, and [`MemoryWindowResult::oldest`]/`newest`
+    /// let a caller chain the next page without gaps or overlaps.
+    async fn get_memories_windowed(&self, params: MemoryWindowParams) -> Result<MemoryWindowResult>;
+
     /// Create a memory
     async fn create_memory(&self, memory: &Memory, table_name: &str, unique: bool) -> Result<UUID>;
 
+    /// Create many memories as a single set-based write instead of one `create_memory` call per
+    /// row. Returns the ID assigned to each memory, in the same order as `memories`.
+    async fn create_memories(&self, memories: &[Memory], table_name: &str) -> Result<Vec<UUID>>;
+
     /// Update a memory
     async fn update_memory(&self, memory: &Memory) -> Result<bool>;
 
-    /// Delete a memory
-    async fn delete_memory(&self, memory_id: &UUID) -> Result<()>;
+    /// Delete a memory, returning the deleted record (if it existed) so callers can audit-log,
+    /// support undo, or cascade cleanup without a separate fetch-then-delete race.
+    async fn delete_memory(&self, memory_id: &UUID) -> Result<Option<Memory>>;
 
-    /// Delete many memories
-    async fn delete_many_memories(&self, memory_ids: &[UUID]) -> Result<()>;
+    /// Delete many memories, returning the deleted records. Since only the ids that actually
+    /// existed come back, this also tells the caller which of the supplied ids were no-ops.
+    async fn delete_many_memories(&self, memory_ids: &[UUID]) -> Result<Vec<Memory>>;
 
     /// Delete all memories in a room
     async fn delete_all_memories(&self, room_id: &UUID, table_name: &str) -> Result<()>;
@@ -258,6 +449,13 @@ pub trait DatabaseAdapter: Send + Sync {
         table_name: Option<&str>,
     ) -> Result<i64>;
 
+    /// Aggregate health/overview stats for a room's memory store (total count, count with an
+    /// embedding, earliest/latest `created_at`, a per-type breakdown, and the `unique = true`
+    /// count) computed with a single grouped query, instead of issuing one `count_memories` call
+    /// per question. `table_name` narrows to one memory table the same way it does on
+    /// [`DatabaseAdapter::count_memories`]; omit it to aggregate across every type in the room.
+    async fn memory_stats(&self, room_id: &UUID, table_name: Option<&str>) -> Result<MemoryStats>;
+
     /// Ensure embedding dimension
     async fn ensure_embedding_dimension(&self, dimension: i32) -> Result<()>;
 
@@ -277,7 +475,7 @@ pub trait DatabaseAdapter: Send + Sync {
     async fn log(&self, params: LogParams) -> Result<()>;
 
     /// Get log entries
-    async fn get_logs(&self, params: GetLogsParams) -> Result<Vec<Log>>;
+    async fn get_logs(&self, filters: QueryFilters) -> Result<Vec<Log>>;
 
     /// Delete a log entry
     async fn delete_log(&self, log_id: &UUID) -> Result<()>;
@@ -292,8 +490,8 @@ pub trait DatabaseAdapter: Send + Sync {
     /// Get a world by ID
     async fn get_world(&self, id: &UUID) -> Result<Option<World>>;
 
-    /// Remove a world
-    async fn remove_world(&self, id: &UUID) -> Result<()>;
+    /// Remove a world, returning the deleted record (if it existed)
+    async fn remove_world(&self, id: &UUID) -> Result<Option<World>>;
 
     /// Get all worlds
     async fn get_all_worlds(&self) -> Result<Vec<World>>;
@@ -380,6 +578,18 @@ pub trait DatabaseAdapter: Send + Sync {
     /// Get relationships for an entity
     async fn get_relationships(&self, params: GetRelationshipsParams) -> Result<Vec<Relationship>>;
 
+    /// Get relationships for an entity, filtered by direction and required tags and
+    /// thresholded/ordered by the `strength` stored in metadata.
+    ///
+    /// `params.direction` restricts which side of the edge `entity_id` must be on,
+    /// `params.tags` (if present) requires every listed tag via JSONB containment, and
+    /// `params.min_strength` (if present) drops edges below that strength. Results are ordered by
+    /// `strength` descending and capped at `params.limit` when set.
+    async fn get_relationships_filtered(
+        &self,
+        params: GetRelationshipsParams,
+    ) -> Result<Vec<Relationship>>;
+
     // =========================================================================
     // Cache Methods
     // =========================================================================
@@ -387,13 +597,28 @@ pub trait DatabaseAdapter: Send + Sync {
     /// Get a cached value
     async fn get_cache<T: serde::de::DeserializeOwned>(&self, key: &str) -> Result<Option<T>>;
 
-    /// Set a cached value
+    /// Set a cached value. Never expires on its own; use
+    /// [`DatabaseAdapter::set_cache_with_ttl`] for entries that should.
     async fn set_cache<T: serde::Serialize + Send + Sync>(
         &self,
         key: &str,
         value: &T,
     ) -> Result<bool>;
 
+    /// Set a cached value that expires `ttl` from now, so rate-limit counters, short-lived API
+    /// responses, and dedup windows don't have to be cleaned up by hand.
+    async fn set_cache_with_ttl<T: serde::Serialize + Send + Sync>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl: std::time::Duration,
+    ) -> Result<bool>;
+
+    /// Delete every cache row whose `expires_at` has passed, so entries set via
+    /// [`DatabaseAdapter::set_cache_with_ttl`] don't accumulate between reads. Returns the number
+    /// of rows purged.
+    async fn purge_expired_cache(&self) -> Result<u64>;
+
     /// Delete a cached value
     async fn delete_cache(&self, key: &str) -> Result<bool>;
 
@@ -404,6 +629,14 @@ pub trait DatabaseAdapter: Send + Sync {
     /// Create a task
     async fn create_task(&self, task: &Task) -> Result<UUID>;
 
+    /// Create a task idempotently: a SHA-256 hash over `(name, metadata, room_id, entity_id)`
+    /// is stored as `uniq_hash`, and a partial unique index rejects a second `pending`/`running`
+    /// row with the same hash. If a live duplicate already exists, its id is returned instead of
+    /// inserting a new row, so agents that re-issue the same action after a reconnect don't
+    /// schedule the same work twice. Tasks created via plain [`DatabaseAdapter::create_task`] are
+    /// unaffected — dedup only applies to tasks created through this method.
+    async fn create_task_idempotent(&self, task: &Task) -> Result<UUID>;
+
     /// Get tasks
     async fn get_tasks(&self, params: GetTasksParams) -> Result<Vec<Task>>;
 
@@ -418,4 +651,42 @@ pub trait DatabaseAdapter: Send + Sync {
 
     /// Delete a task
     async fn delete_task(&self, id: &UUID) -> Result<()>;
+
+    /// Atomically reserve up to `limit` pending, due (`scheduled_at` unset or in the past) tasks
+    /// for `worker_id`, so multiple workers pulling from the same queue never claim the same row
+    /// (`FOR UPDATE SKIP LOCKED` lets concurrent claims proceed without blocking on each other).
+    /// Claimed tasks move to `running` and are returned in claim order.
+    async fn claim_tasks(&self, limit: i64, worker_id: &str) -> Result<Vec<Task>>;
+
+    /// Record a task's failure: store `error_message`, increment its retry counter, and either
+    /// re-queue it with exponential backoff (`scheduled_at = now() + base * 2^retries`) or, once
+    /// `max_retries` is reached, move it to `failed`.
+    async fn fail_task(&self, id: &UUID, error_message: &str) -> Result<()>;
+
+    /// Mark a task as successfully completed. A task created with
+    /// [`DatabaseAdapter::create_periodic_task`] instead reschedules itself: its row advances to
+    /// the next fire time (computed from its `repeat_interval` or cron metadata) rather than
+    /// being deleted; a plain one-shot task's row is deleted.
+    async fn complete_task(&self, id: &UUID) -> Result<()>;
+
+    /// Create a task that recurs on a `schedule` instead of running once. `schedule` is stored on
+    /// the row (`repeat_interval` for [`TaskSchedule::Interval`], a `cronExpression` metadata key
+    /// for [`TaskSchedule::Cron`]) so a later [`DatabaseAdapter::complete_task`] call can consult
+    /// it to compute the next `scheduled_at` instead of deleting the row.
+    async fn create_periodic_task(&self, task: &Task, schedule: TaskSchedule) -> Result<UUID>;
+
+    // =========================================================================
+    // Blob Methods
+    // =========================================================================
+
+    /// Upload `bytes` to the configured object-storage backend and persist a `media` row
+    /// mapping a freshly generated media ID to the resulting URL. Returns that media ID.
+    async fn put_blob(&self, bytes: Vec<u8>, content_type: &str) -> Result<UUID>;
+
+    /// Fetch the bytes for a blob previously stored via [`DatabaseAdapter::put_blob`], or
+    /// `None` if `media_id` has no mapping.
+    async fn get_blob(&self, media_id: &UUID) -> Result<Option<Vec<u8>>>;
+
+    /// Delete a blob's object-storage data and its `media` mapping row.
+    async fn delete_blob(&self, media_id: &UUID) -> Result<()>;
 }
@@ -35,6 +35,9 @@ pub mod schema;
 #[cfg(feature = "native")]
 pub mod postgres;
 
+#[cfg(feature = "otel")]
+pub mod otel;
+
 #[cfg(feature = "wasm")]
 pub mod pglite;
 
@@ -48,7 +51,7 @@ pub use elizaos::types::*;
 pub use base::DatabaseAdapter;
 
 #[cfg(feature = "native")]
-pub use postgres::PostgresAdapter;
+pub use postgres::{PostgresAdapter, PostgresTransaction};
 
 #[cfg(feature = "wasm")]
 pub use pglite::PgLiteAdapter;
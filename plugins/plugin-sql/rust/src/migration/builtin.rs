@@ -0,0 +1,168 @@
+#![allow(missing_docs)]
+//! The core elizaOS schema, expressed as an ordered list of versioned migrations instead of the
+//! flat, unversioned list [`crate::postgres::PostgresConnectionManager::run_migrations`] runs.
+//! The DDL itself is the same SQL defined in [`crate::schema`]; it's just grouped per resource so
+//! each step can be tracked, checksummed, and rolled back independently.
+
+use crate::schema::*;
+
+use super::storage::Migration;
+
+/// The full set of core migrations, in the order they must apply.
+pub fn core_migrations() -> Vec<Migration> {
+    vec![
+        Migration::new(
+            1,
+            "ensure_vector_extension",
+            embedding::ENSURE_VECTOR_EXTENSION,
+            "-- left in place: dropping the `vector` extension could cascade into other schemas",
+        ),
+        Migration::new(
+            2,
+            "create_agents",
+            format!(
+                "{}\n{}",
+                agent::CREATE_AGENTS_TABLE,
+                agent::CREATE_AGENTS_INDEXES
+            ),
+            "DROP TABLE IF EXISTS agents CASCADE",
+        ),
+        Migration::new(
+            3,
+            "create_worlds",
+            format!(
+                "{}\n{}",
+                world::CREATE_WORLDS_TABLE,
+                world::CREATE_WORLDS_INDEXES
+            ),
+            "DROP TABLE IF EXISTS worlds CASCADE",
+        ),
+        Migration::new(
+            4,
+            "create_entities",
+            format!(
+                "{}\n{}",
+                entity::CREATE_ENTITIES_TABLE,
+                entity::CREATE_ENTITIES_INDEXES
+            ),
+            "DROP TABLE IF EXISTS entities CASCADE",
+        ),
+        Migration::new(
+            5,
+            "create_rooms",
+            format!(
+                "{}\n{}",
+                room::CREATE_ROOMS_TABLE,
+                room::CREATE_ROOMS_INDEXES
+            ),
+            "DROP TABLE IF EXISTS rooms CASCADE",
+        ),
+        Migration::new(
+            6,
+            "create_memories",
+            format!(
+                "{}\n{}",
+                memory::CREATE_MEMORIES_TABLE,
+                memory::CREATE_MEMORIES_INDEXES
+            ),
+            "DROP TABLE IF EXISTS memories CASCADE",
+        ),
+        Migration::new(
+            7,
+            "create_embeddings",
+            format!(
+                "{}\n{}",
+                embedding::create_embeddings_table_sql(embedding::DEFAULT_DIMENSION),
+                embedding::CREATE_EMBEDDINGS_INDEXES
+            ),
+            "DROP TABLE IF EXISTS embeddings CASCADE",
+        ),
+        Migration::new(
+            8,
+            "create_components",
+            format!(
+                "{}\n{}",
+                component::CREATE_COMPONENTS_TABLE,
+                component::CREATE_COMPONENTS_INDEXES
+            ),
+            "DROP TABLE IF EXISTS components CASCADE",
+        ),
+        Migration::new(
+            9,
+            "create_participants",
+            format!(
+                "{}\n{}",
+                participant::CREATE_PARTICIPANTS_TABLE,
+                participant::CREATE_PARTICIPANTS_INDEXES
+            ),
+            "DROP TABLE IF EXISTS participants CASCADE",
+        ),
+        Migration::new(
+            10,
+            "create_relationships",
+            format!(
+                "{}\n{}",
+                relationship::CREATE_RELATIONSHIPS_TABLE,
+                relationship::CREATE_RELATIONSHIPS_INDEXES
+            ),
+            "DROP TABLE IF EXISTS relationships CASCADE",
+        ),
+        Migration::new(
+            11,
+            "create_tasks",
+            format!(
+                "{}\n{}",
+                task::CREATE_TASKS_TABLE,
+                task::CREATE_TASKS_INDEXES
+            ),
+            "DROP TABLE IF EXISTS tasks CASCADE",
+        ),
+        Migration::new(
+            12,
+            "create_logs",
+            format!("{}\n{}", log::CREATE_LOGS_TABLE, log::CREATE_LOGS_INDEXES),
+            "DROP TABLE IF EXISTS logs CASCADE",
+        ),
+        Migration::new(
+            13,
+            "create_cache",
+            format!(
+                "{}\n{}",
+                cache::CREATE_CACHE_TABLE,
+                cache::CREATE_CACHE_INDEXES
+            ),
+            "DROP TABLE IF EXISTS cache CASCADE",
+        ),
+        Migration::new(
+            14,
+            "create_media",
+            format!(
+                "{}\n{}",
+                media::CREATE_MEDIA_TABLE,
+                media::CREATE_MEDIA_INDEXES
+            ),
+            "DROP TABLE IF EXISTS media CASCADE",
+        ),
+        Migration::new(
+            15,
+            "task_queue_columns",
+            task::ADD_TASKS_QUEUE_COLUMNS,
+            r#"
+            ALTER TABLE tasks
+                DROP COLUMN IF EXISTS retries,
+                DROP COLUMN IF EXISTS max_retries,
+                DROP COLUMN IF EXISTS error_message,
+                DROP COLUMN IF EXISTS locked_by
+            "#,
+        ),
+        Migration::new(
+            16,
+            "task_uniq_hash",
+            task::ADD_TASKS_UNIQ_HASH_COLUMN,
+            r#"
+            DROP INDEX IF EXISTS idx_tasks_uniq_hash_live;
+            ALTER TABLE tasks DROP COLUMN IF EXISTS uniq_hash;
+            "#,
+        ),
+    ]
+}
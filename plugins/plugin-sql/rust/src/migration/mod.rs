@@ -14,12 +14,14 @@
 //! - Transaction-safe migrations
 //! - Plugin schema namespacing
 
+pub mod builtin;
 pub mod schema_namespace;
 pub mod service;
 pub mod storage;
 pub mod tracker;
 
+pub use builtin::core_migrations;
 pub use schema_namespace::{derive_schema_name, SchemaNamespaceManager};
-pub use service::MigrationService;
-pub use storage::{JournalStorage, SnapshotStorage};
-pub use tracker::MigrationTracker;
+pub use service::{MigrationService, MigrationStatus};
+pub use storage::{JournalStorage, Migration, SnapshotStorage};
+pub use tracker::{AppliedMigration, MigrationTracker};
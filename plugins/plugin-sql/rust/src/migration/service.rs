@@ -0,0 +1,170 @@
+#![allow(missing_docs)]
+//! Orchestrates applying, rolling back, and reporting on migrations — the versioned alternative
+//! to [`crate::postgres::PostgresConnectionManager::run_migrations`]'s flat list of unversioned
+//! `CREATE TABLE IF NOT EXISTS` statements.
+
+use anyhow::{bail, Context, Result};
+use sqlx::PgPool;
+use std::collections::HashSet;
+
+use super::storage::JournalStorage;
+use super::tracker::{AppliedMigration, MigrationTracker};
+
+/// Applied vs. pending migrations, as reported by [`MigrationService::status`].
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub applied: Vec<AppliedMigration>,
+    pub pending: Vec<String>,
+}
+
+/// Drives a [`JournalStorage`]'s migrations against a database through a [`MigrationTracker`].
+pub struct MigrationService {
+    pool: PgPool,
+    journal: JournalStorage,
+    tracker: MigrationTracker,
+}
+
+impl MigrationService {
+    /// Create a new service over the given connection pool and migration journal.
+    pub fn new(pool: PgPool, journal: JournalStorage) -> Self {
+        let tracker = MigrationTracker::new(pool.clone());
+        Self {
+            pool,
+            journal,
+            tracker,
+        }
+    }
+
+    /// Apply every migration in the journal that hasn't been applied yet, each inside its own
+    /// transaction alongside its `schema_migrations` bookkeeping row. Refuses to apply anything
+    /// if an already-applied migration's SQL no longer matches what was recorded for it.
+    pub async fn migrate(&self) -> Result<MigrationStatus> {
+        self.tracker.ensure_table().await?;
+        let applied = self.tracker.applied().await?;
+        self.check_for_drift(&applied)?;
+
+        let applied_versions: HashSet<i64> = applied.iter().map(|m| m.version).collect();
+        for migration in self.journal.pending(&applied_versions) {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .context("Failed to begin transaction")?;
+
+            sqlx::query(&migration.up_sql)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Migration {} ({}) failed",
+                        migration.version, migration.name
+                    )
+                })?;
+
+            self.tracker
+                .record_applied(
+                    &mut tx,
+                    migration.version,
+                    &migration.name,
+                    &migration.checksum(),
+                )
+                .await?;
+
+            tx.commit().await.with_context(|| {
+                format!("Failed to commit migration {}", migration.version)
+            })?;
+        }
+
+        self.status().await
+    }
+
+    /// Roll back the `steps` most recently applied migrations, running each one's `down_sql` in
+    /// reverse order and removing its `schema_migrations` row.
+    pub async fn rollback(&self, steps: usize) -> Result<()> {
+        self.tracker.ensure_table().await?;
+        let mut applied = self.tracker.applied().await?;
+        applied.sort_by_key(|m| m.version);
+        applied.reverse();
+
+        for applied_migration in applied.into_iter().take(steps) {
+            let migration = self
+                .journal
+                .find(applied_migration.version)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Applied migration {} ({}) is no longer in the journal; cannot roll it back",
+                        applied_migration.version,
+                        applied_migration.name
+                    )
+                })?;
+
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .context("Failed to begin transaction")?;
+
+            sqlx::query(&migration.down_sql)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Rollback of migration {} ({}) failed",
+                        migration.version, migration.name
+                    )
+                })?;
+
+            self.tracker
+                .remove_applied(&mut tx, migration.version)
+                .await?;
+
+            tx.commit().await.with_context(|| {
+                format!("Failed to commit rollback of migration {}", migration.version)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Report which migrations are applied and which are still pending.
+    pub async fn status(&self) -> Result<MigrationStatus> {
+        self.tracker.ensure_table().await?;
+        let applied = self.tracker.applied().await?;
+        self.check_for_drift(&applied)?;
+
+        let applied_versions: HashSet<i64> = applied.iter().map(|m| m.version).collect();
+        let pending = self
+            .journal
+            .pending(&applied_versions)
+            .into_iter()
+            .map(|m| m.name.clone())
+            .collect();
+
+        Ok(MigrationStatus { applied, pending })
+    }
+
+    /// Compare every already-applied migration's recorded checksum against the journal's current
+    /// definition, refusing to proceed if any of them no longer match.
+    fn check_for_drift(&self, applied: &[AppliedMigration]) -> Result<()> {
+        for applied_migration in applied {
+            let Some(migration) = self.journal.find(applied_migration.version) else {
+                // A migration no longer in the journal only matters if someone tries to roll it
+                // back; it's not drift.
+                continue;
+            };
+
+            let current_checksum = migration.checksum();
+            if current_checksum != applied_migration.checksum {
+                bail!(
+                    "Migration {} ({}) has changed since it was applied: recorded checksum {}, \
+                     current checksum {}. Refusing to run further migrations until this is resolved.",
+                    applied_migration.version,
+                    applied_migration.name,
+                    applied_migration.checksum,
+                    current_checksum
+                );
+            }
+        }
+        Ok(())
+    }
+}
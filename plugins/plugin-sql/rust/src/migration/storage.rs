@@ -0,0 +1,176 @@
+#![allow(missing_docs)]
+//! In-memory definitions of the migrations a [`crate::migration::MigrationService`] can apply,
+//! plus point-in-time schema snapshots for diagnostics.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+/// A single migration step: a version, a human-readable name, and the SQL to apply it and to
+/// undo it.
+#[derive(Clone, Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub name: String,
+    pub up_sql: String,
+    pub down_sql: String,
+}
+
+impl Migration {
+    /// Construct a migration step.
+    pub fn new(
+        version: i64,
+        name: impl Into<String>,
+        up_sql: impl Into<String>,
+        down_sql: impl Into<String>,
+    ) -> Self {
+        Self {
+            version,
+            name: name.into(),
+            up_sql: up_sql.into(),
+            down_sql: down_sql.into(),
+        }
+    }
+
+    /// SHA-256 checksum of this migration's `up_sql`, used to detect drift in an
+    /// already-applied migration.
+    pub fn checksum(&self) -> String {
+        format!("{:x}", Sha256::digest(self.up_sql.as_bytes()))
+    }
+}
+
+/// The ordered list of migrations a [`MigrationService`](crate::migration::MigrationService) can
+/// apply or roll back, analogous to a migration "journal" file.
+pub struct JournalStorage {
+    migrations: Vec<Migration>,
+}
+
+impl JournalStorage {
+    /// Build a journal from an ordered list of migrations.
+    pub fn new(migrations: Vec<Migration>) -> Self {
+        Self { migrations }
+    }
+
+    /// Every migration in the journal, in version order.
+    pub fn all(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// Migrations whose version isn't in `applied_versions`, in version order.
+    pub fn pending(
+        &self,
+        applied_versions: &std::collections::HashSet<i64>,
+    ) -> Vec<&Migration> {
+        self.migrations
+            .iter()
+            .filter(|m| !applied_versions.contains(&m.version))
+            .collect()
+    }
+
+    /// The migration with the given version, if the journal has one.
+    pub fn find(&self, version: i64) -> Option<&Migration> {
+        self.migrations.iter().find(|m| m.version == version)
+    }
+}
+
+/// A point-in-time record of which migration version the schema was at, for diagnostics
+/// independent of the append-only `schema_migrations` history.
+#[derive(Clone, Debug)]
+pub struct SchemaSnapshot {
+    pub version: i64,
+    pub description: String,
+    pub taken_at: DateTime<Utc>,
+}
+
+/// Records schema snapshots in a `schema_snapshots` table.
+pub struct SnapshotStorage {
+    pool: PgPool,
+}
+
+impl SnapshotStorage {
+    /// Create a new snapshot store over the given connection pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create the `schema_snapshots` table if it doesn't already exist.
+    pub async fn ensure_table(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_snapshots (
+                id BIGSERIAL PRIMARY KEY,
+                version BIGINT NOT NULL,
+                description TEXT NOT NULL,
+                taken_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create schema_snapshots table")?;
+        Ok(())
+    }
+
+    /// Record a snapshot at the given migration version.
+    pub async fn record(&self, version: i64, description: &str) -> Result<()> {
+        sqlx::query("INSERT INTO schema_snapshots (version, description) VALUES ($1, $2)")
+            .bind(version)
+            .bind(description)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record schema snapshot")?;
+        Ok(())
+    }
+
+    /// Fetch the most recently recorded snapshot, if any.
+    pub async fn latest(&self) -> Result<Option<SchemaSnapshot>> {
+        let row = sqlx::query_as::<_, (i64, String, DateTime<Utc>)>(
+            "SELECT version, description, taken_at FROM schema_snapshots ORDER BY id DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch latest schema snapshot")?;
+
+        Ok(row.map(|(version, description, taken_at)| SchemaSnapshot {
+            version,
+            description,
+            taken_at,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_migrations() -> Vec<Migration> {
+        vec![
+            Migration::new(1, "one", "SELECT 1", "SELECT 1"),
+            Migration::new(2, "two", "SELECT 2", "SELECT 2"),
+        ]
+    }
+
+    #[test]
+    fn pending_excludes_applied_versions() {
+        let journal = JournalStorage::new(sample_migrations());
+        let applied = std::collections::HashSet::from([1]);
+        let pending = journal.pending(&applied);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].version, 2);
+    }
+
+    #[test]
+    fn checksum_is_stable_and_sql_specific() {
+        let migrations = sample_migrations();
+        assert_eq!(migrations[0].checksum(), migrations[0].checksum());
+        assert_ne!(migrations[0].checksum(), migrations[1].checksum());
+    }
+
+    #[test]
+    fn find_looks_up_by_version() {
+        let journal = JournalStorage::new(sample_migrations());
+        assert_eq!(journal.find(2).unwrap().name, "two");
+        assert!(journal.find(99).is_none());
+    }
+}
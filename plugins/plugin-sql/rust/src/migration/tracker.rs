@@ -0,0 +1,100 @@
+#![allow(missing_docs)]
+//! Tracks which migrations have already been applied to the database.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// A migration recorded as applied in the `schema_migrations` table.
+#[derive(Clone, Debug)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+/// Records and queries which migrations have been applied, via a `schema_migrations` tracking
+/// table created on first use.
+pub struct MigrationTracker {
+    pool: PgPool,
+}
+
+impl MigrationTracker {
+    /// Create a new tracker over the given connection pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create the `schema_migrations` tracking table if it doesn't already exist.
+    pub async fn ensure_table(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version BIGINT PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create schema_migrations table")?;
+        Ok(())
+    }
+
+    /// List every migration recorded as applied, ordered by version.
+    pub async fn applied(&self) -> Result<Vec<AppliedMigration>> {
+        let rows = sqlx::query_as::<_, (i64, String, String, DateTime<Utc>)>(
+            "SELECT version, name, checksum, applied_at FROM schema_migrations ORDER BY version",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list applied migrations")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(version, name, checksum, applied_at)| AppliedMigration {
+                version,
+                name,
+                checksum,
+                applied_at,
+            })
+            .collect())
+    }
+
+    /// Record a migration as applied. Takes the same transaction the migration's `up_sql` ran in,
+    /// so the bookkeeping row and the schema change commit or roll back together.
+    pub async fn record_applied(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        version: i64,
+        name: &str,
+        checksum: &str,
+    ) -> Result<()> {
+        sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES ($1, $2, $3)")
+            .bind(version)
+            .bind(name)
+            .bind(checksum)
+            .execute(&mut **tx)
+            .await
+            .context("Failed to record applied migration")?;
+        Ok(())
+    }
+
+    /// Remove a migration's applied record, as part of a rollback. Takes the same transaction the
+    /// migration's `down_sql` ran in.
+    pub async fn remove_applied(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        version: i64,
+    ) -> Result<()> {
+        sqlx::query("DELETE FROM schema_migrations WHERE version = $1")
+            .bind(version)
+            .execute(&mut **tx)
+            .await
+            .context("Failed to remove applied migration record")?;
+        Ok(())
+    }
+}
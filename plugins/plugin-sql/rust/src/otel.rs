@@ -0,0 +1,50 @@
+//! Opt-in OpenTelemetry export for `PostgresAdapter`'s tracing spans and metrics.
+//!
+//! `PostgresAdapter`'s instrumentation (see `postgres::telemetry`) is built on the `tracing` and
+//! `metrics` facades, which are no-ops until something is installed — so this module is the
+//! "something": it wires a real OTLP exporter on top. Disabled by default; enable the `otel`
+//! feature and call [`install`] once at startup to start shipping spans to a collector.
+//!
+//! The exporter is deliberately pluggable: swap [`install`]'s body for a different
+//! `opentelemetry_sdk::trace::SpanExporter` (e.g. stdout, Jaeger) without touching any
+//! instrumented adapter code, since callers only ever interact with `tracing`/`metrics`.
+
+#![cfg(feature = "otel")]
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing_subscriber::prelude::*;
+
+/// Install a process-wide OTLP exporter for this crate's tracing spans, shipping to the
+/// collector at `otlp_endpoint` (e.g. `http://localhost:4317`). Drop the returned guard (e.g. at
+/// the end of `main`) to flush any buffered spans before exiting.
+pub fn install(service_name: &str, otlp_endpoint: &str) -> anyhow::Result<impl Drop> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(otlp_endpoint)
+        .build()?;
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .build();
+
+    let tracer = provider.tracer(service_name.to_string());
+    let otel_layer = tracing_opentelemetry::layer().with_tracer(tracer);
+
+    tracing_subscriber::registry().with(otel_layer).init();
+
+    Ok(OtelGuard { provider })
+}
+
+/// Flushes the OTLP exporter's buffered spans when dropped.
+struct OtelGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(err) = self.provider.shutdown() {
+            tracing::warn!(?err, "Failed to shut down OTEL tracer provider cleanly");
+        }
+    }
+}
@@ -1,8 +1,13 @@
 #![allow(missing_docs)]
 //! PostgreSQL adapter implementation for elizaOS
 
-use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
+use cron::Schedule as CronSchedule;
+use sha2::{Digest, Sha256};
 use sqlx::Row;
 
 use crate::base::*;
@@ -12,13 +17,143 @@ use elizaos::{
     SearchMemoriesParams, Task, World, UUID,
 };
 
+use super::blob::BlobStore;
+use super::telemetry::{record_latency, record_outcome, record_rows, sql_error_category};
 use super::PostgresConnectionManager;
 
+/// Row count per `UNNEST`-based batch insert chunk, chosen to keep bind parameters for the widest
+/// of these batches (entities, at 4 columns) comfortably under Postgres's 65535-parameter limit.
+pub(crate) const ENTITY_BATCH_SIZE: usize = 1000;
+
+/// Row count per memory batch-insert chunk; see [`ENTITY_BATCH_SIZE`].
+const MEMORY_BATCH_SIZE: usize = 1000;
+
+/// Append a [`QueryFilters`]'s time window, inclusion/exclusion, ordering, and pagination clauses
+/// onto an in-progress `WHERE 1=1 ...` query, so [`PostgresAdapter::get_logs`] and
+/// [`PostgresAdapter::query_memories`] share one implementation of "any combination of filters
+/// composes" instead of each hand-rolling their own `QueryBuilder` plumbing.
+fn push_query_filters(
+    qb: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>,
+    filters: &QueryFilters,
+    created_at_col: &str,
+    room_id_col: &str,
+    entity_id_col: &str,
+    type_col: &str,
+) -> Result<()> {
+    if let Some(room_ids) = filters.room_ids.as_ref() {
+        let uuids = room_ids
+            .iter()
+            .map(|id| uuid::Uuid::parse_str(id.as_str()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        qb.push(format!(" AND {} = ANY(", room_id_col))
+            .push_bind(uuids)
+            .push(")");
+    }
+
+    if let Some(entity_ids) = filters.entity_ids.as_ref() {
+        let uuids = entity_ids
+            .iter()
+            .map(|id| uuid::Uuid::parse_str(id.as_str()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        qb.push(format!(" AND {} = ANY(", entity_id_col))
+            .push_bind(uuids)
+            .push(")");
+    }
+
+    if let Some(types) = filters.types.as_ref() {
+        qb.push(format!(" AND {} = ANY(", type_col))
+            .push_bind(types.clone())
+            .push(")");
+    }
+
+    if let Some(exclude_types) = filters.exclude_types.as_ref() {
+        qb.push(format!(" AND {} <> ALL(", type_col))
+            .push_bind(exclude_types.clone())
+            .push(")");
+    }
+
+    if let Some(after) = filters.after {
+        qb.push(format!(" AND {} >= to_timestamp(", created_at_col))
+            .push_bind(after as f64 / 1000.0)
+            .push(")");
+    }
+
+    if let Some(before) = filters.before {
+        qb.push(format!(" AND {} <= to_timestamp(", created_at_col))
+            .push_bind(before as f64 / 1000.0)
+            .push(")");
+    }
+
+    qb.push(format!(" ORDER BY {} ", created_at_col));
+    qb.push(if filters.reverse { "ASC" } else { "DESC" });
+
+    if let Some(limit) = filters.limit {
+        qb.push(" LIMIT ").push_bind(limit);
+    }
+
+    if let Some(offset) = filters.offset {
+        qb.push(" OFFSET ").push_bind(offset);
+    }
+
+    Ok(())
+}
+
+/// Append the `entity_id`/`room_id`/`world_id` equality filters shared by both legs of
+/// [`PostgresAdapter::hybrid_search_memories`] onto an in-progress query, keyed off `table_alias`
+/// so the same helper works whether the query aliases `memories` as `m` or something else.
+fn push_hybrid_filters(
+    qb: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>,
+    params: &HybridSearchMemoriesParams,
+    table_alias: &str,
+) -> Result<()> {
+    if let Some(entity_id) = params.entity_id.as_ref() {
+        let entity_uuid = uuid::Uuid::parse_str(entity_id.as_str())?;
+        qb.push(format!(" AND {}.entity_id = ", table_alias))
+            .push_bind(entity_uuid);
+    }
+
+    if let Some(room_id) = params.room_id.as_ref() {
+        let room_uuid = uuid::Uuid::parse_str(room_id.as_str())?;
+        qb.push(format!(" AND {}.room_id = ", table_alias))
+            .push_bind(room_uuid);
+    }
+
+    if let Some(world_id) = params.world_id.as_ref() {
+        let world_uuid = uuid::Uuid::parse_str(world_id.as_str())?;
+        qb.push(format!(" AND {}.world_id = ", table_alias))
+            .push_bind(world_uuid);
+    }
+
+    Ok(())
+}
+
+/// SHA-256 hash over a canonical `(name, metadata, room_id, entity_id)` serialization, used to
+/// dedup tasks created via [`PostgresAdapter::create_task_idempotent`].
+fn task_uniq_hash(task: &Task) -> Result<String> {
+    let canonical = serde_json::json!({
+        "name": task.name,
+        "metadata": task.metadata,
+        "roomId": task.room_id.as_ref().map(|u| u.as_str()),
+        "entityId": task.entity_id.as_ref().map(|u| u.as_str()),
+    });
+    let bytes = serde_json::to_vec(&canonical)?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Component `data` payloads at or above this size are transparently offloaded to the
+/// configured [`BlobStore`] instead of being inlined into the `components` row.
+const INLINE_BLOB_THRESHOLD_BYTES: usize = 32 * 1024;
+
+/// Base delay (seconds) for [`PostgresAdapter::fail_task`]'s exponential backoff: the Nth retry
+/// is rescheduled `TASK_RETRY_BASE_SECONDS * 2^N` seconds out.
+const TASK_RETRY_BASE_SECONDS: f64 = 30.0;
+
 /// PostgreSQL database adapter
 pub struct PostgresAdapter {
-    manager: PostgresConnectionManager,
-    agent_id: uuid::Uuid,
+    pub(crate) manager: PostgresConnectionManager,
+    pub(crate) agent_id: uuid::Uuid,
     embedding_dimension: i32,
+    blob_store: Option<BlobStore>,
 }
 
 impl PostgresAdapter {
@@ -31,26 +166,201 @@ impl PostgresAdapter {
             manager,
             agent_id: agent_uuid,
             embedding_dimension: embedding::DEFAULT_DIMENSION,
+            blob_store: None,
         })
     }
 
+    /// Configure the object-storage backend `put_blob`/`get_blob`/`delete_blob` (and transparent
+    /// large-`Component::data` offload) upload to. Without this, those calls fail with an error
+    /// rather than silently falling back to inlining, so callers notice a missing configuration
+    /// immediately instead of via unexpectedly large rows.
+    pub fn with_blob_store(mut self, blob_store: BlobStore) -> Self {
+        self.blob_store = Some(blob_store);
+        self
+    }
+
     /// Get the connection manager
     pub fn manager(&self) -> &PostgresConnectionManager {
         &self.manager
     }
+
+    /// Record a just-finished operation's latency, row count, and outcome against the shared
+    /// metrics, and (on failure) the SQL error category on the current span, so instrumented
+    /// trait methods don't each have to repeat this bookkeeping by hand.
+    fn finish<T>(
+        operation: &'static str,
+        direction: &'static str,
+        rows: u64,
+        started: std::time::Instant,
+        result: &Result<T>,
+    ) {
+        record_latency(operation, started.elapsed());
+        match result {
+            Ok(_) => {
+                record_rows(operation, direction, rows);
+                record_outcome(operation, true);
+            }
+            Err(err) => {
+                tracing::Span::current().record("db.error.category", sql_error_category(err));
+                record_outcome(operation, false);
+            }
+        }
+    }
+
+    /// Build a [`crate::migration::MigrationService`] over this adapter's connection pool and the
+    /// core schema's migrations. `init` drives this itself; this accessor exists so the same
+    /// `migrate`/`rollback`/`status` surface can also be driven from a CLI subcommand.
+    pub fn migrations(&self) -> crate::migration::MigrationService {
+        crate::migration::MigrationService::new(
+            self.manager.get_pool().clone(),
+            crate::migration::JournalStorage::new(crate::migration::core_migrations()),
+        )
+    }
+
+    /// If `data` serializes to at least [`INLINE_BLOB_THRESHOLD_BYTES`], upload it to the blob
+    /// store and return a small marker object in its place; otherwise return `data` unchanged.
+    /// Used to keep large `Component::data` payloads out of Postgres rows and the WAL.
+    async fn maybe_offload_component_data(
+        &self,
+        data: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let bytes = serde_json::to_vec(&data)?;
+        if bytes.len() < INLINE_BLOB_THRESHOLD_BYTES || self.blob_store.is_none() {
+            return Ok(data);
+        }
+
+        let media_id = self.put_blob(bytes, "application/json").await?;
+        Ok(serde_json::json!({
+            "__offloaded_media_id": media_id.as_str(),
+        }))
+    }
+
+    /// Inverse of [`Self::maybe_offload_component_data`]: if `data` is an offload marker,
+    /// download and parse the original JSON back out of the blob store; otherwise return `data`
+    /// unchanged.
+    async fn maybe_rehydrate_component_data(
+        &self,
+        data: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let Some(media_id) = data
+            .as_object()
+            .and_then(|obj| obj.get("__offloaded_media_id"))
+            .and_then(|v| v.as_str())
+        else {
+            return Ok(data);
+        };
+
+        let media_id = UUID::new(media_id).context("Invalid media ID in offloaded component")?;
+        let bytes = self
+            .get_blob(&media_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Offloaded component data missing from blob store"))?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Fetch up to `count` memories matching `params`' room/entity/world/table filters with
+    /// `created_at` bounded by `lower`/`upper` (each an inclusive-or-exclusive ms-since-epoch
+    /// bound), ordered as requested. Always returns the batch sorted oldest-to-newest, regardless
+    /// of which direction it was queried in, so callers ([`Self::get_memories_windowed`]) can
+    /// merge and present windows consistently.
+    async fn fetch_memory_window(
+        &self,
+        params: &MemoryWindowParams,
+        lower: Option<(i64, bool)>,
+        upper: Option<(i64, bool)>,
+        count: i64,
+        query_ascending: bool,
+    ) -> Result<Vec<Memory>> {
+        let mut qb = sqlx::QueryBuilder::new(
+            r#"
+            SELECT id, type, created_at, content, entity_id, agent_id,
+                   room_id, world_id, "unique", metadata
+            FROM memories WHERE type = "#,
+        );
+        qb.push_bind(&params.table_name);
+
+        if let Some(room_id) = params.room_id.as_ref() {
+            let room_uuid = uuid::Uuid::parse_str(room_id.as_str())?;
+            qb.push(" AND room_id = ").push_bind(room_uuid);
+        }
+
+        if let Some(entity_id) = params.entity_id.as_ref() {
+            let entity_uuid = uuid::Uuid::parse_str(entity_id.as_str())?;
+            qb.push(" AND entity_id = ").push_bind(entity_uuid);
+        }
+
+        if let Some(world_id) = params.world_id.as_ref() {
+            let world_uuid = uuid::Uuid::parse_str(world_id.as_str())?;
+            qb.push(" AND world_id = ").push_bind(world_uuid);
+        }
+
+        if let Some((timestamp, inclusive)) = lower {
+            qb.push(if inclusive {
+                " AND created_at >= to_timestamp("
+            } else {
+                " AND created_at > to_timestamp("
+            });
+            qb.push_bind(timestamp as f64 / 1000.0).push(")");
+        }
+
+        if let Some((timestamp, inclusive)) = upper {
+            qb.push(if inclusive {
+                " AND created_at <= to_timestamp("
+            } else {
+                " AND created_at < to_timestamp("
+            });
+            qb.push_bind(timestamp as f64 / 1000.0).push(")");
+        }
+
+        qb.push(" ORDER BY created_at ");
+        qb.push(if query_ascending { "ASC" } else { "DESC" });
+        qb.push(" LIMIT ").push_bind(count);
+
+        let rows = qb.build().fetch_all(self.manager.get_pool()).await?;
+
+        let mut memories: Vec<Memory> = rows
+            .into_iter()
+            .map(|r| {
+                MemoryRecord {
+                    id: r.get("id"),
+                    memory_type: r.get("type"),
+                    created_at: r.get("created_at"),
+                    content: r.get("content"),
+                    entity_id: r.get("entity_id"),
+                    agent_id: r.get("agent_id"),
+                    room_id: r.get("room_id"),
+                    world_id: r.get("world_id"),
+                    unique: r.get("unique"),
+                    metadata: r.get("metadata"),
+                }
+                .to_memory()
+            })
+            .collect();
+
+        if !query_ascending {
+            memories.reverse();
+        }
+
+        Ok(memories)
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl DatabaseAdapter for PostgresAdapter {
+    #[tracing::instrument(skip(self), fields(otel.kind = "client", db.system = "postgresql", db.operation = "init"), err)]
     async fn init(&self) -> Result<()> {
-        self.manager.run_migrations().await
+        self.migrations().migrate().await?;
+        Ok(())
     }
 
+    #[tracing::instrument(skip(self), fields(otel.kind = "client", db.system = "postgresql", db.operation = "is_ready"), err)]
     async fn is_ready(&self) -> Result<bool> {
         self.manager.test_connection().await
     }
 
+    #[tracing::instrument(skip(self), fields(otel.kind = "client", db.system = "postgresql", db.operation = "close"), err)]
     async fn close(&self) -> Result<()> {
         self.manager.close().await;
         Ok(())
@@ -299,36 +609,86 @@ impl DatabaseAdapter for PostgresAdapter {
             .collect())
     }
 
+    #[tracing::instrument(
+        skip(self, entities),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "create_entities",
+            db.rows_count = entities.len(),
+            db.error.category = tracing::field::Empty,
+        )
+    )]
     async fn create_entities(&self, entities: &[Entity]) -> Result<bool> {
-        for entity in entities {
-            let id = entity
-                .id
-                .as_ref()
-                .map(|u| uuid::Uuid::parse_str(u.as_str()).unwrap())
-                .unwrap_or_else(uuid::Uuid::new_v4);
-            let agent_id = entity
-                .agent_id
-                .as_ref()
-                .ok_or_else(|| anyhow::anyhow!("Entity agent_id is required"))?;
-            let agent_id = uuid::Uuid::parse_str(agent_id.as_str())?;
-            let names = serde_json::to_value(&entity.names)?;
-            let metadata = serde_json::to_value(&entity.metadata)?;
+        let started = std::time::Instant::now();
+        let result: Result<bool> = async {
+        if entities.is_empty() {
+            return Ok(true);
+        }
+
+        let mut tx = self
+            .manager
+            .get_pool()
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+
+        for chunk in entities.chunks(ENTITY_BATCH_SIZE) {
+            let mut ids = Vec::with_capacity(chunk.len());
+            let mut agent_ids = Vec::with_capacity(chunk.len());
+            let mut names = Vec::with_capacity(chunk.len());
+            let mut metadatas = Vec::with_capacity(chunk.len());
+
+            for entity in chunk {
+                let id = entity
+                    .id
+                    .as_ref()
+                    .map(|u| uuid::Uuid::parse_str(u.as_str()).unwrap())
+                    .unwrap_or_else(uuid::Uuid::new_v4);
+                let agent_id = entity
+                    .agent_id
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Entity agent_id is required"))?;
+                let agent_id = uuid::Uuid::parse_str(agent_id.as_str())?;
+
+                ids.push(id);
+                agent_ids.push(agent_id);
+                names.push(serde_json::to_value(&entity.names)?);
+                metadatas.push(serde_json::to_value(&entity.metadata)?);
+            }
 
+            // UNNEST turns the whole chunk into one multi-row INSERT instead of one round trip
+            // per entity, while keeping the existing ON CONFLICT semantics.
             sqlx::query(
                 r#"
                 INSERT INTO entities (id, agent_id, names, metadata)
-                VALUES ($1, $2, $3, $4)
+                SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::jsonb[], $4::jsonb[])
                 ON CONFLICT (id) DO NOTHING
                 "#,
             )
-            .bind(id)
-            .bind(agent_id)
+            .bind(&ids)
+            .bind(&agent_ids)
             .bind(&names)
-            .bind(&metadata)
-            .execute(self.manager.get_pool())
+            .bind(&metadatas)
+            .execute(&mut *tx)
             .await?;
         }
+
+        tx.commit()
+            .await
+            .context("Failed to commit entities batch")?;
         Ok(true)
+        }
+        .await;
+
+        Self::finish(
+            "create_entities",
+            "write",
+            entities.len() as u64,
+            started,
+            &result,
+        );
+        result
     }
 
     async fn update_entity(&self, entity: &Entity) -> Result<()> {
@@ -403,20 +763,28 @@ impl DatabaseAdapter for PostgresAdapter {
             .fetch_optional(self.manager.get_pool())
             .await?;
 
-        Ok(row.map(|r| {
-            ComponentRecord {
-                id: r.get("id"),
-                entity_id: r.get("entity_id"),
-                agent_id: r.get("agent_id"),
-                room_id: r.get("room_id"),
-                world_id: r.get("world_id"),
-                source_entity_id: r.get("source_entity_id"),
-                component_type: r.get("type"),
-                data: r.get("data"),
-                created_at: r.get("created_at"),
-            }
-            .to_component()
-        }))
+        let Some(r) = row else {
+            return Ok(None);
+        };
+
+        let mut component = ComponentRecord {
+            id: r.get("id"),
+            entity_id: r.get("entity_id"),
+            agent_id: r.get("agent_id"),
+            room_id: r.get("room_id"),
+            world_id: r.get("world_id"),
+            source_entity_id: r.get("source_entity_id"),
+            component_type: r.get("type"),
+            data: r.get("data"),
+            created_at: r.get("created_at"),
+        }
+        .to_component();
+
+        if let Some(data) = component.data.take() {
+            component.data = Some(self.maybe_rehydrate_component_data(data).await?);
+        }
+
+        Ok(Some(component))
     }
 
     async fn get_components(
@@ -451,26 +819,43 @@ impl DatabaseAdapter for PostgresAdapter {
             .fetch_all(self.manager.get_pool())
             .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| {
-                ComponentRecord {
-                    id: r.get("id"),
-                    entity_id: r.get("entity_id"),
-                    agent_id: r.get("agent_id"),
-                    room_id: r.get("room_id"),
-                    world_id: r.get("world_id"),
-                    source_entity_id: r.get("source_entity_id"),
-                    component_type: r.get("type"),
-                    data: r.get("data"),
-                    created_at: r.get("created_at"),
-                }
-                .to_component()
-            })
-            .collect())
+        let mut components = Vec::with_capacity(rows.len());
+        for r in rows {
+            let mut component = ComponentRecord {
+                id: r.get("id"),
+                entity_id: r.get("entity_id"),
+                agent_id: r.get("agent_id"),
+                room_id: r.get("room_id"),
+                world_id: r.get("world_id"),
+                source_entity_id: r.get("source_entity_id"),
+                component_type: r.get("type"),
+                data: r.get("data"),
+                created_at: r.get("created_at"),
+            }
+            .to_component();
+
+            if let Some(data) = component.data.take() {
+                component.data = Some(self.maybe_rehydrate_component_data(data).await?);
+            }
+
+            components.push(component);
+        }
+
+        Ok(components)
     }
 
+    #[tracing::instrument(
+        skip(self, component),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "create_component",
+            db.error.category = tracing::field::Empty,
+        )
+    )]
     async fn create_component(&self, component: &Component) -> Result<bool> {
+        let started = std::time::Instant::now();
+        let result: Result<bool> = async {
         let id =
             uuid::Uuid::parse_str(component.id.as_str()).unwrap_or_else(|_| uuid::Uuid::new_v4());
         let entity_id = uuid::Uuid::parse_str(component.entity_id.as_str())?;
@@ -487,6 +872,7 @@ impl DatabaseAdapter for PostgresAdapter {
             .ok_or_else(|| anyhow::anyhow!("Component source_entity_id is required"))?;
         let source_entity_id = uuid::Uuid::parse_str(source_entity_id.as_str())?;
         let data = serde_json::to_value(&component.data)?;
+        let data = self.maybe_offload_component_data(data).await?;
 
         sqlx::query(
             r#"
@@ -507,6 +893,11 @@ impl DatabaseAdapter for PostgresAdapter {
         .await?;
 
         Ok(true)
+        }
+        .await;
+
+        Self::finish("create_component", "write", 1, started, &result);
+        result
     }
 
     async fn update_component(&self, component: &Component) -> Result<()> {
@@ -541,7 +932,20 @@ impl DatabaseAdapter for PostgresAdapter {
     // Memory Methods
     // =========================================================================
 
+    #[tracing::instrument(
+        skip(self, params),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "get_memories",
+            db.memory.table = %params.table_name,
+            db.rows_count = tracing::field::Empty,
+            db.error.category = tracing::field::Empty,
+        )
+    )]
     async fn get_memories(&self, params: GetMemoriesParams) -> Result<Vec<Memory>> {
+        let started = std::time::Instant::now();
+        let result: Result<Vec<Memory>> = async {
         let mut qb = sqlx::QueryBuilder::new(
             r#"
             SELECT m.id, m.type, m.created_at, m.content, m.entity_id, m.agent_id,
@@ -620,6 +1024,20 @@ impl DatabaseAdapter for PostgresAdapter {
                 .to_memory()
             })
             .collect())
+        }
+        .await;
+
+        Self::finish(
+            "get_memories",
+            "read",
+            result.as_ref().map(|rows| rows.len() as u64).unwrap_or(0),
+            started,
+            &result,
+        );
+        if let Ok(rows) = &result {
+            tracing::Span::current().record("db.rows_count", rows.len());
+        }
+        result
     }
 
     async fn get_memory_by_id(&self, id: &UUID) -> Result<Option<Memory>> {
@@ -750,9 +1168,9 @@ impl DatabaseAdapter for PostgresAdapter {
     ) -> Result<Vec<EmbeddingSearchResult>> {
         let rows = sqlx::query(
             r#"
-            SELECT e.id, e.embedding, m.content
+            SELECT e.memory_id AS id, e.embedding, m.content
             FROM embeddings e
-            JOIN memories m ON e.id = m.id
+            JOIN memories m ON e.memory_id = m.id
             WHERE m.type = $1
             "#,
         )
@@ -773,17 +1191,72 @@ impl DatabaseAdapter for PostgresAdapter {
             .collect())
     }
 
+    #[tracing::instrument(
+        skip(self, params),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "search_memories",
+            db.memory.table = %params.table_name,
+            db.rows_count = tracing::field::Empty,
+            db.error.category = tracing::field::Empty,
+        )
+    )]
     async fn search_memories(&self, params: SearchMemoriesParams) -> Result<Vec<Memory>> {
+        let started = std::time::Instant::now();
+        let result: Result<Vec<Memory>> = async {
+        if params.embedding.len() as i32 != self.embedding_dimension {
+            bail!(
+                "Query embedding has dimension {}, but this adapter is configured for dimension {}",
+                params.embedding.len(),
+                self.embedding_dimension
+            );
+        }
+
         let threshold = params.match_threshold.unwrap_or(0.7);
         let count = params.count.unwrap_or(10);
 
-        let query = embedding::search_embeddings_sql(self.embedding_dimension, count);
+        // INNER JOIN excludes memories with no embedding row (and `e.embedding IS NOT NULL`
+        // guards against a row whose embedding was never populated), so a memory without a
+        // usable embedding is simply absent from vector results rather than causing an error.
+        let mut qb = sqlx::QueryBuilder::new(
+            r#"
+            SELECT m.id, m.type, m.created_at, m.content, m.entity_id, m.agent_id,
+                   m.room_id, m.world_id, m."unique", m.metadata,
+                   1 - (e.embedding <=> "#,
+        );
+        qb.push_bind(&params.embedding);
+        qb.push(") AS similarity FROM memories m INNER JOIN embeddings e ON e.memory_id = m.id WHERE e.embedding IS NOT NULL AND m.type = ");
+        qb.push_bind(&params.table_name);
+
+        if let Some(entity_id) = params.entity_id.as_ref() {
+            let entity_uuid = uuid::Uuid::parse_str(entity_id.as_str())?;
+            qb.push(" AND m.entity_id = ").push_bind(entity_uuid);
+        }
 
-        let rows = sqlx::query(&query)
-            .bind(&params.embedding)
-            .bind(threshold)
-            .fetch_all(self.manager.get_pool())
-            .await?;
+        if let Some(room_id) = params.room_id.as_ref() {
+            let room_uuid = uuid::Uuid::parse_str(room_id.as_str())?;
+            qb.push(" AND m.room_id = ").push_bind(room_uuid);
+        }
+
+        if let Some(world_id) = params.world_id.as_ref() {
+            let world_uuid = uuid::Uuid::parse_str(world_id.as_str())?;
+            qb.push(" AND m.world_id = ").push_bind(world_uuid);
+        }
+
+        if params.unique.unwrap_or(false) {
+            qb.push(r#" AND m."unique" = true"#);
+        }
+
+        qb.push(" AND 1 - (e.embedding <=> ");
+        qb.push_bind(&params.embedding);
+        qb.push(") >= ").push_bind(threshold);
+
+        qb.push(" ORDER BY e.embedding <=> ");
+        qb.push_bind(&params.embedding);
+        qb.push(" LIMIT ").push_bind(count);
+
+        let rows = qb.build().fetch_all(self.manager.get_pool()).await?;
 
         Ok(rows
             .into_iter()
@@ -806,29 +1279,339 @@ impl DatabaseAdapter for PostgresAdapter {
                 memory
             })
             .collect())
-    }
-
-    async fn create_memory(
+        }
+        .await;
+
+        Self::finish(
+            "search_memories",
+            "read",
+            result.as_ref().map(|rows| rows.len() as u64).unwrap_or(0),
+            started,
+            &result,
+        );
+        if let Ok(rows) = &result {
+            tracing::Span::current().record("db.rows_count", rows.len());
+        }
+        result
+    }
+
+    #[tracing::instrument(
+        skip(self, params),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "hybrid_search_memories",
+            db.memory.table = %params.table_name,
+            db.rows_count = tracing::field::Empty,
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn hybrid_search_memories(
         &self,
-        memory: &Memory,
-        table_name: &str,
-        _unique: bool,
-    ) -> Result<UUID> {
-        let record = MemoryRecord::from_memory(memory, table_name);
+        params: HybridSearchMemoriesParams,
+    ) -> Result<Vec<Memory>> {
+        let started = std::time::Instant::now();
+        let result: Result<Vec<Memory>> = async {
+        if params.embedding.len() as i32 != self.embedding_dimension {
+            bail!(
+                "Query embedding has dimension {}, but this adapter is configured for dimension {}",
+                params.embedding.len(),
+                self.embedding_dimension
+            );
+        }
 
-        sqlx::query(
+        let count = params.count.unwrap_or(10);
+        let candidate_count = params.candidate_count.unwrap_or(count.max(10) * 4);
+        let k = params.rrf_k.unwrap_or(60.0);
+        let vector_weight = params.vector_weight.unwrap_or(1.0);
+        let text_weight = params.text_weight.unwrap_or(1.0);
+
+        // Vector-similarity leg: same shape as `search_memories`, but we only need the ranking
+        // (id, ordered by similarity) to feed into fusion below.
+        let mut vector_qb = sqlx::QueryBuilder::new(
+            "SELECT m.id, 1 - (e.embedding <=> ",
+        );
+        vector_qb.push_bind(&params.embedding);
+        vector_qb.push(") AS similarity FROM memories m INNER JOIN embeddings e ON e.memory_id = m.id WHERE e.embedding IS NOT NULL AND m.type = ");
+        vector_qb.push_bind(&params.table_name);
+        push_hybrid_filters(&mut vector_qb, &params, "m")?;
+        vector_qb.push(" ORDER BY e.embedding <=> ");
+        vector_qb.push_bind(&params.embedding);
+        vector_qb.push(" LIMIT ").push_bind(candidate_count);
+
+        let vector_rows = vector_qb.build().fetch_all(self.manager.get_pool()).await?;
+        let vector_ids: Vec<uuid::Uuid> = vector_rows.iter().map(|r| r.get("id")).collect();
+
+        // Full-text leg: ranks memories whose `content->>'text'` matches the query, via
+        // Postgres's built-in text search (`to_tsvector`/`plainto_tsquery`/`ts_rank`).
+        let mut text_qb = sqlx::QueryBuilder::new(
+            r#"SELECT m.id, ts_rank(to_tsvector('english', m.content->>'text'), plainto_tsquery('english', "#,
+        );
+        text_qb.push_bind(&params.query);
+        text_qb.push(r#")) AS rank FROM memories m WHERE m.type = "#);
+        text_qb.push_bind(&params.table_name);
+        text_qb.push(r#" AND to_tsvector('english', m.content->>'text') @@ plainto_tsquery('english', "#);
+        text_qb.push_bind(&params.query);
+        text_qb.push(")");
+        push_hybrid_filters(&mut text_qb, &params, "m")?;
+        text_qb.push(" ORDER BY rank DESC LIMIT ").push_bind(candidate_count);
+
+        let text_rows = text_qb.build().fetch_all(self.manager.get_pool()).await?;
+        let text_ids: Vec<uuid::Uuid> = text_rows.iter().map(|r| r.get("id")).collect();
+
+        // Reciprocal rank fusion: each list contributes `weight / (k + r)` per id at its
+        // (0-indexed) rank `r`; ids present in only one list still get that list's contribution.
+        let mut scores: HashMap<uuid::Uuid, f64> = HashMap::new();
+        for (r, id) in vector_ids.iter().enumerate() {
+            *scores.entry(*id).or_insert(0.0) += vector_weight / (k + r as f64);
+        }
+        for (r, id) in text_ids.iter().enumerate() {
+            *scores.entry(*id).or_insert(0.0) += text_weight / (k + r as f64);
+        }
+
+        let mut ranked: Vec<(uuid::Uuid, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(count.max(0) as usize);
+
+        if ranked.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let fused_ids: Vec<uuid::Uuid> = ranked.iter().map(|(id, _)| *id).collect();
+        let rows = sqlx::query(
             r#"
-            INSERT INTO memories (id, type, content, entity_id, agent_id, room_id, world_id, "unique", metadata)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            "#
+            SELECT id, type, created_at, content, entity_id, agent_id, room_id, world_id, "unique", metadata
+            FROM memories WHERE id = ANY($1)
+            "#,
         )
-        .bind(record.id)
-        .bind(&record.memory_type)
-        .bind(&record.content)
-        .bind(record.entity_id)
-        .bind(record.agent_id)
-        .bind(record.room_id)
-        .bind(record.world_id)
+        .bind(&fused_ids)
+        .fetch_all(self.manager.get_pool())
+        .await?;
+
+        let mut memories_by_id: HashMap<uuid::Uuid, Memory> = rows
+            .into_iter()
+            .map(|r| {
+                let id: uuid::Uuid = r.get("id");
+                let memory = MemoryRecord {
+                    id,
+                    memory_type: r.get("type"),
+                    created_at: r.get("created_at"),
+                    content: r.get("content"),
+                    entity_id: r.get("entity_id"),
+                    agent_id: r.get("agent_id"),
+                    room_id: r.get("room_id"),
+                    world_id: r.get("world_id"),
+                    unique: r.get("unique"),
+                    metadata: r.get("metadata"),
+                }
+                .to_memory();
+                (id, memory)
+            })
+            .collect();
+
+        Ok(ranked
+            .into_iter()
+            .filter_map(|(id, score)| {
+                let mut memory = memories_by_id.remove(&id)?;
+                memory.similarity = Some(score);
+                Some(memory)
+            })
+            .collect())
+        }
+        .await;
+
+        Self::finish(
+            "hybrid_search_memories",
+            "read",
+            result.as_ref().map(|rows| rows.len() as u64).unwrap_or(0),
+            started,
+            &result,
+        );
+        if let Ok(rows) = &result {
+            tracing::Span::current().record("db.rows_count", rows.len());
+        }
+        result
+    }
+
+    #[tracing::instrument(
+        skip(self, params),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "get_memories_windowed",
+            db.rows_count = tracing::field::Empty,
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn get_memories_windowed(&self, params: MemoryWindowParams) -> Result<MemoryWindowResult> {
+        let started = std::time::Instant::now();
+        let result: Result<MemoryWindowResult> = async {
+            let memories = match params.selector {
+                MemoryWindowSelector::Latest { count } => {
+                    self.fetch_memory_window(&params, None, None, count, false)
+                        .await?
+                }
+                MemoryWindowSelector::Before { timestamp, count } => {
+                    self.fetch_memory_window(&params, None, Some((timestamp, false)), count, false)
+                        .await?
+                }
+                MemoryWindowSelector::After { timestamp, count } => {
+                    self.fetch_memory_window(&params, Some((timestamp, false)), None, count, true)
+                        .await?
+                }
+                MemoryWindowSelector::Between { start, end, count } => {
+                    self.fetch_memory_window(
+                        &params,
+                        Some((start, true)),
+                        Some((end, true)),
+                        count,
+                        true,
+                    )
+                    .await?
+                }
+                MemoryWindowSelector::Around { timestamp, count } => {
+                    let before_count = (count / 2).max(1);
+                    let before = self
+                        .fetch_memory_window(
+                            &params,
+                            None,
+                            Some((timestamp, false)),
+                            before_count,
+                            false,
+                        )
+                        .await?;
+                    let after_count = (count - before.len() as i64).max(0);
+                    let after = self
+                        .fetch_memory_window(
+                            &params,
+                            Some((timestamp, false)),
+                            None,
+                            after_count,
+                            true,
+                        )
+                        .await?;
+                    let mut merged = before;
+                    merged.extend(after);
+                    merged
+                }
+            };
+
+            let oldest = memories.first().and_then(|m| m.created_at);
+            let newest = memories.last().and_then(|m| m.created_at);
+
+            Ok(MemoryWindowResult {
+                memories,
+                oldest,
+                newest,
+            })
+        }
+        .await;
+
+        Self::finish(
+            "get_memories_windowed",
+            "read",
+            result.as_ref().map(|r| r.memories.len() as u64).unwrap_or(0),
+            started,
+            &result,
+        );
+        if let Ok(r) = &result {
+            tracing::Span::current().record("db.rows_count", r.memories.len());
+        }
+        result
+    }
+
+    #[tracing::instrument(
+        skip(self, filters),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "query_memories",
+            db.rows_count = tracing::field::Empty,
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn query_memories(&self, filters: QueryFilters) -> Result<Vec<Memory>> {
+        let started = std::time::Instant::now();
+        let result: Result<Vec<Memory>> = async {
+            let mut qb = sqlx::QueryBuilder::new(
+                r#"
+                SELECT id, type, created_at, content, entity_id, agent_id,
+                       room_id, world_id, "unique", metadata
+                FROM memories WHERE 1=1
+                "#,
+            );
+
+            push_query_filters(&mut qb, &filters, "created_at", "room_id", "entity_id", "type")?;
+
+            let rows = qb.build().fetch_all(self.manager.get_pool()).await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|r| {
+                    MemoryRecord {
+                        id: r.get("id"),
+                        memory_type: r.get("type"),
+                        created_at: r.get("created_at"),
+                        content: r.get("content"),
+                        entity_id: r.get("entity_id"),
+                        agent_id: r.get("agent_id"),
+                        room_id: r.get("room_id"),
+                        world_id: r.get("world_id"),
+                        unique: r.get("unique"),
+                        metadata: r.get("metadata"),
+                    }
+                    .to_memory()
+                })
+                .collect())
+        }
+        .await;
+
+        Self::finish(
+            "query_memories",
+            "read",
+            result.as_ref().map(|rows| rows.len() as u64).unwrap_or(0),
+            started,
+            &result,
+        );
+        if let Ok(rows) = &result {
+            tracing::Span::current().record("db.rows_count", rows.len());
+        }
+        result
+    }
+
+    #[tracing::instrument(
+        skip(self, memory),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "create_memory",
+            db.memory.table = %table_name,
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn create_memory(
+        &self,
+        memory: &Memory,
+        table_name: &str,
+        _unique: bool,
+    ) -> Result<UUID> {
+        let started = std::time::Instant::now();
+        let result: Result<UUID> = async {
+        let record = MemoryRecord::from_memory(memory, table_name);
+
+        sqlx::query(
+            r#"
+            INSERT INTO memories (id, type, content, entity_id, agent_id, room_id, world_id, "unique", metadata)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#
+        )
+        .bind(record.id)
+        .bind(&record.memory_type)
+        .bind(&record.content)
+        .bind(record.entity_id)
+        .bind(record.agent_id)
+        .bind(record.room_id)
+        .bind(record.world_id)
         .bind(record.unique)
         .bind(&record.metadata)
         .execute(self.manager.get_pool())
@@ -838,7 +1621,7 @@ impl DatabaseAdapter for PostgresAdapter {
         if let Some(embedding) = &memory.embedding {
             sqlx::query(
                 r#"
-                INSERT INTO embeddings (id, embedding)
+                INSERT INTO embeddings (memory_id, embedding)
                 VALUES ($1, $2::vector)
                 "#,
             )
@@ -849,9 +1632,128 @@ impl DatabaseAdapter for PostgresAdapter {
         }
 
         Ok(UUID::new(&record.id.to_string()).unwrap())
+        }
+        .await;
+
+        Self::finish("create_memory", "write", 1, started, &result);
+        result
     }
 
+    #[tracing::instrument(
+        skip(self, memories),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "create_memories",
+            db.memory.table = %table_name,
+            db.rows_count = memories.len(),
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn create_memories(&self, memories: &[Memory], table_name: &str) -> Result<Vec<UUID>> {
+        let started = std::time::Instant::now();
+        let result: Result<Vec<UUID>> = async {
+        if memories.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows: Vec<_> = memories
+            .iter()
+            .map(|memory| (memory, MemoryRecord::from_memory(memory, table_name)))
+            .collect();
+
+        let mut tx = self
+            .manager
+            .get_pool()
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+
+        for chunk in rows.chunks(MEMORY_BATCH_SIZE) {
+            let ids: Vec<_> = chunk.iter().map(|(_, r)| r.id).collect();
+            let memory_types: Vec<_> = chunk.iter().map(|(_, r)| r.memory_type.clone()).collect();
+            let contents: Vec<_> = chunk.iter().map(|(_, r)| r.content.clone()).collect();
+            let entity_ids: Vec<_> = chunk.iter().map(|(_, r)| r.entity_id).collect();
+            let agent_ids: Vec<_> = chunk.iter().map(|(_, r)| r.agent_id).collect();
+            let room_ids: Vec<_> = chunk.iter().map(|(_, r)| r.room_id).collect();
+            let world_ids: Vec<_> = chunk.iter().map(|(_, r)| r.world_id).collect();
+            let uniques: Vec<_> = chunk.iter().map(|(_, r)| r.unique).collect();
+            let metadatas: Vec<_> = chunk.iter().map(|(_, r)| r.metadata.clone()).collect();
+
+            sqlx::query(
+                r#"
+                INSERT INTO memories (id, type, content, entity_id, agent_id, room_id, world_id, "unique", metadata)
+                SELECT * FROM UNNEST(
+                    $1::uuid[], $2::text[], $3::jsonb[], $4::uuid[], $5::uuid[],
+                    $6::uuid[], $7::uuid[], $8::bool[], $9::jsonb[]
+                )
+                "#,
+            )
+            .bind(&ids)
+            .bind(&memory_types)
+            .bind(&contents)
+            .bind(&entity_ids)
+            .bind(&agent_ids)
+            .bind(&room_ids)
+            .bind(&world_ids)
+            .bind(&uniques)
+            .bind(&metadatas)
+            .execute(&mut *tx)
+            .await?;
+
+            // pgvector has no `vector[]` array type to batch these through UNNEST with (and
+            // UNNEST over a second true array argument would flatten every element into one
+            // column rather than zipping one embedding per row anyway), so each embedding is
+            // inserted with its own round trip rather than batched with the rest of the chunk.
+            for (memory, record) in chunk {
+                let Some(embedding) = memory.embedding.as_ref() else {
+                    continue;
+                };
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO embeddings (memory_id, embedding)
+                    VALUES ($1, $2::vector)
+                    "#,
+                )
+                .bind(record.id)
+                .bind(embedding)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await.context("Failed to commit memories batch")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(_, r)| UUID::new(&r.id.to_string()).unwrap())
+            .collect())
+        }
+        .await;
+
+        Self::finish(
+            "create_memories",
+            "write",
+            result.as_ref().map(|ids| ids.len() as u64).unwrap_or(0),
+            started,
+            &result,
+        );
+        result
+    }
+
+    #[tracing::instrument(
+        skip(self, memory),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "update_memory",
+            db.error.category = tracing::field::Empty,
+        )
+    )]
     async fn update_memory(&self, memory: &Memory) -> Result<bool> {
+        let started = std::time::Instant::now();
+        let result: Result<bool> = async {
         let id = memory
             .id
             .as_ref()
@@ -875,31 +1777,122 @@ impl DatabaseAdapter for PostgresAdapter {
         .await?;
 
         Ok(true)
+        }
+        .await;
+
+        Self::finish("update_memory", "write", 1, started, &result);
+        result
     }
 
-    async fn delete_memory(&self, memory_id: &UUID) -> Result<()> {
+    #[tracing::instrument(
+        skip(self),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "delete_memory",
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn delete_memory(&self, memory_id: &UUID) -> Result<Option<Memory>> {
+        let started = std::time::Instant::now();
+        let result: Result<Option<Memory>> = async {
         let uuid = uuid::Uuid::parse_str(memory_id.as_str())?;
 
-        sqlx::query("DELETE FROM memories WHERE id = $1")
-            .bind(uuid)
-            .execute(self.manager.get_pool())
-            .await?;
+        let row = sqlx::query(
+            r#"
+            DELETE FROM memories WHERE id = $1
+            RETURNING id, type, created_at, content, entity_id, agent_id, room_id, world_id, "unique", metadata
+            "#,
+        )
+        .bind(uuid)
+        .fetch_optional(self.manager.get_pool())
+        .await?;
 
-        Ok(())
+        Ok(row.map(|r| {
+            MemoryRecord {
+                id: r.get("id"),
+                memory_type: r.get("type"),
+                created_at: r.get("created_at"),
+                content: r.get("content"),
+                entity_id: r.get("entity_id"),
+                agent_id: r.get("agent_id"),
+                room_id: r.get("room_id"),
+                world_id: r.get("world_id"),
+                unique: r.get("unique"),
+                metadata: r.get("metadata"),
+            }
+            .to_memory()
+        }))
+        }
+        .await;
+
+        Self::finish(
+            "delete_memory",
+            "write",
+            result.as_ref().map(|m| m.is_some() as u64).unwrap_or(0),
+            started,
+            &result,
+        );
+        result
     }
 
-    async fn delete_many_memories(&self, memory_ids: &[UUID]) -> Result<()> {
+    #[tracing::instrument(
+        skip(self, memory_ids),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "delete_many_memories",
+            db.rows_count = memory_ids.len(),
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn delete_many_memories(&self, memory_ids: &[UUID]) -> Result<Vec<Memory>> {
+        let started = std::time::Instant::now();
+        let result: Result<Vec<Memory>> = async {
         let uuids: Vec<uuid::Uuid> = memory_ids
             .iter()
             .filter_map(|id| uuid::Uuid::parse_str(id.as_str()).ok())
             .collect();
 
-        sqlx::query("DELETE FROM memories WHERE id = ANY($1)")
-            .bind(&uuids)
-            .execute(self.manager.get_pool())
-            .await?;
+        let rows = sqlx::query(
+            r#"
+            DELETE FROM memories WHERE id = ANY($1)
+            RETURNING id, type, created_at, content, entity_id, agent_id, room_id, world_id, "unique", metadata
+            "#,
+        )
+        .bind(&uuids)
+        .fetch_all(self.manager.get_pool())
+        .await?;
 
-        Ok(())
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                MemoryRecord {
+                    id: r.get("id"),
+                    memory_type: r.get("type"),
+                    created_at: r.get("created_at"),
+                    content: r.get("content"),
+                    entity_id: r.get("entity_id"),
+                    agent_id: r.get("agent_id"),
+                    room_id: r.get("room_id"),
+                    world_id: r.get("world_id"),
+                    unique: r.get("unique"),
+                    metadata: r.get("metadata"),
+                }
+                .to_memory()
+            })
+            .collect())
+        }
+        .await;
+
+        Self::finish(
+            "delete_many_memories",
+            "write",
+            result.as_ref().map(|rows| rows.len() as u64).unwrap_or(0),
+            started,
+            &result,
+        );
+        result
     }
 
     async fn delete_all_memories(&self, room_id: &UUID, table_name: &str) -> Result<()> {
@@ -940,6 +1933,77 @@ impl DatabaseAdapter for PostgresAdapter {
         Ok(count)
     }
 
+    #[tracing::instrument(
+        skip(self),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "memory_stats",
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn memory_stats(&self, room_id: &UUID, table_name: Option<&str>) -> Result<MemoryStats> {
+        let started = std::time::Instant::now();
+        let result: Result<MemoryStats> = async {
+        let uuid = uuid::Uuid::parse_str(room_id.as_str())?;
+
+        // One row per distinct `type` in the room; the overall totals are just these rows
+        // summed/min'd/max'd in Rust below, so the database only has to do a single grouped scan.
+        let mut qb = sqlx::QueryBuilder::new(
+            r#"
+            SELECT
+                m.type AS memory_type,
+                COUNT(*) AS total,
+                COUNT(e.memory_id) AS with_embedding,
+                COUNT(*) FILTER (WHERE m."unique") AS unique_count,
+                (EXTRACT(EPOCH FROM MIN(m.created_at)) * 1000)::bigint AS earliest,
+                (EXTRACT(EPOCH FROM MAX(m.created_at)) * 1000)::bigint AS latest
+            FROM memories m
+            LEFT JOIN embeddings e ON e.memory_id = m.id
+            WHERE m.room_id =
+            "#,
+        );
+        qb.push_bind(uuid);
+
+        if let Some(table) = table_name {
+            qb.push(" AND m.type = ").push_bind(table);
+        }
+
+        qb.push(" GROUP BY m.type");
+
+        let rows = qb.build().fetch_all(self.manager.get_pool()).await?;
+
+        let mut stats = MemoryStats::default();
+        for r in rows {
+            let memory_type: String = r.get("memory_type");
+            let total: i64 = r.get("total");
+            let with_embedding: i64 = r.get("with_embedding");
+            let unique_count: i64 = r.get("unique_count");
+            let earliest: Option<i64> = r.get("earliest");
+            let latest: Option<i64> = r.get("latest");
+
+            stats.total += total;
+            stats.with_embedding += with_embedding;
+            stats.unique_count += unique_count;
+            stats.earliest_created_at = match (stats.earliest_created_at, earliest) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            stats.latest_created_at = match (stats.latest_created_at, latest) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            };
+            stats.by_type.insert(memory_type, total);
+        }
+
+        Ok(stats)
+        }
+        .await;
+
+        Self::finish("memory_stats", "read", 1, started, &result);
+        result
+    }
+
     async fn ensure_embedding_dimension(&self, dimension: i32) -> Result<()> {
         if dimension != self.embedding_dimension {
             sqlx::query("DROP TABLE IF EXISTS embeddings")
@@ -1054,7 +2118,7 @@ impl DatabaseAdapter for PostgresAdapter {
         Ok(())
     }
 
-    async fn get_logs(&self, params: GetLogsParams) -> Result<Vec<Log>> {
+    async fn get_logs(&self, filters: QueryFilters) -> Result<Vec<Log>> {
         let mut qb = sqlx::QueryBuilder::new(
             r#"
             SELECT id, entity_id, room_id, type, body, created_at
@@ -1062,27 +2126,7 @@ impl DatabaseAdapter for PostgresAdapter {
             "#,
         );
 
-        if let Some(entity_id) = params.entity_id.as_ref() {
-            let entity_uuid = uuid::Uuid::parse_str(entity_id.as_str())?;
-            qb.push(" AND entity_id = ").push_bind(entity_uuid);
-        }
-        if let Some(room_id) = params.room_id.as_ref() {
-            let room_uuid = uuid::Uuid::parse_str(room_id.as_str())?;
-            qb.push(" AND room_id = ").push_bind(room_uuid);
-        }
-        if let Some(log_type) = params.log_type.as_ref() {
-            qb.push(" AND type = ").push_bind(log_type);
-        }
-
-        qb.push(" ORDER BY created_at DESC");
-
-        if let Some(count) = params.count {
-            qb.push(" LIMIT ").push_bind(count);
-        }
-
-        if let Some(offset) = params.offset {
-            qb.push(" OFFSET ").push_bind(offset);
-        }
+        push_query_filters(&mut qb, &filters, "created_at", "room_id", "entity_id", "type")?;
 
         let rows = qb.build().fetch_all(self.manager.get_pool()).await?;
 
@@ -1167,13 +2211,26 @@ impl DatabaseAdapter for PostgresAdapter {
         }))
     }
 
-    async fn remove_world(&self, id: &UUID) -> Result<()> {
+    async fn remove_world(&self, id: &UUID) -> Result<Option<World>> {
         let uuid = uuid::Uuid::parse_str(id.as_str())?;
-        sqlx::query("DELETE FROM worlds WHERE id = $1")
-            .bind(uuid)
-            .execute(self.manager.get_pool())
-            .await?;
-        Ok(())
+        let row = sqlx::query(
+            "DELETE FROM worlds WHERE id = $1 RETURNING id, created_at, name, agent_id, message_server_id, metadata",
+        )
+        .bind(uuid)
+        .fetch_optional(self.manager.get_pool())
+        .await?;
+
+        Ok(row.map(|r| {
+            WorldRecord {
+                id: r.get("id"),
+                created_at: r.get("created_at"),
+                name: r.get("name"),
+                agent_id: r.get("agent_id"),
+                message_server_id: r.get("message_server_id"),
+                metadata: r.get("metadata"),
+            }
+            .to_world()
+        }))
     }
 
     async fn get_all_worlds(&self) -> Result<Vec<World>> {
@@ -1488,24 +2545,30 @@ impl DatabaseAdapter for PostgresAdapter {
     }
 
     async fn add_participants_room(&self, entity_ids: &[UUID], room_id: &UUID) -> Result<bool> {
-        let room_uuid = uuid::Uuid::parse_str(room_id.as_str())?;
+        if entity_ids.is_empty() {
+            return Ok(true);
+        }
 
-        for entity_id in entity_ids {
-            let entity_uuid = uuid::Uuid::parse_str(entity_id.as_str())?;
+        let room_uuid = uuid::Uuid::parse_str(room_id.as_str())?;
+        let entity_uuids: Vec<uuid::Uuid> = entity_ids
+            .iter()
+            .map(|id| uuid::Uuid::parse_str(id.as_str()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let ids: Vec<uuid::Uuid> = entity_uuids.iter().map(|_| uuid::Uuid::new_v4()).collect();
+        let room_uuids: Vec<uuid::Uuid> = vec![room_uuid; entity_uuids.len()];
 
-            sqlx::query(
-                r#"
-                INSERT INTO participants (id, entity_id, room_id)
-                VALUES ($1, $2, $3)
-                ON CONFLICT DO NOTHING
-                "#,
-            )
-            .bind(uuid::Uuid::new_v4())
-            .bind(entity_uuid)
-            .bind(room_uuid)
-            .execute(self.manager.get_pool())
-            .await?;
-        }
+        sqlx::query(
+            r#"
+            INSERT INTO participants (id, entity_id, room_id)
+            SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::uuid[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&ids)
+        .bind(&entity_uuids)
+        .bind(&room_uuids)
+        .execute(self.manager.get_pool())
+        .await?;
 
         Ok(true)
     }
@@ -1668,6 +2731,70 @@ impl DatabaseAdapter for PostgresAdapter {
             .collect())
     }
 
+    async fn get_relationships_filtered(
+        &self,
+        params: GetRelationshipsParams,
+    ) -> Result<Vec<Relationship>> {
+        let entity_id = uuid::Uuid::parse_str(params.entity_id.as_str())?;
+
+        let mut qb = sqlx::QueryBuilder::new(
+            r#"
+            SELECT id, source_entity_id, target_entity_id, agent_id, tags, metadata, created_at
+            FROM relationships WHERE 1=1
+            "#,
+        );
+
+        match params.direction {
+            RelationshipDirection::Outgoing => {
+                qb.push(" AND source_entity_id = ").push_bind(entity_id);
+            }
+            RelationshipDirection::Incoming => {
+                qb.push(" AND target_entity_id = ").push_bind(entity_id);
+            }
+            RelationshipDirection::Either => {
+                qb.push(" AND (source_entity_id = ")
+                    .push_bind(entity_id)
+                    .push(" OR target_entity_id = ")
+                    .push_bind(entity_id)
+                    .push(")");
+            }
+        }
+
+        if let Some(tags) = params.tags.as_ref() {
+            let tags_json = serde_json::to_value(tags)?;
+            qb.push(" AND tags @> ").push_bind(tags_json);
+        }
+
+        if let Some(min_strength) = params.min_strength {
+            qb.push(" AND COALESCE((metadata->>'strength')::double precision, 0) >= ")
+                .push_bind(min_strength);
+        }
+
+        qb.push(" ORDER BY COALESCE((metadata->>'strength')::double precision, 0) DESC");
+
+        if let Some(limit) = params.limit {
+            qb.push(" LIMIT ").push_bind(limit);
+        }
+
+        let rows = qb.build().fetch_all(self.manager.get_pool()).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                RelationshipRecord {
+                    id: r.get("id"),
+                    source_entity_id: r.get("source_entity_id"),
+                    target_entity_id: r.get("target_entity_id"),
+                    agent_id: r.get("agent_id"),
+                    tags: r.get("tags"),
+                    metadata: r.get("metadata"),
+                    created_at: r.get("created_at"),
+                }
+                .to_relationship()
+            })
+            .collect())
+    }
+
     // =========================================================================
     // Cache Methods
     // =========================================================================
@@ -1714,6 +2841,38 @@ impl DatabaseAdapter for PostgresAdapter {
         Ok(true)
     }
 
+    async fn set_cache_with_ttl<T: serde::Serialize + Send + Sync>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl: std::time::Duration,
+    ) -> Result<bool> {
+        let json = serde_json::to_value(value)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO cache (key, value, expires_at)
+            VALUES ($1, $2, now() + make_interval(secs => $3::double precision))
+            ON CONFLICT (key) DO UPDATE SET value = $2, expires_at = now() + make_interval(secs => $3::double precision)
+            "#,
+        )
+        .bind(key)
+        .bind(&json)
+        .bind(ttl.as_secs_f64())
+        .execute(self.manager.get_pool())
+        .await?;
+
+        Ok(true)
+    }
+
+    async fn purge_expired_cache(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM cache WHERE expires_at IS NOT NULL AND expires_at < now()")
+            .execute(self.manager.get_pool())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     async fn delete_cache(&self, key: &str) -> Result<bool> {
         sqlx::query("DELETE FROM cache WHERE key = $1")
             .bind(key)
@@ -1771,6 +2930,70 @@ impl DatabaseAdapter for PostgresAdapter {
         Ok(UUID::new(&id.to_string()).unwrap())
     }
 
+    async fn create_task_idempotent(&self, task: &Task) -> Result<UUID> {
+        let id = task
+            .id
+            .as_ref()
+            .map(|u| uuid::Uuid::parse_str(u.as_str()).unwrap())
+            .unwrap_or_else(uuid::Uuid::new_v4);
+        let room_id = task
+            .room_id
+            .as_ref()
+            .map(|u| uuid::Uuid::parse_str(u.as_str()))
+            .transpose()?;
+        let entity_id = task
+            .entity_id
+            .as_ref()
+            .map(|u| uuid::Uuid::parse_str(u.as_str()))
+            .transpose()?;
+        let world_id = task
+            .world_id
+            .as_ref()
+            .map(|u| uuid::Uuid::parse_str(u.as_str()))
+            .transpose()?;
+        let tags = serde_json::to_value(&task.tags)?;
+        let metadata = serde_json::to_value(&task.metadata)?;
+        let uniq_hash = task_uniq_hash(task)?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO tasks (id, name, description, room_id, entity_id, world_id, status, tags, metadata, uniq_hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (uniq_hash) WHERE status IN ('pending', 'running') DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(id)
+        .bind(&task.name)
+        .bind(&task.description)
+        .bind(room_id)
+        .bind(entity_id)
+        .bind(world_id)
+        .bind(task.status.as_ref().map(|s| s.as_str()))
+        .bind(&tags)
+        .bind(&metadata)
+        .bind(&uniq_hash)
+        .fetch_optional(self.manager.get_pool())
+        .await?;
+
+        let existing_id: uuid::Uuid = match inserted {
+            Some(row) => row.get("id"),
+            None => {
+                // A live duplicate already won the partial unique index; hand its id back
+                // instead of erroring, so a retried caller gets the in-flight task either way.
+                sqlx::query(
+                    "SELECT id FROM tasks WHERE uniq_hash = $1 AND status IN ('pending', 'running')",
+                )
+                .bind(&uniq_hash)
+                .fetch_one(self.manager.get_pool())
+                .await?
+                .get("id")
+            }
+        };
+
+        Ok(UUID::new(&existing_id.to_string()).unwrap())
+    }
+
     async fn get_tasks(&self, params: GetTasksParams) -> Result<Vec<Task>> {
         let mut qb = sqlx::QueryBuilder::new(
             r#"
@@ -1919,4 +3142,305 @@ impl DatabaseAdapter for PostgresAdapter {
 
         Ok(())
     }
+
+    #[tracing::instrument(
+        skip(self),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "claim_tasks",
+            db.rows_count = tracing::field::Empty,
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn claim_tasks(&self, limit: i64, worker_id: &str) -> Result<Vec<Task>> {
+        let started = std::time::Instant::now();
+        let result: Result<Vec<Task>> = async {
+        let rows = sqlx::query(
+            r#"
+            UPDATE tasks SET status = 'running', updated_at = now(), locked_by = $2
+            WHERE id IN (
+                SELECT id FROM tasks
+                WHERE status = 'pending' AND (scheduled_at IS NULL OR scheduled_at <= now())
+                ORDER BY created_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, name, description, room_id, entity_id, world_id, status, tags, metadata,
+                      created_at, updated_at, scheduled_at, repeat_interval, data
+            "#,
+        )
+        .bind(limit)
+        .bind(worker_id)
+        .fetch_all(self.manager.get_pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                TaskRecord {
+                    id: r.get("id"),
+                    name: r.get("name"),
+                    description: r.get("description"),
+                    room_id: r.get("room_id"),
+                    entity_id: r.get("entity_id"),
+                    world_id: r.get("world_id"),
+                    status: r.get("status"),
+                    tags: r.get("tags"),
+                    metadata: r.get("metadata"),
+                    created_at: r.get("created_at"),
+                    updated_at: r.get("updated_at"),
+                    scheduled_at: r.try_get("scheduled_at").ok().flatten(),
+                    repeat_interval: r.try_get("repeat_interval").ok().flatten(),
+                    data: r.try_get("data").ok().flatten(),
+                }
+                .to_task()
+            })
+            .collect())
+        }
+        .await;
+
+        Self::finish(
+            "claim_tasks",
+            "write",
+            result.as_ref().map(|rows| rows.len() as u64).unwrap_or(0),
+            started,
+            &result,
+        );
+        result
+    }
+
+    async fn fail_task(&self, id: &UUID, error_message: &str) -> Result<()> {
+        let uuid = uuid::Uuid::parse_str(id.as_str())?;
+
+        // `retries`/`max_retries` live on the row itself, so the backoff/failed decision is made
+        // in one UPDATE rather than a read-then-write race between concurrent workers.
+        sqlx::query(
+            r#"
+            UPDATE tasks SET
+                retries = retries + 1,
+                error_message = $2,
+                status = CASE WHEN retries + 1 >= max_retries THEN 'failed' ELSE 'pending' END,
+                scheduled_at = CASE
+                    WHEN retries + 1 >= max_retries THEN scheduled_at
+                    ELSE now() + (make_interval(secs => $3::double precision) * power(2, retries + 1))
+                END,
+                updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(uuid)
+        .bind(error_message)
+        .bind(TASK_RETRY_BASE_SECONDS)
+        .execute(self.manager.get_pool())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn complete_task(&self, id: &UUID) -> Result<()> {
+        let uuid = uuid::Uuid::parse_str(id.as_str())?;
+
+        let row = sqlx::query("SELECT repeat_interval, metadata FROM tasks WHERE id = $1")
+            .bind(uuid)
+            .fetch_optional(self.manager.get_pool())
+            .await?;
+
+        let Some(row) = row else {
+            return Ok(());
+        };
+
+        let repeat_interval: Option<i64> = row.get("repeat_interval");
+        let metadata: serde_json::Value = row.get("metadata");
+        let cron_expression = metadata
+            .get("cronExpression")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        if let Some(interval_ms) = repeat_interval {
+            // Fixed-interval periodic task: advance `scheduled_at` by `repeat_interval` and go
+            // back to `pending` instead of being marked `completed`.
+            sqlx::query(
+                r#"
+                UPDATE tasks SET
+                    status = 'pending',
+                    scheduled_at = now() + make_interval(secs => $2::double precision),
+                    updated_at = now()
+                WHERE id = $1
+                "#,
+            )
+            .bind(uuid)
+            .bind(interval_ms as f64 / 1000.0)
+            .execute(self.manager.get_pool())
+            .await?;
+        } else if let Some(expression) = cron_expression {
+            // Cron-scheduled periodic task: compute the next fire time strictly after `now()`.
+            let schedule = CronSchedule::from_str(&expression)
+                .context("Invalid cron expression in task metadata")?;
+            let next = schedule
+                .upcoming(chrono::Utc)
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Cron schedule has no upcoming fire time"))?;
+
+            sqlx::query(
+                "UPDATE tasks SET status = 'pending', scheduled_at = $2, updated_at = now() WHERE id = $1",
+            )
+            .bind(uuid)
+            .bind(next)
+            .execute(self.manager.get_pool())
+            .await?;
+        } else {
+            // Plain one-shot task: nothing reschedules it, so its row is removed rather than
+            // left behind as "completed" forever.
+            sqlx::query("DELETE FROM tasks WHERE id = $1")
+                .bind(uuid)
+                .execute(self.manager.get_pool())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn create_periodic_task(&self, task: &Task, schedule: TaskSchedule) -> Result<UUID> {
+        let mut task = task.clone();
+
+        match &schedule {
+            TaskSchedule::Interval(interval_ms) => {
+                task.repeat_interval = Some(*interval_ms);
+            }
+            TaskSchedule::Cron(expression) => {
+                CronSchedule::from_str(expression).context("Invalid cron expression")?;
+
+                let mut metadata = task.metadata.unwrap_or_default();
+                metadata.insert(
+                    "cronExpression".to_string(),
+                    serde_json::Value::String(expression.clone()),
+                );
+                task.metadata = Some(metadata);
+            }
+        }
+
+        self.create_task(&task).await
+    }
+
+    #[tracing::instrument(
+        skip(self, bytes),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "put_blob",
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn put_blob(&self, bytes: Vec<u8>, content_type: &str) -> Result<UUID> {
+        let started = std::time::Instant::now();
+        let result: Result<UUID> = async {
+            let blob_store = self
+                .blob_store
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("No blob store configured on this adapter"))?;
+
+            let media_id = uuid::Uuid::new_v4();
+            let size_bytes = bytes.len() as i64;
+            let url = blob_store.put(&media_id, bytes, content_type).await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO media (media_id, url, content_type, size_bytes)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(media_id)
+            .bind(&url)
+            .bind(content_type)
+            .bind(size_bytes)
+            .execute(self.manager.get_pool())
+            .await?;
+
+            Ok(UUID::new(&media_id.to_string()).unwrap())
+        }
+        .await;
+
+        Self::finish("put_blob", "write", 1, started, &result);
+        result
+    }
+
+    #[tracing::instrument(
+        skip(self),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "get_blob",
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn get_blob(&self, media_id: &UUID) -> Result<Option<Vec<u8>>> {
+        let started = std::time::Instant::now();
+        let result: Result<Option<Vec<u8>>> = async {
+            let blob_store = self
+                .blob_store
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("No blob store configured on this adapter"))?;
+
+            let uuid = uuid::Uuid::parse_str(media_id.as_str())?;
+
+            let row = sqlx::query("SELECT url FROM media WHERE media_id = $1")
+                .bind(uuid)
+                .fetch_optional(self.manager.get_pool())
+                .await?;
+
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let url: String = row.get("url");
+
+            Ok(Some(blob_store.get(&url).await?))
+        }
+        .await;
+
+        Self::finish("get_blob", "read", 1, started, &result);
+        result
+    }
+
+    #[tracing::instrument(
+        skip(self),
+        fields(
+            otel.kind = "client",
+            db.system = "postgresql",
+            db.operation = "delete_blob",
+            db.error.category = tracing::field::Empty,
+        )
+    )]
+    async fn delete_blob(&self, media_id: &UUID) -> Result<()> {
+        let started = std::time::Instant::now();
+        let result: Result<()> = async {
+            let blob_store = self
+                .blob_store
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("No blob store configured on this adapter"))?;
+
+            let uuid = uuid::Uuid::parse_str(media_id.as_str())?;
+
+            let row = sqlx::query("SELECT url FROM media WHERE media_id = $1")
+                .bind(uuid)
+                .fetch_optional(self.manager.get_pool())
+                .await?;
+
+            if let Some(row) = row {
+                let url: String = row.get("url");
+                blob_store.delete(&url).await?;
+
+                sqlx::query("DELETE FROM media WHERE media_id = $1")
+                    .bind(uuid)
+                    .execute(self.manager.get_pool())
+                    .await?;
+            }
+
+            Ok(())
+        }
+        .await;
+
+        Self::finish("delete_blob", "write", 1, started, &result);
+        result
+    }
 }
@@ -0,0 +1,171 @@
+#![allow(missing_docs)]
+//! S3-compatible object storage backend for [`super::PostgresAdapter`]'s blob offload.
+//!
+//! This only talks to the object store itself (put/get/delete bytes by key); the `media_id` ->
+//! URL mapping lives in Postgres and is owned by the adapter's `put_blob`/`get_blob`/`delete_blob`
+//! methods. Swapping buckets or providers (AWS, MinIO, Garage, R2, ...) only ever touches
+//! [`BlobStoreConfig`], since every S3-compatible provider speaks the same API behind a
+//! configurable `endpoint`.
+
+use anyhow::{Context, Result};
+use aws_config::BehaviorVersion;
+use aws_credential_types::Credentials;
+use aws_sdk_s3::{
+    config::{Builder as S3ConfigBuilder, Region},
+    primitives::ByteStream,
+    Client,
+};
+
+/// Configuration for the S3-compatible backend a [`BlobStore`] uploads to.
+#[derive(Clone, Debug)]
+pub struct BlobStoreConfig {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub region: String,
+    pub bucket: String,
+    pub key_prefix: String,
+    pub endpoint: Option<String>,
+    pub force_path_style: bool,
+}
+
+impl BlobStoreConfig {
+    /// Create a new blob store configuration.
+    pub fn new(access_key_id: &str, secret_access_key: &str, region: &str, bucket: &str) -> Self {
+        Self {
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+            region: region.to_string(),
+            bucket: bucket.to_string(),
+            key_prefix: String::new(),
+            endpoint: None,
+            force_path_style: false,
+        }
+    }
+
+    /// Set a key prefix applied to every object this store writes (e.g. `"media/"`).
+    pub fn key_prefix(mut self, prefix: &str) -> Self {
+        self.key_prefix = prefix.to_string();
+        self
+    }
+
+    /// Set a custom S3-compatible endpoint (MinIO, Garage, R2, ...).
+    pub fn endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Enable path-style addressing, required by most non-AWS S3-compatible providers.
+    pub fn force_path_style(mut self, force: bool) -> Self {
+        self.force_path_style = force;
+        self
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        if let Some(ref endpoint) = self.endpoint {
+            format!("{}/{}/{}", endpoint, self.bucket, key)
+        } else {
+            format!(
+                "https://{}.s3.{}.amazonaws.com/{}",
+                self.bucket, self.region, key
+            )
+        }
+    }
+
+    /// Recover the object key this store would have generated for a URL previously returned by
+    /// [`BlobStore::put`], so a `get`/`delete` only needs the `media` table's stored URL.
+    fn key_from_url(&self, url: &str) -> String {
+        url.rsplit_once(&format!("/{}/", self.bucket))
+            .map(|(_, key)| key.to_string())
+            .unwrap_or_else(|| url.to_string())
+    }
+}
+
+/// Thin wrapper around an S3-compatible client used to transparently offload large inline
+/// content that would otherwise bloat `memories`/`components` rows and the WAL.
+pub struct BlobStore {
+    client: Client,
+    config: BlobStoreConfig,
+}
+
+impl BlobStore {
+    /// Build a blob store client from `config`.
+    pub async fn new(config: BlobStoreConfig) -> Result<Self> {
+        let credentials = Credentials::new(
+            &config.access_key_id,
+            &config.secret_access_key,
+            None,
+            None,
+            "elizaos-plugin-sql",
+        );
+
+        let mut s3_config = S3ConfigBuilder::new()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new(config.region.clone()))
+            .credentials_provider(credentials)
+            .force_path_style(config.force_path_style);
+
+        if let Some(ref endpoint) = config.endpoint {
+            s3_config = s3_config.endpoint_url(endpoint);
+        }
+
+        let client = Client::from_conf(s3_config.build());
+
+        Ok(Self { client, config })
+    }
+
+    /// Upload `bytes` under a fresh object key derived from `media_id`, returning the object's
+    /// public URL to persist in the `media` table.
+    pub async fn put(&self, media_id: &uuid::Uuid, bytes: Vec<u8>, content_type: &str) -> Result<String> {
+        let key = format!("{}{}", self.config.key_prefix, media_id);
+
+        self.client
+            .put_object()
+            .bucket(&self.config.bucket)
+            .key(&key)
+            .body(ByteStream::from(bytes))
+            .content_type(content_type)
+            .send()
+            .await
+            .context("Failed to upload blob to object storage")?;
+
+        Ok(self.config.public_url(&key))
+    }
+
+    /// Download the bytes previously uploaded under `url` (as returned by [`BlobStore::put`]).
+    pub async fn get(&self, url: &str) -> Result<Vec<u8>> {
+        let key = self.config.key_from_url(url);
+
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.config.bucket)
+            .key(&key)
+            .send()
+            .await
+            .context("Failed to download blob from object storage")?;
+
+        let bytes = response
+            .body
+            .collect()
+            .await
+            .context("Failed to read blob body from object storage")?
+            .into_bytes();
+
+        Ok(bytes.to_vec())
+    }
+
+    /// Delete the object previously uploaded under `url` (as returned by [`BlobStore::put`]).
+    pub async fn delete(&self, url: &str) -> Result<()> {
+        let key = self.config.key_from_url(url);
+
+        self.client
+            .delete_object()
+            .bucket(&self.config.bucket)
+            .key(&key)
+            .send()
+            .await
+            .context("Failed to delete blob from object storage")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+#![allow(missing_docs)]
+//! PostgreSQL-backed adapter for native (non-WASM) deployments.
+
+pub mod adapter;
+pub mod blob;
+pub mod manager;
+pub mod telemetry;
+pub mod transaction;
+
+pub use adapter::PostgresAdapter;
+pub use blob::{BlobStore, BlobStoreConfig};
+pub use manager::PostgresConnectionManager;
+pub use transaction::PostgresTransaction;
@@ -0,0 +1,62 @@
+#![allow(missing_docs)]
+//! Tracing and metrics helpers for [`super::PostgresAdapter`].
+//!
+//! These build on the `tracing` and `metrics` facades rather than talking to an OTEL SDK
+//! directly: both are no-ops until a subscriber/recorder is installed, so `PostgresAdapter` stays
+//! usable embedded with zero instrumentation overhead when nothing is configured. Wiring an
+//! actual OTLP exporter on top is a separate, opt-in concern — see [`crate::otel`].
+
+use std::time::Duration;
+
+use metrics::{counter, gauge, histogram};
+
+use super::PostgresConnectionManager;
+
+/// Classify a failed database operation's error for the `db.error.category` span/metric label,
+/// without leaking the full (potentially sensitive) error message into low-cardinality metrics.
+pub(crate) fn sql_error_category(err: &anyhow::Error) -> &'static str {
+    match err.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(db_err)) => match db_err.code().as_deref() {
+            Some(code) if code.starts_with("23") => "constraint_violation",
+            Some(code) if code.starts_with("08") => "connection",
+            Some(code) if code.starts_with("57") => "operator_intervention",
+            _ => "database",
+        },
+        Some(sqlx::Error::PoolTimedOut) => "pool_timeout",
+        Some(sqlx::Error::PoolClosed) => "pool_closed",
+        Some(sqlx::Error::RowNotFound) => "row_not_found",
+        Some(sqlx::Error::ColumnNotFound(_)) => "column_not_found",
+        Some(sqlx::Error::Io(_)) => "io",
+        Some(_) => "other_sqlx",
+        None => "other",
+    }
+}
+
+/// Record rows affected/returned by an operation against the shared row-count counters, split by
+/// operation name and direction (`read` vs `write`) so dashboards can break down throughput per
+/// method without the adapter needing one counter per method.
+pub(crate) fn record_rows(operation: &'static str, direction: &'static str, rows: u64) {
+    counter!("db_rows_total", "operation" => operation, "direction" => direction).increment(rows);
+}
+
+/// Record how long an operation took against the shared latency histogram, keyed by operation
+/// name, so per-operation p50/p95/p99 can be graphed without one histogram per method.
+pub(crate) fn record_latency(operation: &'static str, elapsed: Duration) {
+    histogram!("db_query_duration_seconds", "operation" => operation).record(elapsed.as_secs_f64());
+}
+
+/// Record this operation's outcome (success/error) against the shared outcome counter.
+pub(crate) fn record_outcome(operation: &'static str, success: bool) {
+    let outcome = if success { "ok" } else { "error" };
+    counter!("db_operations_total", "operation" => operation, "outcome" => outcome).increment(1);
+}
+
+/// Sample the connection pool's current utilization into a gauge, so "how saturated is the pool"
+/// can be graphed without a separate poller — call this periodically (e.g. from a health check)
+/// or at points where a caller is about to wait on a connection.
+pub fn record_pool_utilization(manager: &PostgresConnectionManager) {
+    let pool = manager.get_pool();
+    gauge!("db_pool_connections_in_use").set((pool.size() as usize - pool.num_idle()) as f64);
+    gauge!("db_pool_connections_idle").set(pool.num_idle() as f64);
+    gauge!("db_pool_connections_max").set(pool.options().get_max_connections() as f64);
+}
@@ -0,0 +1,404 @@
+#![allow(missing_docs)]
+//! A handle over an open PostgreSQL transaction, so a caller can compose several writes (e.g.
+//! creating a room, adding its participants, and logging the event) into one unit and have
+//! partial writes undone on error, instead of each `PostgresAdapter` method auto-committing its
+//! own statement.
+
+use anyhow::{Context, Result};
+use elizaos::{Component, Entity, Memory, Room, Task, UUID};
+use sqlx::{Postgres, Transaction};
+
+use crate::base::{CreateRelationshipParams, LogParams};
+use crate::schema::MemoryRecord;
+
+use super::adapter::ENTITY_BATCH_SIZE;
+use super::PostgresAdapter;
+
+/// An open PostgreSQL transaction, with a small CRUD surface mirroring the subset of
+/// [`crate::base::DatabaseAdapter`] needed to compose multi-row writes atomically. Nothing is
+/// persisted until [`commit`](PostgresTransaction::commit) is called; dropping the handle without
+/// committing rolls the transaction back.
+///
+/// `sqlx::Pool::begin()` returns a `Transaction<'static, Postgres>` that owns its checked-out
+/// connection rather than borrowing it, so this handle can hold the transaction directly without
+/// any self-referential borrow-from-connection bookkeeping.
+pub struct PostgresTransaction {
+    tx: Transaction<'static, Postgres>,
+    agent_id: uuid::Uuid,
+}
+
+impl PostgresAdapter {
+    /// Begin a new transaction against this adapter's connection pool.
+    pub async fn begin_transaction(&self) -> Result<PostgresTransaction> {
+        let tx = self
+            .manager
+            .get_pool()
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+        Ok(PostgresTransaction {
+            tx,
+            agent_id: self.agent_id,
+        })
+    }
+}
+
+impl PostgresTransaction {
+    /// Insert entities, as [`PostgresAdapter::create_entities`] does, but on this transaction.
+    pub async fn create_entities(&mut self, entities: &[Entity]) -> Result<bool> {
+        if entities.is_empty() {
+            return Ok(true);
+        }
+
+        for chunk in entities.chunks(ENTITY_BATCH_SIZE) {
+            let mut ids = Vec::with_capacity(chunk.len());
+            let mut agent_ids = Vec::with_capacity(chunk.len());
+            let mut names = Vec::with_capacity(chunk.len());
+            let mut metadatas = Vec::with_capacity(chunk.len());
+
+            for entity in chunk {
+                let id = entity
+                    .id
+                    .as_ref()
+                    .map(|u| uuid::Uuid::parse_str(u.as_str()).unwrap())
+                    .unwrap_or_else(uuid::Uuid::new_v4);
+                let agent_id = entity
+                    .agent_id
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Entity agent_id is required"))?;
+                let agent_id = uuid::Uuid::parse_str(agent_id.as_str())?;
+
+                ids.push(id);
+                agent_ids.push(agent_id);
+                names.push(serde_json::to_value(&entity.names)?);
+                metadatas.push(serde_json::to_value(&entity.metadata)?);
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO entities (id, agent_id, names, metadata)
+                SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::jsonb[], $4::jsonb[])
+                ON CONFLICT (id) DO NOTHING
+                "#,
+            )
+            .bind(&ids)
+            .bind(&agent_ids)
+            .bind(&names)
+            .bind(&metadatas)
+            .execute(&mut *self.tx)
+            .await?;
+        }
+        Ok(true)
+    }
+
+    /// Insert a component, as [`PostgresAdapter::create_component`] does, but on this transaction.
+    pub async fn create_component(&mut self, component: &Component) -> Result<bool> {
+        let id =
+            uuid::Uuid::parse_str(component.id.as_str()).unwrap_or_else(|_| uuid::Uuid::new_v4());
+        let entity_id = uuid::Uuid::parse_str(component.entity_id.as_str())?;
+        let agent_id = uuid::Uuid::parse_str(component.agent_id.as_str())?;
+        let room_id = uuid::Uuid::parse_str(component.room_id.as_str())?;
+        let world_id = component
+            .world_id
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Component world_id is required"))?;
+        let world_id = uuid::Uuid::parse_str(world_id.as_str())?;
+        let source_entity_id = component
+            .source_entity_id
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Component source_entity_id is required"))?;
+        let source_entity_id = uuid::Uuid::parse_str(source_entity_id.as_str())?;
+        let data = serde_json::to_value(&component.data)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO components (id, entity_id, agent_id, room_id, world_id, source_entity_id, type, data)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(id)
+        .bind(entity_id)
+        .bind(agent_id)
+        .bind(room_id)
+        .bind(world_id)
+        .bind(source_entity_id)
+        .bind(&component.component_type)
+        .bind(&data)
+        .execute(&mut *self.tx)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Insert a memory (and its embedding, if any), as [`PostgresAdapter::create_memory`] does,
+    /// but on this transaction.
+    pub async fn create_memory(
+        &mut self,
+        memory: &Memory,
+        table_name: &str,
+        _unique: bool,
+    ) -> Result<UUID> {
+        let record = MemoryRecord::from_memory(memory, table_name);
+
+        sqlx::query(
+            r#"
+            INSERT INTO memories (id, type, content, entity_id, agent_id, room_id, world_id, "unique", metadata)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#
+        )
+        .bind(record.id)
+        .bind(&record.memory_type)
+        .bind(&record.content)
+        .bind(record.entity_id)
+        .bind(record.agent_id)
+        .bind(record.room_id)
+        .bind(record.world_id)
+        .bind(record.unique)
+        .bind(&record.metadata)
+        .execute(&mut *self.tx)
+        .await?;
+
+        if let Some(embedding) = &memory.embedding {
+            sqlx::query(
+                r#"
+                INSERT INTO embeddings (memory_id, embedding)
+                VALUES ($1, $2::vector)
+                "#,
+            )
+            .bind(record.id)
+            .bind(embedding)
+            .execute(&mut *self.tx)
+            .await?;
+        }
+
+        Ok(UUID::new(&record.id.to_string()).unwrap())
+    }
+
+    /// Insert rooms, as [`PostgresAdapter::create_rooms`](crate::base::DatabaseAdapter::create_rooms)
+    /// does, but on this transaction.
+    pub async fn create_rooms(&mut self, rooms: &[Room]) -> Result<Vec<UUID>> {
+        let mut created_ids = Vec::new();
+
+        for room in rooms {
+            let id = uuid::Uuid::parse_str(room.id.as_str())?;
+            let agent_id = room
+                .agent_id
+                .as_ref()
+                .map(|u| uuid::Uuid::parse_str(u.as_str()))
+                .transpose()?;
+            let world_id = room
+                .world_id
+                .as_ref()
+                .map(|u| uuid::Uuid::parse_str(u.as_str()))
+                .transpose()?;
+            let metadata = serde_json::to_value(&room.metadata)?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO rooms (id, name, agent_id, source, type, channel_id, message_server_id, world_id, metadata)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (id) DO NOTHING
+                "#,
+            )
+            .bind(id)
+            .bind(&room.name)
+            .bind(agent_id)
+            .bind(&room.source)
+            .bind(room.room_type.as_str())
+            .bind(&room.channel_id)
+            .bind(
+                room.message_server_id
+                    .as_ref()
+                    .and_then(|u| uuid::Uuid::parse_str(u.as_str()).ok()),
+            )
+            .bind(world_id)
+            .bind(&metadata)
+            .execute(&mut *self.tx)
+            .await?;
+
+            created_ids.push(room.id.clone());
+        }
+
+        Ok(created_ids)
+    }
+
+    /// Add participants to a room, as
+    /// [`PostgresAdapter::add_participants_room`](crate::base::DatabaseAdapter::add_participants_room)
+    /// does, but on this transaction.
+    pub async fn add_participants_room(
+        &mut self,
+        entity_ids: &[UUID],
+        room_id: &UUID,
+    ) -> Result<bool> {
+        if entity_ids.is_empty() {
+            return Ok(true);
+        }
+
+        let room_uuid = uuid::Uuid::parse_str(room_id.as_str())?;
+        let entity_uuids: Vec<uuid::Uuid> = entity_ids
+            .iter()
+            .map(|id| uuid::Uuid::parse_str(id.as_str()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ids: Vec<uuid::Uuid> = entity_uuids.iter().map(|_| uuid::Uuid::new_v4()).collect();
+        let room_uuids: Vec<uuid::Uuid> = vec![room_uuid; entity_uuids.len()];
+
+        sqlx::query(
+            r#"
+            INSERT INTO participants (id, entity_id, room_id)
+            SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::uuid[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&ids)
+        .bind(&entity_uuids)
+        .bind(&room_uuids)
+        .execute(&mut *self.tx)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Create a relationship, as
+    /// [`PostgresAdapter::create_relationship`](crate::base::DatabaseAdapter::create_relationship)
+    /// does, but on this transaction.
+    pub async fn create_relationship(&mut self, params: CreateRelationshipParams) -> Result<bool> {
+        let source_id = uuid::Uuid::parse_str(params.source_entity_id.as_str())?;
+        let target_id = uuid::Uuid::parse_str(params.target_entity_id.as_str())?;
+        let tags = serde_json::to_value(&params.tags)?;
+        let metadata = serde_json::to_value(&params.metadata)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO relationships (id, source_entity_id, target_entity_id, agent_id, tags, metadata)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(source_id)
+        .bind(target_id)
+        .bind(self.agent_id)
+        .bind(&tags)
+        .bind(&metadata)
+        .execute(&mut *self.tx)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Create a task, as [`PostgresAdapter::create_task`](crate::base::DatabaseAdapter::create_task)
+    /// does, but on this transaction.
+    pub async fn create_task(&mut self, task: &Task) -> Result<UUID> {
+        let id = task
+            .id
+            .as_ref()
+            .map(|u| uuid::Uuid::parse_str(u.as_str()).unwrap())
+            .unwrap_or_else(uuid::Uuid::new_v4);
+        let room_id = task
+            .room_id
+            .as_ref()
+            .map(|u| uuid::Uuid::parse_str(u.as_str()))
+            .transpose()?;
+        let entity_id = task
+            .entity_id
+            .as_ref()
+            .map(|u| uuid::Uuid::parse_str(u.as_str()))
+            .transpose()?;
+        let world_id = task
+            .world_id
+            .as_ref()
+            .map(|u| uuid::Uuid::parse_str(u.as_str()))
+            .transpose()?;
+        let tags = serde_json::to_value(&task.tags)?;
+        let metadata = serde_json::to_value(&task.metadata)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, name, description, room_id, entity_id, world_id, status, tags, metadata)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(id)
+        .bind(&task.name)
+        .bind(&task.description)
+        .bind(room_id)
+        .bind(entity_id)
+        .bind(world_id)
+        .bind(task.status.as_ref().map(|s| s.as_str()))
+        .bind(&tags)
+        .bind(&metadata)
+        .execute(&mut *self.tx)
+        .await?;
+
+        Ok(UUID::new(&id.to_string()).unwrap())
+    }
+
+    /// Set a cached value, as [`PostgresAdapter::set_cache`](crate::base::DatabaseAdapter::set_cache)
+    /// does, but on this transaction.
+    pub async fn set_cache<T: serde::Serialize + Send + Sync>(
+        &mut self,
+        key: &str,
+        value: &T,
+    ) -> Result<bool> {
+        let json = serde_json::to_value(value)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO cache (key, value) VALUES ($1, $2)
+            ON CONFLICT (key) DO UPDATE SET value = $2
+            "#,
+        )
+        .bind(key)
+        .bind(&json)
+        .execute(&mut *self.tx)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Insert a log entry, as [`PostgresAdapter::log`](crate::base::DatabaseAdapter::log) does,
+    /// but on this transaction.
+    pub async fn log(&mut self, params: LogParams) -> Result<()> {
+        let entity_id = uuid::Uuid::parse_str(params.entity_id.as_str())?;
+        let room_id = params
+            .room_id
+            .as_ref()
+            .map(|r| uuid::Uuid::parse_str(r.as_str()))
+            .transpose()?;
+        let body = serde_json::to_value(&params.body)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO logs (id, entity_id, room_id, type, body)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(entity_id)
+        .bind(room_id)
+        .bind(&params.log_type)
+        .bind(&body)
+        .execute(&mut *self.tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Commit every write made on this transaction.
+    pub async fn commit(self) -> Result<()> {
+        self.tx
+            .commit()
+            .await
+            .context("Failed to commit transaction")
+    }
+
+    /// Discard every write made on this transaction. Equivalent to dropping the handle, but
+    /// surfaces rollback errors instead of ignoring them.
+    pub async fn rollback(self) -> Result<()> {
+        self.tx
+            .rollback()
+            .await
+            .context("Failed to roll back transaction")
+    }
+}
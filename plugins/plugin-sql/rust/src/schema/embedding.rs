@@ -0,0 +1,34 @@
+#![allow(missing_docs)]
+//! Embeddings table schema: stores memory embeddings for pgvector-backed similarity search.
+
+/// Default vector dimension used when an adapter is constructed without an explicit embedding
+/// dimension (matches OpenAI's `text-embedding-3-small`).
+pub const DEFAULT_DIMENSION: i32 = 1536;
+
+/// Ensure the `vector` extension (pgvector) is installed before any embedding table DDL runs.
+pub const ENSURE_VECTOR_EXTENSION: &str = "CREATE EXTENSION IF NOT EXISTS vector";
+
+/// Build the `CREATE TABLE` statement for the embeddings table, with a `vector(dimension)`
+/// column sized to the adapter's configured embedding dimension.
+pub fn create_embeddings_table_sql(dimension: i32) -> String {
+    format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS embeddings (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            memory_id UUID NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+            embedding vector({dimension}),
+            created_at BIGINT NOT NULL DEFAULT (extract(epoch from now()) * 1000)::bigint
+        )
+        "#
+    )
+}
+
+/// Indexes for the embeddings table: an HNSW index over the vector column (cosine distance) for
+/// fast approximate nearest-neighbor search, plus a plain index on `memory_id` for row-level
+/// joins and deletes.
+pub const CREATE_EMBEDDINGS_INDEXES: &str = r#"
+    CREATE INDEX IF NOT EXISTS idx_embeddings_memory_id ON embeddings (memory_id);
+    CREATE INDEX IF NOT EXISTS idx_embeddings_vector_hnsw
+        ON embeddings USING hnsw (embedding vector_cosine_ops);
+"#;
+
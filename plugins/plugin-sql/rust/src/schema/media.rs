@@ -0,0 +1,34 @@
+#![allow(missing_docs)]
+//! Media schema for elizaOS database
+//!
+//! Stores only a mapping from a generated `media_id` to the external object-storage URL a blob
+//! was uploaded to, so large binary content (images, audio, documents) never bloats `memories`
+//! or `components` rows and the WAL alongside them.
+
+/// SQL for creating the media table
+pub const CREATE_MEDIA_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS media (
+    media_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    url TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+)
+"#;
+
+/// SQL for creating indexes on media table
+pub const CREATE_MEDIA_INDEXES: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_media_created_at ON media (created_at);
+"#;
+
+/// Media record structure
+#[derive(Clone, Debug)]
+pub struct MediaRecord {
+    pub media_id: uuid::Uuid,
+    pub url: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
@@ -9,6 +9,7 @@ pub mod component;
 pub mod embedding;
 pub mod entity;
 pub mod log;
+pub mod media;
 pub mod memory;
 pub mod participant;
 pub mod relationship;
@@ -22,6 +23,7 @@ pub use component::ComponentRecord;
 pub use embedding::DEFAULT_DIMENSION;
 pub use entity::EntityRecord;
 pub use log::LogRecord;
+pub use media::MediaRecord;
 pub use memory::MemoryRecord;
 pub use participant::ParticipantRecord;
 pub use relationship::RelationshipRecord;
@@ -55,4 +57,6 @@ pub mod table_names {
     pub const LOGS: &str = "logs";
     /// Cache table
     pub const CACHE: &str = "cache";
+    /// Media table
+    pub const MEDIA: &str = "media";
 }
@@ -21,6 +21,24 @@ CREATE TABLE IF NOT EXISTS tasks (
 )
 "#;
 
+/// SQL adding the columns needed to use `tasks` as a claimable work queue: a retry counter and
+/// cap, the last failure's error message, and which worker currently holds the row.
+pub const ADD_TASKS_QUEUE_COLUMNS: &str = r#"
+ALTER TABLE tasks
+    ADD COLUMN IF NOT EXISTS retries INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS max_retries INTEGER NOT NULL DEFAULT 3,
+    ADD COLUMN IF NOT EXISTS error_message TEXT,
+    ADD COLUMN IF NOT EXISTS locked_by TEXT
+"#;
+
+/// SQL adding the column and partial unique index backing idempotent task creation: a task
+/// created with a uniqueness hash can't have a second "live" (pending/running) duplicate.
+pub const ADD_TASKS_UNIQ_HASH_COLUMN: &str = r#"
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS uniq_hash TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tasks_uniq_hash_live ON tasks (uniq_hash)
+    WHERE status IN ('pending', 'running');
+"#;
+
 /// SQL for creating indexes on tasks table
 pub const CREATE_TASKS_INDEXES: &str = r#"
 CREATE INDEX IF NOT EXISTS idx_tasks_room_id ON tasks (room_id);
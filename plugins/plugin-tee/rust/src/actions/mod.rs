@@ -0,0 +1,10 @@
+#![allow(missing_docs)]
+//! TEE actions module.
+
+pub mod remote_attestation;
+pub mod verify_attestation;
+
+pub use remote_attestation::{
+    RemoteAttestationAction, RemoteAttestationResult, REMOTE_ATTESTATION_EXAMPLES,
+};
+pub use verify_attestation::{VerifyAttestationAction, VerifyAttestationResult};
@@ -2,15 +2,48 @@
 
 use tracing::{debug, error, info};
 
-use crate::client::upload_attestation_quote;
 use crate::error::{Result, TeeError};
-use crate::providers::PhalaRemoteAttestationProvider;
-use crate::types::{RemoteAttestationMessage, RemoteAttestationMessageContent};
-use crate::utils::{current_timestamp_ms, hex_to_bytes};
+use crate::providers::{
+    AttestationBackend, AzureTdxVtpmProvider, IntelTrustAuthorityProvider,
+    PhalaRemoteAttestationProvider,
+};
+use crate::types::{
+    RemoteAttestationArtifact, RemoteAttestationBackend, RemoteAttestationMessage,
+    RemoteAttestationMessageContent, TdxQuoteHashAlgorithm,
+};
+use crate::utils::{bytes_to_hex, current_timestamp_ms, hash_with_algorithm};
+
+/// Environment variable holding the API key used for the Intel Trust Authority backend.
+const INTEL_TRUST_AUTHORITY_API_KEY_VAR: &str = "INTEL_TRUST_AUTHORITY_API_KEY";
+
+/// Construct the attestation backend `backend` selects.
+fn build_backend(
+    tee_mode: &str,
+    backend: RemoteAttestationBackend,
+) -> Result<Box<dyn AttestationBackend>> {
+    match backend {
+        RemoteAttestationBackend::Phala => {
+            Ok(Box::new(PhalaRemoteAttestationProvider::new(tee_mode)?))
+        }
+        RemoteAttestationBackend::IntelTrustAuthority => {
+            let api_key = std::env::var(INTEL_TRUST_AUTHORITY_API_KEY_VAR).map_err(|_| {
+                TeeError::config(format!(
+                    "{} must be set to use the intel_trust_authority backend",
+                    INTEL_TRUST_AUTHORITY_API_KEY_VAR
+                ))
+            })?;
+            Ok(Box::new(IntelTrustAuthorityProvider::new(
+                tee_mode, api_key, None,
+            )?))
+        }
+        RemoteAttestationBackend::AzureTdxVtpm => Ok(Box::new(AzureTdxVtpmProvider::new(None))),
+    }
+}
 
 /// Remote Attestation Action.
 ///
-/// Generates a remote attestation quote and uploads it to the proof service.
+/// Generates a remote attestation quote and delivers it through the selected
+/// [`RemoteAttestationBackend`] (Phala's public proof service by default).
 pub struct RemoteAttestationAction;
 
 /// Result of remote attestation action.
@@ -19,6 +52,21 @@ pub struct RemoteAttestationResult {
     pub success: bool,
     /// Result text (URL or error message).
     pub text: String,
+    /// The challenge that was folded into the attested report data, if the caller supplied one,
+    /// so the relying party can confirm it matches what it sent before trusting the quote.
+    pub nonce: Option<String>,
+    /// The original, pre-digest JSON of the attested message. `report_data` only carries a
+    /// digest of this (TDX/SGX's `report_data` field is a fixed 64 bytes and can't hold
+    /// arbitrary-length content), so a verifier must recompute `digest_algorithm` over this
+    /// value and confirm the result matches the quote's `report_data` before trusting it.
+    pub runtime_data: Option<String>,
+    /// Which digest algorithm was used to reduce `runtime_data` into the quote's `report_data`.
+    /// `None` only when attestation failed before a digest was computed.
+    pub digest_algorithm: Option<TdxQuoteHashAlgorithm>,
+    /// The proof artifact produced by the selected backend (a URL, a JWT, or raw evidence bytes
+    /// — see [`RemoteAttestationArtifact`]). `None` only when attestation failed before a
+    /// backend finished producing one.
+    pub artifact: Option<RemoteAttestationArtifact>,
 }
 
 impl RemoteAttestationAction {
@@ -50,22 +98,41 @@ impl RemoteAttestationAction {
     /// * `entity_id` - The entity ID from the message.
     /// * `room_id` - The room ID from the message.
     /// * `content` - The message content.
+    /// * `nonce` - Optional verifier-supplied challenge to fold into the report data, so the
+    ///   returned quote can't be replayed as proof of a different request.
+    /// * `digest_algorithm` - Digest used to reduce the attested message into the quote's
+    ///   fixed-size `report_data` field. Defaults to SHA-512 (TDX's `report_data` is 64 bytes,
+    ///   exactly one SHA-512 digest). A verifier must recompute this digest over the returned
+    ///   `runtime_data` and compare it against `report_data` inside the quote to confirm the
+    ///   binding; the quote's `report_data` field alone never contains enough information to
+    ///   recover the original message.
+    /// * `backend` - Which attestation backend generates and delivers the proof. Defaults to
+    ///   Phala. Intel Trust Authority additionally requires
+    ///   `INTEL_TRUST_AUTHORITY_API_KEY` to be set in the environment.
     ///
     /// # Returns
     ///
     /// The action result.
+    #[allow(clippy::too_many_arguments)]
     pub async fn handle(
         tee_mode: Option<&str>,
         agent_id: &str,
         entity_id: &str,
         room_id: &str,
         content: &str,
+        nonce: Option<&str>,
+        digest_algorithm: Option<TdxQuoteHashAlgorithm>,
+        backend: Option<RemoteAttestationBackend>,
     ) -> RemoteAttestationResult {
         let Some(mode) = tee_mode else {
             error!("TEE_MODE is not configured");
             return RemoteAttestationResult {
                 success: false,
                 text: "TEE_MODE is not configured. Cannot generate attestation.".to_string(),
+                nonce: nonce.map(String::from),
+                runtime_data: None,
+                digest_algorithm: None,
+                artifact: None,
             };
         };
 
@@ -78,6 +145,7 @@ impl RemoteAttestationAction {
                 room_id: room_id.to_string(),
                 content: content.to_string(),
             },
+            nonce: nonce.map(String::from),
         };
 
         debug!(
@@ -85,74 +153,138 @@ impl RemoteAttestationAction {
             serde_json::to_string(&attestation_message)
         );
 
-        // Generate attestation
-        let provider = match PhalaRemoteAttestationProvider::new(mode) {
-            Ok(p) => p,
+        // Build the selected attestation backend (generation + delivery)
+        let attestation_backend = match build_backend(mode, backend.unwrap_or_default()) {
+            Ok(b) => b,
             Err(e) => {
-                error!("Failed to create attestation provider: {}", e);
+                error!("Failed to create attestation backend: {}", e);
                 return RemoteAttestationResult {
                     success: false,
-                    text: format!("Failed to create attestation provider: {}", e),
+                    text: format!("Failed to create attestation backend: {}", e),
+                    nonce: nonce.map(String::from),
+                    runtime_data: None,
+                    digest_algorithm: None,
+                    artifact: None,
                 };
             }
         };
 
-        let report_data = match serde_json::to_string(&attestation_message) {
+        let runtime_data = match serde_json::to_string(&attestation_message) {
             Ok(s) => s,
             Err(e) => {
                 error!("Failed to serialize attestation message: {}", e);
                 return RemoteAttestationResult {
                     success: false,
                     text: format!("Failed to serialize attestation message: {}", e),
+                    nonce: nonce.map(String::from),
+                    runtime_data: None,
+                    digest_algorithm: None,
+                    artifact: None,
                 };
             }
         };
 
-        let attestation = match provider.generate_attestation(&report_data, None).await {
+        // TDX/SGX's report_data is a fixed 64 bytes, too small for arbitrary-length JSON, so we
+        // bind the message by digest instead and carry the original JSON alongside the quote as
+        // runtime data for a verifier to re-hash and compare.
+        let algorithm = digest_algorithm.unwrap_or(TdxQuoteHashAlgorithm::Sha512);
+        let report_data = bytes_to_hex(&hash_with_algorithm(runtime_data.as_bytes(), algorithm));
+
+        let artifact = match attestation_backend
+            .attest(&report_data, &runtime_data, Some(TdxQuoteHashAlgorithm::Raw))
+            .await
+        {
             Ok(a) => a,
             Err(e) => {
                 error!("Failed to generate attestation: {}", e);
                 return RemoteAttestationResult {
                     success: false,
                     text: format!("Failed to generate attestation: {}", e),
+                    nonce: nonce.map(String::from),
+                    runtime_data: Some(runtime_data),
+                    digest_algorithm: Some(algorithm),
+                    artifact: None,
                 };
             }
         };
 
-        // Upload to proof service
-        let attestation_data = match hex_to_bytes(&attestation.quote) {
-            Ok(d) => d,
-            Err(e) => {
-                error!("Failed to decode attestation quote: {}", e);
-                return RemoteAttestationResult {
-                    success: false,
-                    text: format!("Failed to decode attestation quote: {}", e),
-                };
+        let text = match &artifact {
+            RemoteAttestationArtifact::Url(url) => {
+                info!("Attestation uploaded: {}", url);
+                format!("Here's my 🧾 RA Quote 🫡\n{}", url)
             }
-        };
-
-        let upload_result = match upload_attestation_quote(&attestation_data).await {
-            Ok(r) => r,
-            Err(e) => {
-                error!("Failed to upload attestation: {}", e);
-                return RemoteAttestationResult {
-                    success: false,
-                    text: format!("Failed to upload attestation: {}", e),
-                };
+            RemoteAttestationArtifact::Jwt(jwt) => {
+                info!("Attestation appraised by Intel Trust Authority");
+                format!("Here's my 🧾 RA appraisal token 🫡\n{}", jwt)
+            }
+            RemoteAttestationArtifact::Raw(hex) => {
+                info!("Attestation evidence retrieved");
+                format!("Here's my 🧾 RA evidence 🫡\n{}", hex)
             }
         };
 
-        let proof_url = format!("https://proof.t16z.com/reports/{}", upload_result.checksum);
-
-        info!("Attestation uploaded: {}", proof_url);
-
         RemoteAttestationResult {
             success: true,
-            text: format!("Here's my 🧾 RA Quote 🫡\n{}", proof_url),
+            text,
+            nonce: nonce.map(String::from),
+            runtime_data: Some(runtime_data),
+            digest_algorithm: Some(algorithm),
+            artifact: Some(artifact),
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A previously captured quote must not be replayable as proof of a different request: two
+    /// otherwise-identical attestation messages that differ only by their verifier-supplied nonce
+    /// must hash to different `report_data` digests.
+    #[test]
+    fn test_nonce_changes_report_data_digest() {
+        let message = RemoteAttestationMessageContent {
+            entity_id: "entity-1".to_string(),
+            room_id: "room-1".to_string(),
+            content: "hello".to_string(),
+        };
+
+        let with_nonce_a = RemoteAttestationMessage {
+            agent_id: "agent-1".to_string(),
+            timestamp: 1,
+            message: message.clone(),
+            nonce: Some("nonce-a".to_string()),
+        };
+        let with_nonce_b = RemoteAttestationMessage {
+            agent_id: "agent-1".to_string(),
+            timestamp: 1,
+            message: message.clone(),
+            nonce: Some("nonce-b".to_string()),
+        };
+        let without_nonce = RemoteAttestationMessage {
+            agent_id: "agent-1".to_string(),
+            timestamp: 1,
+            message,
+            nonce: None,
+        };
+
+        let digest = |m: &RemoteAttestationMessage| {
+            let runtime_data = serde_json::to_string(m).unwrap();
+            bytes_to_hex(&hash_with_algorithm(
+                runtime_data.as_bytes(),
+                TdxQuoteHashAlgorithm::Sha512,
+            ))
+        };
+
+        let digest_a = digest(&with_nonce_a);
+        let digest_b = digest(&with_nonce_b);
+        let digest_none = digest(&without_nonce);
+
+        assert_ne!(digest_a, digest_b);
+        assert_ne!(digest_a, digest_none);
+    }
+}
+
 /// Example conversations for the remote attestation action.
 pub const REMOTE_ATTESTATION_EXAMPLES: &[&[(&str, &str)]] = &[
     &[
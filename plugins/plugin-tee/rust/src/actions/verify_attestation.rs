@@ -0,0 +1,135 @@
+//! Verify Attestation Action for TEE.
+
+use tracing::{error, info};
+
+use crate::types::{AttestationClaims, RemoteAttestationArtifact, TdxQuoteHashAlgorithm};
+use crate::verification::{verify_jwt, verify_quote_report_data};
+
+/// Verify Attestation Action.
+///
+/// Locally validates a previously-produced [`RemoteAttestationArtifact`] rather than trusting it
+/// at face value: a JWT is checked against its issuer's JWKS, a raw quote is checked by
+/// confirming its embedded report data matches the expected digest.
+pub struct VerifyAttestationAction;
+
+/// Result of the verify attestation action.
+#[derive(Debug, Clone)]
+pub struct VerifyAttestationResult {
+    /// Whether verification succeeded.
+    pub success: bool,
+    /// Human-readable result text.
+    pub text: String,
+    /// Claims extracted from the artifact. Populated only when verification succeeded.
+    pub claims: Option<AttestationClaims>,
+}
+
+impl VerifyAttestationAction {
+    /// Action name.
+    pub const NAME: &'static str = "VERIFY_ATTESTATION";
+
+    /// Action description.
+    pub const DESCRIPTION: &'static str =
+        "Locally verify a previously-generated remote attestation artifact (JWT or raw quote) before trusting it";
+
+    /// Verify that `artifact` is bound to `expected_runtime_data`.
+    ///
+    /// # Arguments
+    ///
+    /// * `artifact` - The attestation artifact to verify.
+    /// * `expected_runtime_data` - The runtime data (e.g. the original attested message JSON
+    ///   `RemoteAttestationAction` returned) the artifact is expected to be bound to.
+    /// * `digest_algorithm` - The digest algorithm used when the quote's report data was
+    ///   computed. Only consulted for a raw-quote artifact; a JWT carries its own binding.
+    /// * `jwks_url` - The issuer's JWKS endpoint, required when `artifact` is a
+    ///   [`RemoteAttestationArtifact::Jwt`].
+    ///
+    /// # Returns
+    ///
+    /// The verification result, including any claims this crate recognized.
+    ///
+    /// Verifying a [`RemoteAttestationArtifact::Url`] is not yet supported: the quote it points
+    /// to would first need to be fetched from the proof service, and that response format is not
+    /// modeled here. Callers holding a `Url` artifact should verify it out-of-band for now.
+    pub async fn handle(
+        artifact: &RemoteAttestationArtifact,
+        expected_runtime_data: &str,
+        digest_algorithm: Option<TdxQuoteHashAlgorithm>,
+        jwks_url: Option<&str>,
+    ) -> VerifyAttestationResult {
+        match artifact {
+            RemoteAttestationArtifact::Jwt(token) => {
+                let Some(jwks_url) = jwks_url else {
+                    error!("JWKS URL is required to verify a JWT attestation artifact");
+                    return VerifyAttestationResult {
+                        success: false,
+                        text: "JWKS URL is required to verify a JWT attestation artifact"
+                            .to_string(),
+                        claims: None,
+                    };
+                };
+
+                match verify_jwt(token, jwks_url, expected_runtime_data).await {
+                    Ok(claims) => {
+                        info!("JWT attestation verified successfully");
+                        VerifyAttestationResult {
+                            success: true,
+                            text: "Attestation token verified.".to_string(),
+                            claims: Some(claims),
+                        }
+                    }
+                    Err(e) => {
+                        error!("JWT attestation verification failed: {}", e);
+                        VerifyAttestationResult {
+                            success: false,
+                            text: format!("Attestation token verification failed: {}", e),
+                            claims: None,
+                        }
+                    }
+                }
+            }
+            RemoteAttestationArtifact::Raw(quote_hex) => {
+                let algorithm = digest_algorithm.unwrap_or(TdxQuoteHashAlgorithm::Sha512);
+                match verify_quote_report_data(quote_hex, expected_runtime_data, algorithm) {
+                    Ok(true) => {
+                        info!("Quote report_data binding verified successfully");
+                        VerifyAttestationResult {
+                            success: true,
+                            text: "Quote report_data matches expected runtime data.".to_string(),
+                            claims: Some(AttestationClaims {
+                                runtime_data: Some(expected_runtime_data.to_string()),
+                                measurements: None,
+                            }),
+                        }
+                    }
+                    Ok(false) => {
+                        error!("Quote report_data does not match expected runtime data");
+                        VerifyAttestationResult {
+                            success: false,
+                            text: "Quote report_data does not match expected runtime data."
+                                .to_string(),
+                            claims: None,
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to verify quote: {}", e);
+                        VerifyAttestationResult {
+                            success: false,
+                            text: format!("Failed to verify quote: {}", e),
+                            claims: None,
+                        }
+                    }
+                }
+            }
+            RemoteAttestationArtifact::Url(_) => {
+                error!("Verifying a Url attestation artifact is not yet supported");
+                VerifyAttestationResult {
+                    success: false,
+                    text: "Verifying a Url attestation artifact requires fetching the quote \
+                        from the proof service first, which is not yet supported."
+                        .to_string(),
+                    claims: None,
+                }
+            }
+        }
+    }
+}
@@ -20,6 +20,10 @@ pub enum TeeError {
     #[error("Invalid TEE_VENDOR: {0}. Must be one of: phala")]
     InvalidVendor(String),
 
+    /// Invalid remote attestation backend.
+    #[error("Invalid attestation backend: {0}. Must be one of: phala, intel_trust_authority, azure_tdx_vtpm")]
+    InvalidBackend(String),
+
     /// Remote attestation error.
     #[error("Failed to generate attestation: {0}")]
     Attestation(String),
@@ -40,28 +40,41 @@ pub mod actions;
 pub mod client;
 pub mod error;
 pub mod providers;
+pub mod ra_tls;
 pub mod services;
 pub mod types;
 pub mod utils;
 pub mod vendors;
+pub mod verification;
 
 // Re-export main types
+pub use actions::{
+    RemoteAttestationAction, RemoteAttestationResult, VerifyAttestationAction,
+    VerifyAttestationResult, REMOTE_ATTESTATION_EXAMPLES,
+};
 pub use client::{upload_attestation_quote, TeeClient};
 pub use error::{Result, TeeError};
 pub use providers::{
-    DeriveKeyProvider, PhalaDeriveKeyProvider, PhalaRemoteAttestationProvider,
-    RemoteAttestationProvider,
+    AttestationBackend, AzureTdxVtpmProvider, DeriveKeyProvider, IntelTrustAuthorityProvider,
+    PhalaDeriveKeyProvider, PhalaRemoteAttestationProvider, RemoteAttestationProvider,
+};
+pub use ra_tls::{
+    generate_ra_tls_certificate, verify_ra_tls_certificate, RaTlsCertificate,
+    RaTlsServerCertVerifier,
 };
 pub use services::TEEService;
 pub use types::{
-    DeriveKeyAttestationData, DeriveKeyResult, EcdsaKeypairResult, Ed25519KeypairResult,
+    AttestationClaims, DeriveKeyAttestationData, DeriveKeyResult, EcdsaKeypairResult,
+    Ed25519KeypairResult, RemoteAttestationArtifact, RemoteAttestationBackend,
     RemoteAttestationMessage, RemoteAttestationQuote, TdxQuoteHashAlgorithm, TeeMode,
     TeeProviderResult, TeeServiceConfig, TeeType, TeeVendor,
 };
 pub use utils::{
-    bytes_to_hex, calculate_keccak256, calculate_sha256, get_tee_endpoint, hex_to_bytes,
+    bytes_to_hex, calculate_keccak256, calculate_sha256, calculate_sha384, calculate_sha512,
+    get_tee_endpoint, hash_with_algorithm, hex_to_bytes,
 };
 pub use vendors::{get_vendor, PhalaVendor, TeeVendorInterface, TeeVendorNames};
+pub use verification::{verify_jwt, verify_quote_report_data};
 
 /// Plugin name.
 pub const PLUGIN_NAME: &str = "tee";
@@ -3,7 +3,9 @@
 use async_trait::async_trait;
 
 use crate::error::Result;
-use crate::types::{DeriveKeyResult, RemoteAttestationQuote, TdxQuoteHashAlgorithm};
+use crate::types::{
+    DeriveKeyResult, RemoteAttestationArtifact, RemoteAttestationQuote, TdxQuoteHashAlgorithm,
+};
 
 /// Trait for deriving keys from the TEE.
 ///
@@ -45,4 +47,34 @@ pub trait RemoteAttestationProvider: Send + Sync {
     ) -> Result<RemoteAttestationQuote>;
 }
 
+/// Trait for a pluggable attestation backend: takes report data through to a finished proof
+/// artifact, covering both quote generation and (for backends that relay off-box) delivery to a
+/// verifier. This is the extension point `RemoteAttestationAction` selects between via
+/// [`RemoteAttestationBackend`](crate::types::RemoteAttestationBackend) rather than hardcoding a
+/// single provider and upload target.
+#[async_trait]
+pub trait AttestationBackend: Send + Sync {
+    /// Attest `report_data` (already digested per `hash_algorithm`, if `Some`) and return the
+    /// resulting proof artifact.
+    ///
+    /// # Arguments
+    ///
+    /// * `report_data` - The data to bind into the quote's report data.
+    /// * `runtime_data` - The original, pre-digest message `report_data` was derived from.
+    ///   Backends that relay evidence to a server-side verifier (e.g. Intel Trust Authority) need
+    ///   this to submit alongside the quote so the verifier can recompute the binding itself;
+    ///   backends that only produce/upload raw quotes can ignore it.
+    /// * `hash_algorithm` - Optional hash algorithm for the quote.
+    ///
+    /// # Returns
+    ///
+    /// The proof artifact produced by this backend.
+    async fn attest(
+        &self,
+        report_data: &str,
+        runtime_data: &str,
+        hash_algorithm: Option<TdxQuoteHashAlgorithm>,
+    ) -> Result<RemoteAttestationArtifact>;
+}
+
 
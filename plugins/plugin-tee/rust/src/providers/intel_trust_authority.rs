@@ -0,0 +1,157 @@
+//! Intel Trust Authority remote attestation backend.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::error::{Result, TeeError};
+use crate::providers::base::{AttestationBackend, RemoteAttestationProvider};
+use crate::providers::remote_attestation::PhalaRemoteAttestationProvider;
+use crate::types::{RemoteAttestationArtifact, TdxQuoteHashAlgorithm};
+use crate::utils::hex_to_bytes;
+
+/// Default Intel Trust Authority appraisal endpoint.
+const DEFAULT_BASE_URL: &str = "https://api.trustauthority.intel.com";
+
+/// Intel Trust Authority attestation backend.
+///
+/// Generates a TDX quote locally (via the same quote-generation path as
+/// [`PhalaRemoteAttestationProvider`]) then submits it, alongside the base64-encoded runtime
+/// data it was bound to, to Intel Trust Authority's appraisal API over HTTPS. A successful
+/// appraisal comes back as a signed JWT rather than a public proof URL.
+pub struct IntelTrustAuthorityProvider {
+    quote_provider: PhalaRemoteAttestationProvider,
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl IntelTrustAuthorityProvider {
+    /// Create a new Intel Trust Authority provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `tee_mode` - The TEE operation mode (LOCAL, DOCKER, PRODUCTION), used to generate the
+    ///   underlying quote.
+    /// * `api_key` - API key issued by Intel Trust Authority for appraisal requests.
+    /// * `base_url` - Override for the appraisal endpoint (defaults to the public ITA API).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `tee_mode` is invalid.
+    pub fn new(
+        tee_mode: &str,
+        api_key: impl Into<String>,
+        base_url: Option<String>,
+    ) -> Result<Self> {
+        Ok(Self {
+            quote_provider: PhalaRemoteAttestationProvider::new(tee_mode)?,
+            client: reqwest::Client::new(),
+            base_url: base_url.unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+            api_key: api_key.into(),
+        })
+    }
+}
+
+/// Request body for Intel Trust Authority's quote appraisal endpoint.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppraisalRequest {
+    /// Base64-encoded TDX quote.
+    quote: String,
+    /// Base64-encoded runtime data the quote's report data was bound to.
+    runtime_data: String,
+}
+
+/// Response body from Intel Trust Authority's quote appraisal endpoint.
+#[derive(Debug, Deserialize)]
+struct AppraisalResponse {
+    /// The signed JWT attesting the appraisal result.
+    token: String,
+}
+
+#[async_trait]
+impl AttestationBackend for IntelTrustAuthorityProvider {
+    async fn attest(
+        &self,
+        report_data: &str,
+        runtime_data: &str,
+        hash_algorithm: Option<TdxQuoteHashAlgorithm>,
+    ) -> Result<RemoteAttestationArtifact> {
+        let quote = self
+            .quote_provider
+            .generate_attestation(report_data, hash_algorithm)
+            .await?;
+        let quote_bytes = hex_to_bytes(&quote.quote)?;
+        let request = build_appraisal_request(&quote_bytes, runtime_data);
+
+        debug!("Submitting quote to Intel Trust Authority for appraisal");
+
+        let response = self
+            .client
+            .post(format!("{}/appraisal/v2/attest", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(TeeError::attestation(format!(
+                "Intel Trust Authority appraisal failed: {} - {}",
+                status, body
+            )));
+        }
+
+        let result: AppraisalResponse = response.json().await?;
+        info!("Intel Trust Authority appraisal succeeded");
+
+        Ok(RemoteAttestationArtifact::Jwt(result.token))
+    }
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+/// Build the appraisal request body. Split out from [`IntelTrustAuthorityProvider::attest`] so
+/// the base64-encoding of `runtime_data` — the original pre-digest message, not the `report_data`
+/// digest it was reduced to — can be exercised without a live quote or network call.
+fn build_appraisal_request(quote_bytes: &[u8], runtime_data: &str) -> AppraisalRequest {
+    AppraisalRequest {
+        quote: base64_encode(quote_bytes),
+        runtime_data: base64_encode(runtime_data.as_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_appraisal_request_encodes_original_runtime_data_not_its_digest() {
+        let runtime_data = r#"{"agentId":"agent-1","timestamp":1}"#;
+        let digest = crate::utils::hash_with_algorithm(
+            runtime_data.as_bytes(),
+            TdxQuoteHashAlgorithm::Sha512,
+        );
+        let report_data_hex = crate::utils::bytes_to_hex(&digest);
+
+        let request = build_appraisal_request(b"quote-bytes", runtime_data);
+
+        assert_eq!(request.runtime_data, base64_encode(runtime_data.as_bytes()));
+        assert_ne!(
+            request.runtime_data,
+            base64_encode(report_data_hex.as_bytes()),
+            "runtime_data must be the original message, not the report_data digest"
+        );
+    }
+
+    #[test]
+    fn test_build_appraisal_request_encodes_quote_bytes() {
+        let request = build_appraisal_request(&[1, 2, 3], "message");
+        assert_eq!(request.quote, base64_encode(&[1, 2, 3]));
+    }
+}
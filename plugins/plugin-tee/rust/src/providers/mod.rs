@@ -1,12 +1,16 @@
 #![allow(missing_docs)]
 //! TEE Providers module.
 
+pub mod azure_tdx_vtpm;
 pub mod base;
 pub mod derive_key;
+pub mod intel_trust_authority;
 pub mod remote_attestation;
 
-pub use base::{DeriveKeyProvider, RemoteAttestationProvider};
+pub use azure_tdx_vtpm::AzureTdxVtpmProvider;
+pub use base::{AttestationBackend, DeriveKeyProvider, RemoteAttestationProvider};
 pub use derive_key::PhalaDeriveKeyProvider;
+pub use intel_trust_authority::IntelTrustAuthorityProvider;
 pub use remote_attestation::PhalaRemoteAttestationProvider;
 
 
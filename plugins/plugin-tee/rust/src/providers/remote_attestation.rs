@@ -3,11 +3,11 @@
 use async_trait::async_trait;
 use tracing::{debug, info};
 
-use crate::client::TeeClient;
+use crate::client::{upload_attestation_quote, TeeClient};
 use crate::error::{Result, TeeError};
-use crate::providers::base::RemoteAttestationProvider;
-use crate::types::{RemoteAttestationQuote, TdxQuoteHashAlgorithm};
-use crate::utils::{current_timestamp_ms, get_tee_endpoint};
+use crate::providers::base::{AttestationBackend, RemoteAttestationProvider};
+use crate::types::{RemoteAttestationArtifact, RemoteAttestationQuote, TdxQuoteHashAlgorithm};
+use crate::utils::{current_timestamp_ms, get_tee_endpoint, hex_to_bytes};
 
 /// Phala Network Remote Attestation Provider.
 ///
@@ -91,3 +91,24 @@ impl RemoteAttestationProvider for PhalaRemoteAttestationProvider {
     }
 }
 
+#[async_trait]
+impl AttestationBackend for PhalaRemoteAttestationProvider {
+    async fn attest(
+        &self,
+        report_data: &str,
+        _runtime_data: &str,
+        hash_algorithm: Option<TdxQuoteHashAlgorithm>,
+    ) -> Result<RemoteAttestationArtifact> {
+        let quote = self.generate_attestation(report_data, hash_algorithm).await?;
+        let quote_bytes = hex_to_bytes(&quote.quote)?;
+        let upload_result = upload_attestation_quote(&quote_bytes)
+            .await
+            .map_err(|e| TeeError::network(e.to_string()))?;
+
+        Ok(RemoteAttestationArtifact::Url(format!(
+            "https://proof.t16z.com/reports/{}",
+            upload_result.checksum
+        )))
+    }
+}
+
@@ -0,0 +1,282 @@
+//! RA-TLS: remote-attestation-bound TLS certificates.
+//!
+//! Borrows the approach pioneered by Teaclave's RA-TLS: a self-signed X.509 certificate carries
+//! a fresh TEE attestation quote in a custom extension, with the quote's report data bound to
+//! the certificate's own public key. A peer validating the certificate during the TLS handshake
+//! recomputes that binding and checks the quote, proving the certificate's private key lives
+//! inside a TEE without a separate out-of-band attestation round trip. Trust in the certificate
+//! comes entirely from the embedded quote, not from a certificate authority, so these
+//! certificates are always self-signed.
+
+use std::sync::Arc;
+
+use rcgen::{CertificateParams, CustomExtension, DistinguishedName, DnType, KeyPair};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, Error as RustlsError, SignatureScheme};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::error::{Result, TeeError};
+use crate::providers::RemoteAttestationProvider;
+use crate::types::TdxQuoteHashAlgorithm;
+use crate::utils::{bytes_to_hex, hash_with_algorithm, hex_to_bytes};
+use crate::verification::extract_report_data;
+
+/// ASN.1 OID under which the attestation quote is embedded as a custom X.509v3 extension.
+/// Arbitrary but stable within this crate, following the same private-OID convention Teaclave
+/// uses for its own RA-TLS extension.
+const RA_TLS_QUOTE_OID: &[u64] = &[1, 2, 840, 113741, 1, 337, 1];
+
+/// Dotted-string form of [`RA_TLS_QUOTE_OID`], used to match extensions parsed back out of a
+/// certificate by `x509-parser`.
+const RA_TLS_QUOTE_OID_STR: &str = "1.2.840.113741.1.337.1";
+
+/// A self-signed certificate and private key produced by [`generate_ra_tls_certificate`], both
+/// DER-encoded and ready to load into a `rustls` config.
+pub struct RaTlsCertificate {
+    /// DER-encoded X.509 certificate, with the attestation quote embedded as a custom extension.
+    pub cert_der: Vec<u8>,
+    /// DER-encoded PKCS#8 private key matching `cert_der`'s public key.
+    pub key_der: Vec<u8>,
+}
+
+/// Generate a self-signed RA-TLS certificate: a fresh keypair, with a TEE attestation quote bound
+/// (via a report-data digest of the public key) embedded as a custom X.509v3 extension.
+///
+/// # Arguments
+///
+/// * `provider` - The attestation provider to generate the quote through.
+/// * `subject_name` - Common name for the self-signed certificate.
+/// * `digest_algorithm` - Digest used to bind the public key into the quote's report data.
+///   Defaults to SHA-512, matching `RemoteAttestationAction`'s default.
+///
+/// # Returns
+///
+/// The certificate and its private key, both DER-encoded.
+pub async fn generate_ra_tls_certificate(
+    provider: &dyn RemoteAttestationProvider,
+    subject_name: &str,
+    digest_algorithm: Option<TdxQuoteHashAlgorithm>,
+) -> Result<RaTlsCertificate> {
+    let key_pair = KeyPair::generate().map_err(|e| TeeError::crypto(e.to_string()))?;
+
+    let mut params =
+        CertificateParams::new(Vec::new()).map_err(|e| TeeError::crypto(e.to_string()))?;
+    let mut dn = DistinguishedName::new();
+    dn.push(DnType::CommonName, subject_name);
+    params.distinguished_name = dn;
+
+    let algorithm = digest_algorithm.unwrap_or(TdxQuoteHashAlgorithm::Sha512);
+    let report_data = bytes_to_hex(&hash_with_algorithm(&key_pair.public_key_der(), algorithm));
+
+    let quote = provider
+        .generate_attestation(&report_data, Some(TdxQuoteHashAlgorithm::Raw))
+        .await?;
+    let quote_bytes = hex_to_bytes(&quote.quote)?;
+
+    params
+        .custom_extensions
+        .push(CustomExtension::from_oid_content(
+            RA_TLS_QUOTE_OID,
+            quote_bytes,
+        ));
+
+    let cert = params
+        .self_signed(&key_pair)
+        .map_err(|e| TeeError::crypto(e.to_string()))?;
+
+    Ok(RaTlsCertificate {
+        cert_der: cert.der().to_vec(),
+        key_der: key_pair.serialize_der(),
+    })
+}
+
+/// Extract the attestation quote embedded in an RA-TLS certificate's custom extension and
+/// confirm its report data matches the digest of the certificate's own public key.
+///
+/// # Arguments
+///
+/// * `cert_der` - DER-encoded X.509 certificate to check.
+/// * `digest_algorithm` - Digest algorithm the certificate's public key was bound with. Must
+///   match what the certificate was generated with.
+///
+/// # Returns
+///
+/// The raw quote bytes, once the report-data binding has been confirmed, so the caller can run
+/// full collateral verification on them if needed.
+pub fn verify_ra_tls_certificate(
+    cert_der: &[u8],
+    digest_algorithm: TdxQuoteHashAlgorithm,
+) -> Result<Vec<u8>> {
+    let (_, cert) = X509Certificate::from_der(cert_der)
+        .map_err(|e| TeeError::attestation(format!("Failed to parse RA-TLS certificate: {}", e)))?;
+
+    let quote_bytes = cert
+        .extensions()
+        .iter()
+        .find(|ext| ext.oid.to_id_string() == RA_TLS_QUOTE_OID_STR)
+        .map(|ext| ext.value.to_vec())
+        .ok_or_else(|| TeeError::attestation("Certificate has no RA-TLS quote extension"))?;
+
+    let public_key_der = cert.public_key().raw;
+    let expected_report_data = hash_with_algorithm(public_key_der, digest_algorithm);
+    let embedded_report_data = extract_report_data(&quote_bytes)?;
+
+    if embedded_report_data != expected_report_data.as_slice() {
+        return Err(TeeError::attestation(
+            "RA-TLS certificate's quote report_data does not match its public key",
+        ));
+    }
+
+    Ok(quote_bytes)
+}
+
+/// A `rustls` server-certificate verifier that trusts a peer's certificate purely on the strength
+/// of its embedded RA-TLS quote, bypassing normal certificate-authority chain validation (these
+/// certificates are always self-signed; there is no CA to chain to). Handshake signature
+/// verification is still delegated to the installed [`CryptoProvider`], so the TLS handshake
+/// itself remains cryptographically sound — only the "do I trust this certificate" decision is
+/// replaced.
+#[derive(Debug)]
+pub struct RaTlsServerCertVerifier {
+    digest_algorithm: TdxQuoteHashAlgorithm,
+    crypto_provider: Arc<CryptoProvider>,
+}
+
+impl RaTlsServerCertVerifier {
+    /// Create a new RA-TLS server certificate verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `digest_algorithm` - Digest algorithm peer certificates are expected to bind their
+    ///   public key with.
+    /// * `crypto_provider` - The `rustls` crypto provider to delegate signature verification to.
+    pub fn new(digest_algorithm: TdxQuoteHashAlgorithm, crypto_provider: Arc<CryptoProvider>) -> Self {
+        Self { digest_algorithm, crypto_provider }
+    }
+}
+
+impl ServerCertVerifier for RaTlsServerCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, RustlsError> {
+        verify_ra_tls_certificate(end_entity.as_ref(), self.digest_algorithm)
+            .map_err(|e| RustlsError::General(e.to_string()))?;
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, RustlsError> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.crypto_provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, RustlsError> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.crypto_provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.crypto_provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RemoteAttestationQuote;
+    use async_trait::async_trait;
+
+    /// A fake provider that returns a synthetic quote whose `REPORTDATA` is exactly
+    /// `report_data` (hex-decoded), so tests can exercise the embed/extract round trip without a
+    /// real TEE.
+    struct FakeAttestationProvider;
+
+    #[async_trait]
+    impl RemoteAttestationProvider for FakeAttestationProvider {
+        async fn generate_attestation(
+            &self,
+            report_data: &str,
+            _hash_algorithm: Option<TdxQuoteHashAlgorithm>,
+        ) -> Result<RemoteAttestationQuote> {
+            let report_data_bytes = hex_to_bytes(report_data)?;
+            let offset = crate::verification::TD_QUOTE_REPORT_DATA_OFFSET;
+            let mut quote = vec![0u8; offset + report_data_bytes.len()];
+            quote[offset..].copy_from_slice(&report_data_bytes);
+            Ok(RemoteAttestationQuote {
+                quote: bytes_to_hex(&quote),
+                timestamp: 0,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_and_verify_ra_tls_certificate_round_trip() {
+        let cert = generate_ra_tls_certificate(
+            &FakeAttestationProvider,
+            "test-subject",
+            Some(TdxQuoteHashAlgorithm::Sha256),
+        )
+        .await
+        .unwrap();
+
+        let quote_bytes =
+            verify_ra_tls_certificate(&cert.cert_der, TdxQuoteHashAlgorithm::Sha256).unwrap();
+        assert!(!quote_bytes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_verify_ra_tls_certificate_rejects_digest_algorithm_mismatch() {
+        let cert = generate_ra_tls_certificate(
+            &FakeAttestationProvider,
+            "test-subject",
+            Some(TdxQuoteHashAlgorithm::Sha256),
+        )
+        .await
+        .unwrap();
+
+        // The cert's quote was bound with SHA-256; verifying against SHA-512 must fail since the
+        // expected report data digest no longer matches.
+        let result = verify_ra_tls_certificate(&cert.cert_der, TdxQuoteHashAlgorithm::Sha512);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_verify_ra_tls_certificate_rejects_cert_without_quote_extension() {
+        let key_pair = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::new()).unwrap();
+        let mut dn = DistinguishedName::new();
+        dn.push(DnType::CommonName, "no-quote");
+        params.distinguished_name = dn;
+        let cert = params.self_signed(&key_pair).unwrap();
+
+        let result =
+            verify_ra_tls_certificate(cert.der(), TdxQuoteHashAlgorithm::Sha256);
+        assert!(result.is_err());
+    }
+}
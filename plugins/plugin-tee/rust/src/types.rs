@@ -94,6 +94,64 @@ pub enum TdxQuoteHashAlgorithm {
     Raw,
 }
 
+/// Which attestation backend generates and delivers the proof for
+/// [`RemoteAttestationAction`](crate::actions::RemoteAttestationAction). Each backend produces a
+/// differently shaped proof artifact (see [`RemoteAttestationArtifact`]): Phala uploads the raw
+/// quote to its public proof service, Intel Trust Authority appraises the quote and returns a
+/// signed JWT, and Azure's vTPM path returns the raw attestation token for the caller's own
+/// verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteAttestationBackend {
+    /// Phala Network's public proof service (`proof.t16z.com`).
+    Phala,
+    /// Intel Trust Authority appraisal API.
+    IntelTrustAuthority,
+    /// Azure confidential-VM vTPM attestation.
+    AzureTdxVtpm,
+}
+
+impl RemoteAttestationBackend {
+    /// Get the string representation of the backend.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Phala => "phala",
+            Self::IntelTrustAuthority => "intel_trust_authority",
+            Self::AzureTdxVtpm => "azure_tdx_vtpm",
+        }
+    }
+
+    /// Parse a backend from string.
+    pub fn parse(s: &str) -> Result<Self, TeeError> {
+        match s.to_lowercase().as_str() {
+            "phala" => Ok(Self::Phala),
+            "intel_trust_authority" | "ita" => Ok(Self::IntelTrustAuthority),
+            "azure_tdx_vtpm" | "azure" => Ok(Self::AzureTdxVtpm),
+            _ => Err(TeeError::InvalidBackend(s.to_string())),
+        }
+    }
+}
+
+impl Default for RemoteAttestationBackend {
+    fn default() -> Self {
+        Self::Phala
+    }
+}
+
+/// The proof artifact a [`RemoteAttestationBackend`] returns once a quote has been generated and
+/// (if that backend relays off-box for appraisal) delivered. Exactly one variant is populated,
+/// chosen by which backend produced it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RemoteAttestationArtifact {
+    /// A public URL where the raw quote can be independently verified (Phala).
+    Url(String),
+    /// A signed JWT asserting the appraisal result (Intel Trust Authority).
+    Jwt(String),
+    /// Raw token bytes returned by the backend, hex-encoded for transport (Azure vTPM).
+    Raw(String),
+}
+
 /// Remote attestation quote.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RemoteAttestationQuote {
@@ -103,6 +161,21 @@ pub struct RemoteAttestationQuote {
     pub timestamp: u64,
 }
 
+/// Claims extracted from a [`RemoteAttestationArtifact`] that
+/// [`VerifyAttestationAction`](crate::actions::VerifyAttestationAction) was able to validate.
+/// Fields are populated only for what the artifact kind being verified actually carries; a quote
+/// verification, for example, never populates `measurements`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AttestationClaims {
+    /// The runtime data the artifact was confirmed to be bound to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_data: Option<String>,
+    /// Measurement register values (e.g. RTMRs) asserted by the artifact, keyed by register
+    /// name, if the artifact carried any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub measurements: Option<std::collections::HashMap<String, String>>,
+}
+
 /// Data included in derive key attestation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -138,6 +211,12 @@ pub struct RemoteAttestationMessage {
     pub timestamp: u64,
     /// Message details.
     pub message: RemoteAttestationMessageContent,
+    /// Verifier-supplied challenge folded into the report data, binding this quote to a single
+    /// verification request so a previously captured quote can't be replayed as fresh proof
+    /// (mirrors Intel Trust Authority's `nonce`-bound appraisal requests). `None` when the
+    /// caller didn't supply one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
 }
 
 /// Result of key derivation.
@@ -206,3 +285,42 @@ pub struct TeeProviderResult {
 
 use crate::error::TeeError;
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_attestation_message_omits_nonce_field_when_absent() {
+        let message = RemoteAttestationMessage {
+            agent_id: "agent-1".to_string(),
+            timestamp: 1,
+            message: RemoteAttestationMessageContent {
+                entity_id: "e".to_string(),
+                room_id: "r".to_string(),
+                content: "c".to_string(),
+            },
+            nonce: None,
+        };
+
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(!json.contains("nonce"));
+    }
+
+    #[test]
+    fn test_remote_attestation_message_includes_nonce_when_present() {
+        let message = RemoteAttestationMessage {
+            agent_id: "agent-1".to_string(),
+            timestamp: 1,
+            message: RemoteAttestationMessageContent {
+                entity_id: "e".to_string(),
+                room_id: "r".to_string(),
+                content: "c".to_string(),
+            },
+            nonce: Some("challenge-123".to_string()),
+        };
+
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(json.contains("\"nonce\":\"challenge-123\""));
+    }
+}
+
@@ -1,7 +1,8 @@
 #![allow(missing_docs)]
 
 use crate::error::{Result, TeeError};
-use sha2::{Digest, Sha256};
+use crate::types::TdxQuoteHashAlgorithm;
+use sha2::{Digest, Sha256, Sha384, Sha512};
 
 pub fn hex_to_bytes(hex_str: &str) -> Result<Vec<u8>> {
     let hex_str = hex_str.trim().trim_start_matches("0x");
@@ -28,6 +29,18 @@ pub fn calculate_sha256(data: &[u8]) -> Vec<u8> {
     hasher.finalize().to_vec()
 }
 
+pub fn calculate_sha384(data: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha384::new();
+    hasher.update(data);
+    hasher.finalize().to_vec()
+}
+
+pub fn calculate_sha512(data: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha512::new();
+    hasher.update(data);
+    hasher.finalize().to_vec()
+}
+
 pub fn calculate_keccak256(data: &[u8]) -> Vec<u8> {
     use sha3::Keccak256;
     let mut hasher = Keccak256::new();
@@ -35,6 +48,18 @@ pub fn calculate_keccak256(data: &[u8]) -> Vec<u8> {
     hasher.finalize().to_vec()
 }
 
+/// Digest `data` per `algorithm`, for binding oversized content into a TDX/SGX quote's
+/// fixed-size `report_data` field. `Raw` passes `data` through unchanged, for callers that have
+/// already hashed (or otherwise size-bounded) their own payload.
+pub fn hash_with_algorithm(data: &[u8], algorithm: TdxQuoteHashAlgorithm) -> Vec<u8> {
+    match algorithm {
+        TdxQuoteHashAlgorithm::Sha256 => calculate_sha256(data),
+        TdxQuoteHashAlgorithm::Sha384 => calculate_sha384(data),
+        TdxQuoteHashAlgorithm::Sha512 => calculate_sha512(data),
+        TdxQuoteHashAlgorithm::Raw => data.to_vec(),
+    }
+}
+
 pub fn get_tee_endpoint(mode: &str) -> Result<Option<String>> {
     match mode.to_uppercase().as_str() {
         "LOCAL" => Ok(Some("http://localhost:8090".to_string())),
@@ -95,6 +120,38 @@ mod tests {
         assert_eq!(result.len(), 32);
     }
 
+    #[test]
+    fn test_calculate_sha384() {
+        let result = calculate_sha384(b"hello");
+        assert_eq!(result.len(), 48);
+    }
+
+    #[test]
+    fn test_calculate_sha512() {
+        let result = calculate_sha512(b"hello");
+        assert_eq!(result.len(), 64);
+    }
+
+    #[test]
+    fn test_hash_with_algorithm() {
+        assert_eq!(
+            hash_with_algorithm(b"hello", TdxQuoteHashAlgorithm::Sha256).len(),
+            32
+        );
+        assert_eq!(
+            hash_with_algorithm(b"hello", TdxQuoteHashAlgorithm::Sha384).len(),
+            48
+        );
+        assert_eq!(
+            hash_with_algorithm(b"hello", TdxQuoteHashAlgorithm::Sha512).len(),
+            64
+        );
+        assert_eq!(
+            hash_with_algorithm(b"hello", TdxQuoteHashAlgorithm::Raw),
+            b"hello"
+        );
+    }
+
     #[test]
     fn test_get_tee_endpoint() {
         assert_eq!(
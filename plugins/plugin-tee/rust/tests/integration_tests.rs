@@ -1,5 +1,6 @@
 use elizaos_plugin_tee::{
-    bytes_to_hex, calculate_sha256, get_tee_endpoint, hex_to_bytes, TeeMode, TeeVendor,
+    bytes_to_hex, calculate_sha256, calculate_sha384, calculate_sha512, get_tee_endpoint,
+    hash_with_algorithm, hex_to_bytes, TdxQuoteHashAlgorithm, TeeMode, TeeVendor,
 };
 
 #[test]
@@ -37,6 +38,38 @@ fn test_calculate_sha256() {
     assert_eq!(result.len(), 32);
 }
 
+#[test]
+fn test_calculate_sha384() {
+    let result = calculate_sha384(b"hello");
+    assert_eq!(result.len(), 48);
+}
+
+#[test]
+fn test_calculate_sha512() {
+    let result = calculate_sha512(b"hello");
+    assert_eq!(result.len(), 64);
+}
+
+#[test]
+fn test_hash_with_algorithm() {
+    assert_eq!(
+        hash_with_algorithm(b"hello", TdxQuoteHashAlgorithm::Sha256).len(),
+        32
+    );
+    assert_eq!(
+        hash_with_algorithm(b"hello", TdxQuoteHashAlgorithm::Sha384).len(),
+        48
+    );
+    assert_eq!(
+        hash_with_algorithm(b"hello", TdxQuoteHashAlgorithm::Sha512).len(),
+        64
+    );
+    assert_eq!(
+        hash_with_algorithm(b"hello", TdxQuoteHashAlgorithm::Raw),
+        b"hello"
+    );
+}
+
 #[test]
 fn test_get_tee_endpoint() {
     assert_eq!(